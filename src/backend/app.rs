@@ -14,10 +14,13 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::backend::app_manager::AppManager;
-use crate::backend::progress_io::{apply_app_export, collect_app_export};
+use crate::backend::progress_io::{apply_app_export, collect_app_export, collect_before_image};
 use crate::dev_println;
 use crate::steam_client::steamworks_types::AppId_t;
-use crate::utils::ipc_types::{SamError, SteamCommand, SteamResponse, read_message, write_message};
+use crate::utils::ipc_types::{
+    Process, SamError, Snapshotted, SteamCommand, SteamResponse, Stored, answer_handshake,
+    read_message, write_message,
+};
 use interprocess::unnamed_pipe::{Recver, Sender};
 use serde::Serialize;
 
@@ -27,7 +30,8 @@ fn send_response<T: Serialize>(tx: &mut Sender, resp: &SteamResponse<T>) {
 
 /// Guard `app_id_param == expected`, then run `f` and send its `Result` as a
 /// `SteamResponse`. Centralizes the four-step pattern (check id, call, wrap,
-/// send) the per-command arms used to repeat.
+/// send) the per-command arms used to repeat, and stamps errors with the app
+/// and this process on their way out.
 fn dispatch<T: Serialize>(
     tx: &mut Sender,
     app_id_param: u32,
@@ -36,17 +40,37 @@ fn dispatch<T: Serialize>(
 ) {
     if app_id_param != expected {
         dev_println!("APPSRV", "App ID mismatch: {app_id_param} != {expected}");
-        send_response::<()>(tx, &SteamResponse::Error(SamError::AppMismatchError));
+        send_response::<()>(
+            tx,
+            &SteamResponse::Error(failed(SamError::AppMismatchError, expected)),
+        );
         return;
     }
-    let result = f();
+    let result = f().map_err(|e| failed(e, expected));
     if let Err(e) = &result {
         dev_println!("APPSRV", "Command failed: {e}");
     }
     send_response(tx, &SteamResponse::<T>::from(result));
 }
 
+fn failed(error: SamError, app_id: u32) -> SamError {
+    error.app(app_id).in_process(Process::AppServer)
+}
+
+/// Taken whatever the write's outcome, so a grant never rides on the answer to
+/// some later write.
+fn stored(am: &mut AppManager, result: Result<bool, SamError>) -> Result<Stored<bool>, SamError> {
+    let granted = am.take_granted();
+    result.map(|result| Stored { result, granted })
+}
+
 pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> u8 {
+    // Before connecting: a stale binary should not get as far as Steam.
+    if let Err(e) = answer_handshake(parent_tx, parent_rx) {
+        eprintln!("[APP SERVER] Handshake with the orchestrator failed: {e}");
+        return 1;
+    }
+
     let mut app_manager = AppManager::new_connected(app_id);
 
     #[cfg(debug_assertions)]
@@ -74,7 +98,7 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> u
                 let resp: SteamResponse<bool> = if app_manager.is_ok() {
                     SteamResponse::Success(true)
                 } else {
-                    SteamResponse::Error(SamError::SteamConnectionFailed)
+                    SteamResponse::Error(failed(SamError::SteamConnectionFailed, app_id))
                 };
                 send_response(parent_tx, &resp);
                 continue;
@@ -85,7 +109,7 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> u
         if app_manager.as_ref().is_err() {
             send_response::<()>(
                 parent_tx,
-                &SteamResponse::Error(SamError::SteamConnectionFailed),
+                &SteamResponse::Error(failed(SamError::SteamConnectionFailed, app_id)),
             );
             continue;
         }
@@ -101,18 +125,26 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> u
             }
             SteamCommand::SetAchievement(id, unlocked, ach_id, store) => {
                 dispatch(parent_tx, id, app_id, || {
-                    am.set_achievement(&ach_id, unlocked, store)
+                    let result = am.set_achievement(&ach_id, unlocked, store);
+                    stored(am, result.map_err(|e| e.item(&ach_id)))
                 })
             }
-            SteamCommand::SetIntStat(id, stat_id, value) => {
-                dispatch(parent_tx, id, app_id, || am.set_stat_i32(&stat_id, value))
-            }
-            SteamCommand::SetFloatStat(id, stat_id, value) => {
-                dispatch(parent_tx, id, app_id, || am.set_stat_f32(&stat_id, value))
+            SteamCommand::SetIntStat(id, stat_id, value, store) => {
+                dispatch(parent_tx, id, app_id, || {
+                    let result = am.set_stat_i32(&stat_id, value, store);
+                    stored(am, result.map_err(|e| e.item(&stat_id)))
+                })
             }
-            SteamCommand::StoreStatsAndAchievements(id) => {
-                dispatch(parent_tx, id, app_id, || am.store_stats_and_achievements())
+            SteamCommand::SetFloatStat(id, stat_id, value, store) => {
+                dispatch(parent_tx, id, app_id, || {
+                    let result = am.set_stat_f32(&stat_id, value, store);
+                    stored(am, result.map_err(|e| e.item(&stat_id)))
+                })
             }
+            SteamCommand::StoreStatsAndAchievements(id) => dispatch(parent_tx, id, app_id, || {
+                let result = am.store_stats_and_achievements();
+                stored(am, result)
+            }),
             SteamCommand::ResetStats(id, achievements_too) => {
                 dispatch(parent_tx, id, app_id, || {
                     am.reset_all_stats(achievements_too)
@@ -127,6 +159,45 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> u
             SteamCommand::ImportAppProgress(id, payload) => dispatch(parent_tx, id, app_id, || {
                 Ok::<_, SamError>(apply_app_export(am, payload))
             }),
+            SteamCommand::SnapshotThen(id, command) => {
+                // Read first: once the command has run, what it replaced is gone.
+                let before = match collect_before_image(am, app_id, &command) {
+                    Ok(before) => Some(before),
+                    Err(e) => {
+                        eprintln!(
+                            "[APP SERVER] Could not read app {app_id} before changing it: {e}"
+                        );
+                        None
+                    }
+                };
+                match *command {
+                    SteamCommand::UnlockAllAchievements(_) => {
+                        dispatch(parent_tx, id, app_id, || {
+                            am.unlock_all_achievements()
+                                .map(|result| Snapshotted { result, before })
+                        })
+                    }
+                    SteamCommand::ResetStats(_, achievements_too) => {
+                        dispatch(parent_tx, id, app_id, || {
+                            am.reset_all_stats(achievements_too)
+                                .map(|result| Snapshotted { result, before })
+                        })
+                    }
+                    SteamCommand::ImportAppProgress(_, payload) => {
+                        dispatch(parent_tx, id, app_id, || {
+                            let result = apply_app_export(am, payload);
+                            Ok::<_, SamError>(Snapshotted { result, before })
+                        })
+                    }
+                    other => {
+                        dev_println!("APPSRV", "Nothing to snapshot before {other:?}");
+                        send_response::<()>(
+                            parent_tx,
+                            &SteamResponse::Error(SamError::UnknownError),
+                        );
+                    }
+                }
+            }
             SteamCommand::GetFriendUnlockTimes(id, friend) => {
                 dispatch(parent_tx, id, app_id, || {
                     am.fetch_friend_unlock_times(&friend)
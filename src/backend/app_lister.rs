@@ -40,7 +40,7 @@ pub struct AppLister<'a> {
     steam_apps_001: &'a SteamApps001,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AppModel {
     pub app_id: AppId_t,
     pub app_name: String,
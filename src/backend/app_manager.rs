@@ -27,8 +27,9 @@ use crate::steam_client::steamworks_types::{
     AppId_t, CSteamID, EResult, GlobalAchievementPercentagesReady_t, UserStatsReceived_t,
 };
 use crate::steam_client::wrapper_types::SteamCallbackId;
-use crate::utils::ipc_types::SamError;
+use crate::utils::ipc_types::{GrantedAchievement, SamError};
 use crate::utils::steam_locator::SteamLocator;
+use std::collections::HashSet;
 use std::env;
 use std::time::UNIX_EPOCH;
 
@@ -40,6 +41,13 @@ pub struct AppManager {
     user_stats_received: bool,
     achievement_definitions: Vec<AchievementDefinition>,
     stat_definitions: Vec<StatDefinition>,
+    /// What was achieved before the first write since the last store, so that
+    /// store can tell what Steam unlocked by itself. `None` when unread.
+    settled: Option<HashSet<String>>,
+    /// Unlocks asked for since then: SamRewritten's doing, not Steam's.
+    asked: HashSet<String>,
+    /// What stores found Steam had unlocked by itself, until taken.
+    granted: Vec<GrantedAchievement>,
 }
 
 pub struct StatState<T> {
@@ -71,7 +79,7 @@ impl AppManager {
 
         #[cfg(feature = "cli")]
         let silent = false;
-        #[cfg(feature = "gui")]
+        #[cfg(not(feature = "cli"))]
         let silent = true;
 
         let connected_steam = match ConnectedSteam::new(silent) {
@@ -88,6 +96,9 @@ impl AppManager {
             user_stats_received: false,
             achievement_definitions: vec![],
             stat_definitions: vec![],
+            settled: None,
+            asked: HashSet::new(),
+            granted: Vec::new(),
         })
     }
 
@@ -130,7 +141,7 @@ impl AppManager {
                     "[APP MANAGER] RequestCurrentStats returned {result:?}; continuing with cached stats"
                 )
             }
-            Err(SamError::Timeout) => {
+            Err(e) if *e.kind() == SamError::Timeout => {
                 eprintln!(
                     "[APP MANAGER] RequestCurrentStats timed out; continuing with cached stats"
                 )
@@ -148,7 +159,7 @@ impl AppManager {
             Ok(callback_handle) => callback_handle,
             Err(e) => {
                 eprintln!("[APP MANAGER] Error requesting user stats: {}", e);
-                return Err(SamError::UnknownError);
+                return Err(SamError::UnknownError.context("Requesting user stats"));
             }
         };
 
@@ -167,7 +178,7 @@ impl AppManager {
                         "[APP MANAGER] Error checking request_user_stats api call completed: {}",
                         e
                     );
-                    return Err(SamError::UnknownError);
+                    return Err(SamError::UnknownError.context("Requesting user stats"));
                 }
             };
 
@@ -185,7 +196,7 @@ impl AppManager {
                             "[APP MANAGER] Error getting request_user_stats api call result: {}",
                             e
                         );
-                        return Err(SamError::UnknownError);
+                        return Err(SamError::UnknownError.context("Requesting user stats"));
                     }
                 };
 
@@ -203,7 +214,7 @@ impl AppManager {
         }
 
         eprintln!("[APP MANAGER] Requesting user stats timed out");
-        Err(SamError::Timeout)
+        Err(SamError::Timeout.context("Requesting user stats"))
     }
 
     /// Resolve a `friend` string — either a SteamID64 or a persona name from the
@@ -264,7 +275,7 @@ impl AppManager {
         let result = self.wait_for_user_stats(steam_id)?;
         if result != EResult::k_EResultOK {
             eprintln!("[APP MANAGER] RequestUserStats for {steam_id64} returned {result:?}");
-            return Err(SamError::ProfilePrivate);
+            return Err(SamError::ProfilePrivate.eresult(result as i32));
         }
 
         let names = user_unlock_times::read_schema_achievements(self.app_id)?;
@@ -591,6 +602,7 @@ impl AppManager {
                         name: def.name.clone(),
                         description: def.description.clone(),
                         global_achieved_percent,
+                        is_hidden: def.is_hidden,
                     });
                 }
                 Err(_) => {
@@ -664,6 +676,7 @@ impl AppManager {
                         permission: definition.base.permission,
                         min_value: definition.min_value,
                         max_value: definition.max_value,
+                        default_value: definition.default_value,
                     }));
                 }
 
@@ -698,6 +711,7 @@ impl AppManager {
                         permission: definition.base.permission,
                         min_value: definition.min_value,
                         max_value: definition.max_value,
+                        default_value: definition.default_value,
                     }));
                 }
             };
@@ -707,11 +721,12 @@ impl AppManager {
     }
 
     pub fn set_achievement(
-        &self,
+        &mut self,
         achievement_id: &str,
         unlock: bool,
         store: bool,
     ) -> Result<bool, SamError> {
+        self.before_write();
         if unlock {
             match self
                 .connected_steam
@@ -719,16 +734,13 @@ impl AppManager {
                 .set_achievement(achievement_id)
             {
                 Ok(_) => {
+                    self.asked.insert(achievement_id.to_owned());
                     if store {
-                        return self
-                            .connected_steam
-                            .user_stats
-                            .store_stats()
-                            .map_err(|_| SamError::StatStoreFailed);
+                        return self.store();
                     }
                     Ok(true)
                 }
-                Err(_) => Err(SamError::LockUnlockAchievementFailed),
+                Err(_) => Err(SamError::LockUnlockAchievementFailed.context("Unlocking")),
             }
         } else {
             match self
@@ -738,15 +750,11 @@ impl AppManager {
             {
                 Ok(_) => {
                     if store {
-                        return self
-                            .connected_steam
-                            .user_stats
-                            .store_stats()
-                            .map_err(|_| SamError::StatStoreFailed);
+                        return self.store();
                     }
                     Ok(true)
                 }
-                Err(_) => Err(SamError::LockUnlockAchievementFailed),
+                Err(_) => Err(SamError::LockUnlockAchievementFailed.context("Locking")),
             }
         }
     }
@@ -754,11 +762,72 @@ impl AppManager {
     /// `Ok(false)` is Steam accepting the call and declining to store. Nothing
     /// set before this point is committed until it returns true, so callers
     /// file history entries and report success on the answer.
-    pub fn store_stats_and_achievements(&self) -> Result<bool, SamError> {
-        self.connected_steam
+    pub fn store_stats_and_achievements(&mut self) -> Result<bool, SamError> {
+        self.store()
+    }
+
+    /// What stores found Steam had unlocked by itself since this was last
+    /// asked.
+    pub fn take_granted(&mut self) -> Vec<GrantedAchievement> {
+        std::mem::take(&mut self.granted)
+    }
+
+    /// Achieved ids and names as Steam's client has them now, writes not
+    /// stored yet included.
+    fn achieved(&mut self) -> Result<Vec<(String, String)>, SamError> {
+        let language = self.loaded_language.clone().unwrap_or_default();
+        Ok(self
+            .get_achievements(false, &language)?
+            .into_iter()
+            .filter(|a| a.is_achieved)
+            .map(|a| (a.id, a.name))
+            .collect())
+    }
+
+    /// Read before the first write rather than before the store: whether Steam
+    /// grants on the set or on the store, its unlocks land after the baseline.
+    fn before_write(&mut self) {
+        if self.settled.is_none() {
+            self.settled = self
+                .achieved()
+                .ok()
+                .map(|achieved| achieved.into_iter().map(|(id, _)| id).collect());
+        }
+    }
+
+    fn store(&mut self) -> Result<bool, SamError> {
+        let stored = self
+            .connected_steam
             .user_stats
             .store_stats()
-            .map_err(|_| SamError::StatStoreFailed)
+            .map_err(|_| SamError::StatStoreFailed.context("Storing"))?;
+        if !stored {
+            // Still waiting in Steam's client, and still to be told apart.
+            return Ok(false);
+        }
+        if let Some(settled) = self.settled.take() {
+            match self.achieved() {
+                Ok(achieved) => self.granted.extend(
+                    achieved
+                        .into_iter()
+                        .filter(|(id, _)| !settled.contains(id) && !self.asked.contains(id))
+                        .map(|(id, name)| GrantedAchievement { id, name }),
+                ),
+                Err(e) => eprintln!(
+                    "[APP MANAGER] Could not re-read app {} after storing: {e}",
+                    self.app_id
+                ),
+            }
+        }
+        self.asked.clear();
+        Ok(true)
+    }
+
+    /// For the writes that answer no `Stored`: what they commit is not told
+    /// apart, and the next write reads a fresh baseline.
+    fn forget_writes(&mut self) {
+        self.settled = None;
+        self.asked.clear();
     }
 
     pub fn read_int_stat_state(&self, id: &str) -> StatState<i32> {
@@ -836,46 +905,56 @@ impl AppManager {
             .connected_steam
             .user_stats
             .store_stats()
-            .map_err(|_| SamError::StatStoreFailed)?;
+            .map_err(|_| SamError::StatStoreFailed.context("Storing"))?;
+        self.forget_writes();
 
         if has_failures {
-            Err(SamError::LockUnlockAchievementFailed)
+            Err(SamError::LockUnlockAchievementFailed.context("Unlocking all"))
         } else {
             Ok(stored)
         }
     }
 
-    pub fn set_stat_i32(&self, stat_name: &str, stat_value: i32) -> Result<bool, SamError> {
+    /// With `store: false` the value waits in Steam's client for a later
+    /// `store_stats_and_achievements`, as with `set_achievement`.
+    pub fn set_stat_i32(
+        &mut self,
+        stat_name: &str,
+        stat_value: i32,
+        store: bool,
+    ) -> Result<bool, SamError> {
+        self.before_write();
         match self
             .connected_steam
             .user_stats
             .set_stat_i32(stat_name, stat_value)
         {
-            Ok(_) => self
-                .connected_steam
-                .user_stats
-                .store_stats()
-                .map_err(|_| SamError::StatStoreFailed),
-            Err(_) => Err(SamError::UnknownError),
+            Ok(_) if !store => Ok(true),
+            Ok(_) => self.store(),
+            Err(_) => Err(SamError::UnknownError.context("Setting the stat")),
         }
     }
 
-    pub fn set_stat_f32(&self, stat_name: &str, stat_value: f32) -> Result<bool, SamError> {
+    pub fn set_stat_f32(
+        &mut self,
+        stat_name: &str,
+        stat_value: f32,
+        store: bool,
+    ) -> Result<bool, SamError> {
+        self.before_write();
         match self
             .connected_steam
             .user_stats
             .set_stat_float(stat_name, stat_value)
         {
-            Ok(_) => self
-                .connected_steam
-                .user_stats
-                .store_stats()
-                .map_err(|_| SamError::StatStoreFailed),
-            Err(_) => Err(SamError::UnknownError),
+            Ok(_) if !store => Ok(true),
+            Ok(_) => self.store(),
+            Err(_) => Err(SamError::UnknownError.context("Setting the stat")),
         }
     }
 
-    pub fn reset_all_stats(&self, achievements_too: bool) -> Result<bool, SamError> {
+    pub fn reset_all_stats(&mut self, achievements_too: bool) -> Result<bool, SamError> {
+        self.forget_writes();
         match self
             .connected_steam
             .user_stats
@@ -885,8 +964,8 @@ impl AppManager {
                 .connected_steam
                 .user_stats
                 .store_stats()
-                .map_err(|_| SamError::StatStoreFailed),
-            Err(_) => Err(SamError::UnknownError),
+                .map_err(|_| SamError::StatStoreFailed.context("Storing")),
+            Err(_) => Err(SamError::UnknownError.context("Resetting stats")),
         }
     }
 
@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `--daemon`: one orchestrator shared by every frontend the user starts, on a
+//! per-user Unix socket speaking the same framed `SteamCommand` protocol as the
+//! pipes. Frontends attach when it answers and spawn a private orchestrator
+//! when it does not.
+//!
+//! Each connection is a session. Its `Shutdown` ends the session, not the
+//! daemon, and whatever apps it left launched are stopped when it hangs up, so
+//! a crashed CLI call cannot keep a game "running" forever.
+//!
+//! It follows the schedule too, so that queued operations run with no window
+//! open.
+
+use crate::backend::orchestrator::{Cancels, Holds, Job, Orchestrator, Out, worker_pool};
+use crate::dev_println;
+use crate::utils::ipc_types::{
+    OrchestratorEvent, SteamCommand, SteamResponse, answer_handshake, offer_handshake, read_tagged,
+    write_tagged,
+};
+use crate::utils::steam_ns::enter_flatpak_steam_ns_if_needed;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Overrides where the socket lives, for both the daemon and its clients.
+const SOCKET_ENV: &str = "SAM_ORCHESTRATOR_SOCKET";
+
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(SOCKET_ENV).filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+    let runtime = std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("samrewritten"))
+        // /tmp is shared, so the folder is per user and checked to be ours.
+        .unwrap_or_else(|| std::env::temp_dir().join(format!("samrewritten-{}", uid())));
+    runtime.join("orchestrator.sock")
+}
+
+fn uid() -> u32 {
+    fs::metadata("/proc/self").map_or(u32::MAX, |m| m.uid())
+}
+
+/// Whoever owns the socket's folder can answer in the daemon's place.
+fn is_ours(dir: &Path) -> bool {
+    fs::metadata(dir).is_ok_and(|m| m.uid() == uid() && m.mode() & 0o077 == 0)
+}
+
+/// A running daemon's socket, or `None` to spawn a private orchestrator. That
+/// includes a daemon left running from another build, which is told apart here
+/// rather than on the first command.
+pub fn connect() -> Option<UnixStream> {
+    let path = socket_path();
+    if !path.parent().is_some_and(is_ours) {
+        return None;
+    }
+    let mut stream = UnixStream::connect(&path).ok()?;
+    let mut reader = stream.try_clone().ok()?;
+    if let Err(e) = offer_handshake(&mut stream, &mut reader) {
+        eprintln!(
+            "Not using the daemon at {}: {e}. Starting a private backend instead",
+            path.display()
+        );
+        return None;
+    }
+    Some(stream)
+}
+
+/// A client that hangs up mid-response must not take the daemon down with it,
+/// and the orchestrator's handlers treat a failed write as fatal.
+struct Output {
+    stream: UnixStream,
+    hung_up: bool,
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.hung_up && self.stream.write_all(buf).is_err() {
+            self.hung_up = true;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Client {
+    /// Shared with its commands in flight, each writing whole frames.
+    out: Arc<Mutex<Output>>,
+    /// Launches this session still holds.
+    held: Arc<Holds>,
+    /// Its bulk commands in flight. Request ids are per session.
+    cancels: Arc<Cancels>,
+    /// The request it follows events under, once it asked to.
+    subscription: Option<u32>,
+    /// It is a window, which can open an app's page when asked.
+    opens_apps: bool,
+}
+
+impl Client {
+    fn send<T: Serialize>(&self, request: u32, msg: &T) {
+        let _ = write_tagged(&mut *self.out.lock().unwrap(), request, msg);
+    }
+}
+
+enum Event {
+    Connected(usize, UnixStream, Arc<Cancels>),
+    /// Client, request id, command.
+    Command(usize, u32, SteamCommand),
+    /// Client, request id, and whether the request it named was still running.
+    Cancelled(usize, u32, bool),
+    /// A command of the client finished, with what it holds.
+    Done(usize, Arc<Holds>),
+    Closed(usize),
+    /// For every client following events.
+    Pushed(OrchestratorEvent),
+}
+
+pub fn daemon() -> u8 {
+    let path = socket_path();
+    let Some(dir) = path.parent() else {
+        eprintln!("[DAEMON] Invalid socket path {}", path.display());
+        return 1;
+    };
+    // Only the folder it would pick itself is created, closed to others. One
+    // named by `SAM_ORCHESTRATOR_SOCKET` is the user's, and left as it is.
+    if std::env::var_os(SOCKET_ENV).is_none_or(|p| p.is_empty())
+        && let Err(e) = fs::DirBuilder::new().mode(0o700).create(dir)
+        && e.kind() != io::ErrorKind::AlreadyExists
+    {
+        eprintln!("[DAEMON] Could not create {}: {e}", dir.display());
+        return 1;
+    }
+    if !is_ours(dir) {
+        eprintln!(
+            "[DAEMON] {} must be a folder of this user's, closed to everyone else; not listening there",
+            dir.display()
+        );
+        return 1;
+    }
+    if UnixStream::connect(&path).is_ok() {
+        eprintln!("[DAEMON] Already running on {}", path.display());
+        return 1;
+    }
+    // Left behind by a daemon that was killed.
+    let _ = fs::remove_file(&path);
+    let listener = match UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[DAEMON] Could not listen on {}: {e}", path.display());
+            return 1;
+        }
+    };
+    eprintln!("[DAEMON] Listening on {}", path.display());
+
+    // Bound first, on the host's side of any namespace, where clients look.
+    if let Some(code) = enter_flatpak_steam_ns_if_needed() {
+        return code;
+    }
+
+    let (events, queue) = channel();
+    let pushed = events.clone();
+    let accepted = events.clone();
+    thread::spawn(move || accept(listener, accepted));
+
+    let orchestrator = Orchestrator::start();
+    orchestrator.follow_schedule();
+    // One follower for the whole daemon, which the loop hands on to every
+    // client that asked.
+    let bus = orchestrator.subscribe();
+    thread::spawn(move || {
+        for event in bus {
+            if pushed.send(Event::Pushed(event)).is_err() {
+                return;
+            }
+        }
+    });
+    // The loop only keeps track of sessions. Commands go to the workers, as
+    // with the pipe, so that one client's long fan-out does not hold up the
+    // others.
+    let jobs = worker_pool(|worker| {
+        thread::spawn(worker);
+    });
+    let mut clients: HashMap<usize, Client> = HashMap::new();
+    for event in queue {
+        match event {
+            Event::Connected(id, stream, cancels) => {
+                dev_println!("DAEMON", "Client {id} connected");
+                clients.insert(
+                    id,
+                    Client {
+                        out: Arc::new(Mutex::new(Output {
+                            stream,
+                            hung_up: false,
+                        })),
+                        held: Arc::default(),
+                        cancels,
+                        subscription: None,
+                        opens_apps: false,
+                    },
+                );
+            }
+            Event::Command(id, request, SteamCommand::Shutdown) => {
+                if let Some(client) = clients.remove(&id) {
+                    client.send(request, &SteamResponse::Success(true));
+                    release(&orchestrator, &jobs, &events, id, client);
+                }
+            }
+            Event::Command(id, request, SteamCommand::SubscribeEvents(opens_apps)) => {
+                if let Some(client) = clients.get_mut(&id) {
+                    client.subscription = Some(request);
+                    client.opens_apps = opens_apps;
+                    for event in orchestrator.current() {
+                        client.send(request, &event);
+                    }
+                }
+            }
+            // To the window that came last, which the asking one is not.
+            Event::Command(id, request, SteamCommand::OpenApp(app_id)) => {
+                let window = clients
+                    .iter()
+                    .filter(|(other, client)| **other != id && client.opens_apps)
+                    .max_by_key(|(other, _)| **other)
+                    .and_then(|(_, client)| Some((client, client.subscription?)));
+                if let Some((window, subscription)) = window {
+                    window.send(subscription, &OrchestratorEvent::OpenApp { app_id });
+                }
+                if let Some(client) = clients.get(&id) {
+                    client.send(request, &SteamResponse::Success(window.is_some()));
+                }
+            }
+            Event::Command(id, request, command) => {
+                let Some(client) = clients.get(&id) else {
+                    continue;
+                };
+                dev_println!("DAEMON", "Client {id}: {command:?}");
+                let orchestrator = orchestrator.clone();
+                let out: Out = client.out.clone();
+                let (held, cancels) = (client.held.clone(), client.cancels.clone());
+                let done = events.clone();
+                let _ = jobs.send(Box::new(move || {
+                    orchestrator.process(request, command, &cancels, &out, &held);
+                    let _ = done.send(Event::Done(id, held));
+                }));
+            }
+            Event::Cancelled(id, request, running) => {
+                if let Some(client) = clients.get(&id) {
+                    client.send(request, &SteamResponse::Success(running));
+                }
+            }
+            Event::Done(id, held) => {
+                let running = orchestrator.refcounts();
+                for client in clients.values() {
+                    client.held.retain_running(&running);
+                }
+                // Launched after its session went.
+                if !clients.contains_key(&id) {
+                    stop_held(&orchestrator, &jobs, &events, id, held);
+                }
+            }
+            Event::Closed(id) => {
+                if let Some(client) = clients.remove(&id) {
+                    release(&orchestrator, &jobs, &events, id, client);
+                }
+            }
+            Event::Pushed(event) => {
+                for client in clients.values() {
+                    if let Some(request) = client.subscription {
+                        client.send(request, &event);
+                    }
+                }
+            }
+        }
+    }
+    0
+}
+
+/// Hang up on a departing session, and stop what it still held.
+fn release(
+    orchestrator: &Arc<Orchestrator>,
+    jobs: &Sender<Job<'static>>,
+    events: &Sender<Event>,
+    id: usize,
+    client: Client,
+) {
+    let _ = client.out.lock().unwrap().stream.shutdown(Shutdown::Both);
+    stop_held(orchestrator, jobs, events, id, client.held);
+}
+
+/// Stop what a session held, as if it had asked itself. On a worker, as a stop
+/// waits for the app server's exchange in progress.
+fn stop_held(
+    orchestrator: &Arc<Orchestrator>,
+    jobs: &Sender<Job<'static>>,
+    events: &Sender<Event>,
+    id: usize,
+    held: Arc<Holds>,
+) {
+    let holds = held.take();
+    if holds.is_empty() {
+        return;
+    }
+    let (orchestrator, done) = (orchestrator.clone(), events.clone());
+    let _ = jobs.send(Box::new(move || {
+        let sink: Out = Arc::new(Mutex::new(io::sink()));
+        let cancels = Arc::new(Cancels::default());
+        // Stops charge nothing, as nothing holds them anymore.
+        let nobody = Holds::default();
+        for (app_id, count) in holds {
+            dev_println!("DAEMON", "Releasing {count} hold(s) on app {app_id}");
+            for _ in 0..count {
+                let command = SteamCommand::StopApp(app_id);
+                orchestrator.process(0, command, &cancels, &sink, &nobody);
+            }
+        }
+        let _ = done.send(Event::Done(id, held));
+    }));
+}
+
+fn accept(listener: UnixListener, events: Sender<Event>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("[DAEMON] Failed to accept a client: {e}");
+                continue;
+            }
+        };
+        let Ok(mut reader) = stream.try_clone() else {
+            continue;
+        };
+        let events = events.clone();
+        thread::spawn(move || {
+            // Here rather than in the loop: a client slow to say hello must not
+            // hold up the others.
+            if let Err(e) = answer_handshake(&mut stream, &mut reader) {
+                eprintln!("[DAEMON] Turned away a client: {e}");
+                return;
+            }
+            let cancels = Arc::new(Cancels::default());
+            if events
+                .send(Event::Connected(id, stream, cancels.clone()))
+                .is_err()
+            {
+                return;
+            }
+            // A client may have several requests in flight, each answered under
+            // its own id. A cancel takes effect here, before the loop sees it,
+            // so that it lands even on a request still on its way to a thread.
+            while let Ok((request, command)) = read_tagged::<SteamCommand>(&mut reader) {
+                let event = match command {
+                    SteamCommand::Cancel(target) => {
+                        Event::Cancelled(id, request, cancels.cancel(target))
+                    }
+                    command => {
+                        cancels.track(request, &command);
+                        Event::Command(id, request, command)
+                    }
+                };
+                if events.send(event).is_err() {
+                    return;
+                }
+            }
+            let _ = events.send(Event::Closed(id));
+        });
+    }
+}
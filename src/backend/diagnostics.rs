@@ -0,0 +1,445 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Everything the locator, the namespace join and the caches decide silently,
+//! gathered for `doctor` and the GUI's diagnostics page. `collect` only looks
+//! at disk and `/proc`; `handshake` is the one part that asks Steam, through
+//! the orchestrator, the same way every other request does.
+
+use crate::backend::local_config::most_recent_login;
+use crate::backend::orchestrator_client::{GetCurrentUser, Request};
+use crate::backend::user_unlock_times::{account_id, stats_dir};
+use crate::utils::app_paths::get_app_cache_dir;
+use crate::utils::steam_locator::SteamLocator;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Variables that take a decision out of the locator's hands.
+const OVERRIDES: [&str; 4] = [
+    "SAM_STEAMCLIENT_PATH",
+    "SAM_STEAM_INSTALL_ROOT",
+    "SAM_CACHE_DIR",
+    "SNAP_REAL_HOME",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub platform: &'static str,
+    pub overrides: BTreeMap<&'static str, String>,
+    /// In the locator's order of preference; the first with a `steamclient`
+    /// is the one loaded.
+    pub installs: Vec<Install>,
+    /// The library the orchestrator would load, after overrides.
+    pub steamclient: Option<PathBuf>,
+    pub namespace: Option<Namespace>,
+    pub snap: Option<Snap>,
+    pub account: Option<Account>,
+    pub caches: Vec<Cache>,
+    pub handshake: Option<Handshake>,
+    /// What looks wrong, most likely cause first. Empty when all is well.
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Install {
+    pub root: PathBuf,
+    /// `flatpak`, `snap` or `native`.
+    pub kind: &'static str,
+    pub has_steamclient: bool,
+    /// `None` where running installs cannot be told apart.
+    pub running: Option<bool>,
+    pub selected: bool,
+}
+
+/// Flatpak Steam runs in its own PID namespace, which the orchestrator has to
+/// join before loading the library.
+#[derive(Debug, Clone, Serialize)]
+pub struct Namespace {
+    pub required: bool,
+    /// The Flatpak `steam` process the orchestrator would join through.
+    pub flatpak_steam_pid: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Snap {
+    /// The Steam folder granted through the portal, if the grant still holds.
+    pub saved_root: Option<PathBuf>,
+    pub pinned_root: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Account {
+    pub steam_id64: u64,
+    pub account_id: u32,
+    /// `loginusers.vdf`, or `steam` once the handshake has answered.
+    pub source: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Cache {
+    pub name: &'static str,
+    pub path: Option<PathBuf>,
+    pub exists: bool,
+    /// Files of interest inside: schemas for `stats`, banners for `librarycache`.
+    pub files: Option<usize>,
+    pub writable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Handshake {
+    pub ok: bool,
+    pub steam_id64: Option<u64>,
+    pub error: Option<String>,
+    pub millis: u128,
+}
+
+/// Everything that can be known without talking to Steam.
+pub fn collect() -> Diagnostics {
+    let mut diagnostics = Diagnostics {
+        platform: std::env::consts::OS,
+        overrides: OVERRIDES
+            .iter()
+            .filter_map(|&name| Some((name, std::env::var(name).ok()?)))
+            .collect(),
+        installs: installs(),
+        steamclient: SteamLocator::get_steamclient_lib_path(true),
+        namespace: namespace(),
+        snap: snap(),
+        account: None,
+        caches: Vec::new(),
+        handshake: None,
+        problems: Vec::new(),
+    };
+
+    diagnostics.account = SteamLocator::get_login_users_path()
+        .and_then(|path| most_recent_login(&path).ok())
+        .map(|steam_id64| Account {
+            steam_id64,
+            account_id: account_id(steam_id64),
+            source: "loginusers.vdf",
+        });
+    diagnostics.caches = caches(diagnostics.account.as_ref().map(|a| a.account_id));
+    diagnostics.problems = diagnostics.find_problems();
+    diagnostics
+}
+
+/// Asks the orchestrator for the signed-in user, which takes a full connection:
+/// namespace join, library load and Steam's pipe.
+pub fn handshake() -> Handshake {
+    let started = Instant::now();
+    let result = GetCurrentUser.request();
+    let millis = started.elapsed().as_millis();
+    match result {
+        Ok(steam_id64) => Handshake {
+            ok: true,
+            steam_id64: Some(steam_id64),
+            error: None,
+            millis,
+        },
+        Err(e) => Handshake {
+            ok: false,
+            steam_id64: None,
+            error: Some(e.to_string()),
+            millis,
+        },
+    }
+}
+
+impl Diagnostics {
+    /// Steam's own answer replaces the on-disk guess at the account.
+    pub fn with_handshake(mut self, handshake: Handshake) -> Self {
+        if let Some(steam_id64) = handshake.steam_id64 {
+            self.account = Some(Account {
+                steam_id64,
+                account_id: account_id(steam_id64),
+                source: "steam",
+            });
+            self.caches = caches(Some(account_id(steam_id64)));
+        }
+        self.handshake = Some(handshake);
+        self.problems = self.find_problems();
+        self
+    }
+
+    fn find_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.installs.is_empty() {
+            problems.push("No Steam install was found".to_owned());
+        }
+        match &self.steamclient {
+            None => problems.push("No steamclient library was found to load".to_owned()),
+            Some(path) if !path.exists() => {
+                problems.push(format!("{} does not exist", path.display()));
+            }
+            Some(_) => {}
+        }
+
+        let selected = self.installs.iter().find(|i| i.selected);
+        let running: Vec<&Install> = self
+            .installs
+            .iter()
+            .filter(|i| i.running == Some(true))
+            .collect();
+        if let Some(selected) = selected
+            && selected.running == Some(false)
+        {
+            match running.first() {
+                Some(other) => problems.push(format!(
+                    "Steam is running from {}, but {} would be loaded",
+                    other.root.display(),
+                    selected.root.display()
+                )),
+                None => problems.push("Steam does not appear to be running".to_owned()),
+            }
+        }
+
+        if let Some(namespace) = &self.namespace
+            && namespace.required
+            && namespace.flatpak_steam_pid.is_none()
+        {
+            problems.push(
+                "Flatpak Steam is the selected install, but no Flatpak Steam process was found"
+                    .to_owned(),
+            );
+        }
+        if let Some(snap) = &self.snap
+            && snap.saved_root.is_none()
+        {
+            problems.push("No Steam folder has been granted to the snap yet".to_owned());
+        }
+
+        if self.account.is_none() {
+            problems.push("No signed-in Steam account was found".to_owned());
+        }
+        for cache in &self.caches {
+            if !cache.exists {
+                problems.push(format!("The {} cache is missing", cache.name));
+            } else if cache.writable == Some(false) {
+                problems.push(format!("The {} cache is not writable", cache.name));
+            } else if cache.files == Some(0) {
+                problems.push(format!("The {} cache is empty", cache.name));
+            }
+        }
+
+        if let Some(handshake) = &self.handshake
+            && !handshake.ok
+        {
+            problems.push(format!(
+                "The test connection failed: {}",
+                handshake.error.as_deref().unwrap_or("no answer")
+            ));
+        }
+        problems
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn installs() -> Vec<Install> {
+    use crate::utils::steam_ns::running_steam_install_roots;
+
+    let running = running_steam_install_roots();
+    // Without any root to go on (AppArmor hides other snaps' maps), a running
+    // `steam` cannot be pinned on one install.
+    let identifiable = !running.is_empty() || !crate::utils::steam_ns::any_steam_process_running();
+
+    let mut selected_found = false;
+    SteamLocator::get_local_steam_install_root_folders()
+        .into_iter()
+        .map(|root| {
+            let has_steamclient = root.join("linux64/steamclient.so").exists();
+            let selected = has_steamclient && !selected_found;
+            selected_found |= selected;
+            let canonical = std::fs::canonicalize(&root).unwrap_or_else(|_| root.clone());
+            Install {
+                kind: install_kind(&root),
+                has_steamclient,
+                running: identifiable.then(|| running.contains(&canonical)),
+                selected,
+                root,
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn installs() -> Vec<Install> {
+    SteamLocator::get_steamclient_lib_path(true)
+        .and_then(|lib| {
+            let root = lib.parent()?.to_path_buf();
+            Some(vec![Install {
+                kind: "native",
+                has_steamclient: lib.exists(),
+                running: None,
+                selected: true,
+                root,
+            }])
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn install_kind(root: &Path) -> &'static str {
+    let path = root.to_string_lossy();
+    if path.contains("com.valvesoftware.Steam") {
+        "flatpak"
+    } else if path.contains("snap/steam") {
+        "snap"
+    } else {
+        "native"
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn namespace() -> Option<Namespace> {
+    let lib = SteamLocator::get_steamclient_lib_path(true)?;
+    Some(Namespace {
+        required: lib.to_string_lossy().contains("com.valvesoftware.Steam"),
+        flatpak_steam_pid: crate::utils::steam_ns::detect_flatpak_steam(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn namespace() -> Option<Namespace> {
+    None
+}
+
+/// The snap only ships the GUI.
+#[cfg(all(unix, feature = "gui"))]
+fn snap() -> Option<Snap> {
+    use crate::utils::snap::{is_snap, load_saved_root};
+
+    is_snap().then(|| Snap {
+        saved_root: load_saved_root(),
+        pinned_root: std::env::var("SAM_STEAM_INSTALL_ROOT").ok(),
+    })
+}
+
+#[cfg(not(all(unix, feature = "gui")))]
+fn snap() -> Option<Snap> {
+    None
+}
+
+/// `stats` and `localconfig` are Steam's, read for counts, unlock times and
+/// playtime; `app_cache` is ours and has to take writes.
+fn caches(account: Option<u32>) -> Vec<Cache> {
+    let stats = stats_dir().ok();
+    let schemas = stats
+        .as_deref()
+        .map(|dir| count_files(dir, "UserGameStatsSchema_"));
+    let librarycache = SteamLocator::get_local_app_banner_file_prefix().map(PathBuf::from);
+    let banners = librarycache.as_deref().map(|dir| count_files(dir, ""));
+    let own = get_app_cache_dir();
+
+    let mut caches = vec![
+        Cache {
+            name: "stats",
+            exists: stats.as_deref().is_some_and(Path::is_dir),
+            path: stats,
+            files: schemas,
+            writable: None,
+        },
+        Cache {
+            name: "librarycache",
+            exists: librarycache.as_deref().is_some_and(Path::is_dir),
+            path: librarycache,
+            files: banners,
+            writable: None,
+        },
+        Cache {
+            name: "app_cache",
+            exists: own.is_dir(),
+            writable: Some(is_writable(&own)),
+            path: Some(own),
+            files: None,
+        },
+    ];
+    if let Some(account) = account {
+        let localconfig = SteamLocator::get_local_config_path(account);
+        caches.push(Cache {
+            name: "localconfig",
+            exists: localconfig.is_some(),
+            path: localconfig,
+            files: None,
+            writable: None,
+        });
+    }
+    caches
+}
+
+fn count_files(dir: &Path, prefix: &str) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with(prefix))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(".doctor-probe");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(root: &str, running: bool, selected: bool) -> Install {
+        Install {
+            root: PathBuf::from(root),
+            kind: "native",
+            has_steamclient: true,
+            running: Some(running),
+            selected,
+        }
+    }
+
+    #[test]
+    fn a_running_install_other_than_the_loaded_one_is_named() {
+        let diagnostics = Diagnostics {
+            platform: "linux",
+            overrides: BTreeMap::new(),
+            installs: vec![install("/a", false, true), install("/b", true, false)],
+            steamclient: None,
+            namespace: None,
+            snap: None,
+            account: None,
+            caches: Vec::new(),
+            handshake: None,
+            problems: Vec::new(),
+        }
+        .with_handshake(Handshake {
+            ok: true,
+            steam_id64: Some(76561197960287930),
+            error: None,
+            millis: 3,
+        });
+
+        assert_eq!(diagnostics.account.as_ref().unwrap().account_id, 22202);
+        assert!(
+            diagnostics
+                .problems
+                .iter()
+                .any(|p| p == "Steam is running from /b, but /a would be loaded")
+        );
+    }
+}
@@ -0,0 +1,550 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Library and achievement queries shared by both frontends, so the CLI's
+//! filter flags and the GUI sidebar agree on what "junk" or "fully unlocked"
+//! means. App filters and sort keys are named after their `filter-*` and
+//! `app-sort` GSettings keys. The achievement half, and the library summary,
+//! are only built for the CLI, which is all that uses them.
+
+use crate::backend::app_lister::{AppModel, AppModelType};
+#[cfg(feature = "cli")]
+use crate::backend::stat_definitions::AchievementInfo;
+use serde::Deserialize;
+#[cfg(feature = "cli")]
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::str::FromStr;
+#[cfg(feature = "cli")]
+use std::time::SystemTime;
+
+/// Fraction unlocked, or `-1.0` when the counts are unknown or there is nothing
+/// to unlock — so those sort after every real percentage.
+pub fn completion(total: u32, unlocked: u32, loaded: bool) -> f64 {
+    if loaded && total > 0 {
+        f64::from(unlocked) / f64::from(total)
+    } else {
+        -1.0
+    }
+}
+
+/// Achievements left to unlock, or `u32::MAX` when the counts are unknown or the
+/// app is done — so the closest-to-done apps sort first.
+pub fn remaining(total: u32, unlocked: u32, loaded: bool) -> u32 {
+    let remaining = total.saturating_sub(unlocked);
+    if loaded && remaining > 0 {
+        remaining
+    } else {
+        u32::MAX
+    }
+}
+
+pub fn is_fully_unlocked(total: u32, unlocked: u32) -> bool {
+    total > 0 && unlocked >= total
+}
+
+/// The sidebar's library filters. Each field is the GSettings key of the same
+/// name, so `junk` hides junk (the "Show junk" box shows its negation).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AppFilter {
+    pub junk: bool,
+    pub hide_fully_unlocked: bool,
+    pub hide_never_launched: bool,
+    pub hide_no_unlocked: bool,
+    pub hide_without_achievements: bool,
+}
+
+impl AppFilter {
+    pub fn depends_on_counts(&self) -> bool {
+        self.hide_fully_unlocked || self.hide_no_unlocked || self.hide_without_achievements
+    }
+
+    /// The half that needs no achievement counts. `last_played` is `0` for an
+    /// app that was never launched.
+    pub fn hides_by_kind(&self, is_junk: bool, last_played: u64) -> bool {
+        (self.junk && is_junk) || (self.hide_never_launched && last_played == 0)
+    }
+
+    /// Only meaningful once counts are loaded: an app whose counts are merely
+    /// missing would otherwise read as having no achievements.
+    pub fn hides_by_counts(&self, total: u32, unlocked: u32) -> bool {
+        (self.hide_without_achievements && total == 0)
+            || (self.hide_fully_unlocked && is_fully_unlocked(total, unlocked))
+            || (self.hide_no_unlocked && unlocked == 0)
+    }
+
+    /// An app the orchestrator could not count reads as having none, the same
+    /// as a finished sweep leaves it in the GUI.
+    pub fn keeps(&self, app: &AppModel) -> bool {
+        let is_junk = matches!(app.app_type, AppModelType::Junk);
+        if self.hides_by_kind(is_junk, app.last_played.unwrap_or(0)) {
+            return false;
+        }
+        !self.hides_by_counts(
+            app.achievement_count.unwrap_or(0),
+            app.unlocked_achievement_count.unwrap_or(0),
+        )
+    }
+}
+
+/// `app-sort` values. Everything but `AppId` breaks ties alphabetically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum AppSort {
+    #[default]
+    AppId,
+    Alphabetical,
+    LastPlayed,
+    Playtime,
+    Completion,
+    Remaining,
+}
+
+impl FromStr for AppSort {
+    type Err = String;
+
+    /// Accepts the GSettings spelling (`last_played`) and the CLI one (`last-played`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "app_id" => Ok(AppSort::AppId),
+            "alphabetical" => Ok(AppSort::Alphabetical),
+            "last_played" => Ok(AppSort::LastPlayed),
+            "playtime" => Ok(AppSort::Playtime),
+            "completion" => Ok(AppSort::Completion),
+            "remaining" => Ok(AppSort::Remaining),
+            _ => Err(format!(
+                "'{s}' is not a sort order; expected one of app_id, alphabetical, \
+                 last_played, playtime, completion, remaining"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for AppSort {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl AppSort {
+    pub fn needs_counts(&self) -> bool {
+        matches!(self, AppSort::Completion | AppSort::Remaining)
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn needs_playtime(&self) -> bool {
+        matches!(self, AppSort::LastPlayed | AppSort::Playtime)
+    }
+
+    pub fn compare(&self, a: &AppModel, b: &AppModel) -> Ordering {
+        let alphabetical = || a.app_name.to_lowercase().cmp(&b.app_name.to_lowercase());
+        let counts = |app: &AppModel| {
+            (
+                app.achievement_count.unwrap_or(0),
+                app.unlocked_achievement_count.unwrap_or(0),
+                app.achievement_count.is_some(),
+            )
+        };
+        match self {
+            AppSort::AppId => a.app_id.cmp(&b.app_id),
+            AppSort::Alphabetical => alphabetical(),
+            AppSort::LastPlayed => b
+                .last_played
+                .unwrap_or(0)
+                .cmp(&a.last_played.unwrap_or(0))
+                .then_with(alphabetical),
+            AppSort::Playtime => b
+                .playtime_minutes
+                .unwrap_or(0)
+                .cmp(&a.playtime_minutes.unwrap_or(0))
+                .then_with(alphabetical),
+            AppSort::Completion => {
+                let (a_total, a_unlocked, a_loaded) = counts(a);
+                let (b_total, b_unlocked, b_loaded) = counts(b);
+                completion(b_total, b_unlocked, b_loaded)
+                    .partial_cmp(&completion(a_total, a_unlocked, a_loaded))
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(alphabetical)
+            }
+            AppSort::Remaining => {
+                let (a_total, a_unlocked, a_loaded) = counts(a);
+                let (b_total, b_unlocked, b_loaded) = counts(b);
+                remaining(a_total, a_unlocked, a_loaded)
+                    .cmp(&remaining(b_total, b_unlocked, b_loaded))
+                    .then_with(alphabetical)
+            }
+        }
+    }
+}
+
+/// Narrow a library listing: the sidebar filters, then a case-insensitive name
+/// search (empty matches everything), then the sort.
+pub fn query_apps(
+    apps: Vec<AppModel>,
+    filter: &AppFilter,
+    search: &str,
+    sort: AppSort,
+) -> Vec<AppModel> {
+    let search = search.to_lowercase();
+    let mut apps: Vec<AppModel> = apps
+        .into_iter()
+        .filter(|app| filter.keeps(app))
+        .filter(|app| search.is_empty() || app.app_name.to_lowercase().contains(&search))
+        .collect();
+    apps.sort_by(|a, b| sort.compare(a, b));
+    apps
+}
+
+/// The profile page's library tiles, over whatever listing it is given. Junk is
+/// left out, as there.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LibrarySummary {
+    pub apps: u32,
+    /// Apps with achievement counts; the sums below are over these.
+    pub measured: u32,
+    pub achievements_unlocked: u64,
+    pub achievements_total: u64,
+    pub perfect: u32,
+    /// Apps with at least one achievement unlocked.
+    pub started: u32,
+    /// Mean completion of the started apps, 0-100: a library is mostly games
+    /// never opened, and averaging those in measures the backlog instead.
+    pub average_completion: f64,
+    pub playtime_minutes: u64,
+}
+
+#[cfg(feature = "cli")]
+pub fn summarize(apps: &[AppModel]) -> LibrarySummary {
+    let mut summary = LibrarySummary::default();
+    let mut started_rate_sum = 0.0;
+    for app in apps {
+        if matches!(app.app_type, AppModelType::Junk) {
+            continue;
+        }
+        summary.apps += 1;
+        summary.playtime_minutes += u64::from(app.playtime_minutes.unwrap_or(0));
+        let (Some(total), Some(unlocked)) = (app.achievement_count, app.unlocked_achievement_count)
+        else {
+            continue;
+        };
+        summary.measured += 1;
+        summary.achievements_total += u64::from(total);
+        summary.achievements_unlocked += u64::from(unlocked);
+        if is_fully_unlocked(total, unlocked) {
+            summary.perfect += 1;
+        }
+        if total > 0 && unlocked > 0 {
+            summary.started += 1;
+            started_rate_sum += f64::from(unlocked.min(total)) / f64::from(total);
+        }
+    }
+    if summary.started > 0 {
+        summary.average_completion = started_rate_sum / f64::from(summary.started) * 100.0;
+    }
+    summary
+}
+
+/// Every bound is optional; an unset one matches everything. Protected means any
+/// non-zero permission, the same test the unlock-all and import paths skip on.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AchievementFilter {
+    /// `Some(true)` keeps only unlocked achievements, `Some(false)` only locked.
+    pub achieved: Option<bool>,
+    pub protected: Option<bool>,
+    pub hidden: Option<bool>,
+    /// Global unlock percentage strictly below this. An achievement Steam has no
+    /// percentage for never matches.
+    pub rarer_than: Option<f32>,
+    /// Unlock time bounds, inclusive. A locked achievement never matches either.
+    pub unlocked_after: Option<SystemTime>,
+    pub unlocked_before: Option<SystemTime>,
+}
+
+#[cfg(feature = "cli")]
+impl AchievementFilter {
+    pub fn keeps(&self, achievement: &AchievementInfo) -> bool {
+        if self
+            .achieved
+            .is_some_and(|wanted| wanted != achievement.is_achieved)
+        {
+            return false;
+        }
+        if self
+            .protected
+            .is_some_and(|wanted| wanted != (achievement.permission != 0))
+        {
+            return false;
+        }
+        if self
+            .hidden
+            .is_some_and(|wanted| wanted != achievement.is_hidden)
+        {
+            return false;
+        }
+        if let Some(threshold) = self.rarer_than
+            && !achievement
+                .global_achieved_percent
+                .is_some_and(|percent| percent < threshold)
+        {
+            return false;
+        }
+        if self.unlocked_after.is_some() || self.unlocked_before.is_some() {
+            let Some(unlocked_at) = achievement.unlock_time else {
+                return false;
+            };
+            if self.unlocked_after.is_some_and(|after| unlocked_at < after)
+                || self
+                    .unlocked_before
+                    .is_some_and(|before| unlocked_at > before)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum AchievementSort {
+    /// The order the schema declares them in, which is what the GUI shows.
+    #[default]
+    Schema,
+    Id,
+    Name,
+    /// Rarest first; achievements without a global percentage last.
+    Rarity,
+    /// Oldest unlock first; locked achievements last.
+    UnlockTime,
+}
+
+#[cfg(feature = "cli")]
+impl FromStr for AchievementSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "schema" => Ok(AchievementSort::Schema),
+            "id" => Ok(AchievementSort::Id),
+            "name" => Ok(AchievementSort::Name),
+            "rarity" => Ok(AchievementSort::Rarity),
+            "unlock_time" => Ok(AchievementSort::UnlockTime),
+            _ => Err(format!(
+                "'{s}' is not a sort order; expected one of schema, id, name, rarity, unlock_time"
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl TryFrom<String> for AchievementSort {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(feature = "cli")]
+impl AchievementSort {
+    pub fn compare(&self, a: &AchievementInfo, b: &AchievementInfo) -> Ordering {
+        match self {
+            AchievementSort::Schema => Ordering::Equal,
+            AchievementSort::Id => a.id.cmp(&b.id),
+            AchievementSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            AchievementSort::Rarity => {
+                match (a.global_achieved_percent, b.global_achieved_percent) {
+                    (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }
+            AchievementSort::UnlockTime => match (a.unlock_time, b.unlock_time) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+}
+
+/// Filter then sort. The sort is stable, so `Schema` keeps the schema order.
+#[cfg(feature = "cli")]
+pub fn query_achievements(
+    achievements: Vec<AchievementInfo>,
+    filter: &AchievementFilter,
+    sort: AchievementSort,
+) -> Vec<AchievementInfo> {
+    let mut achievements: Vec<AchievementInfo> = achievements
+        .into_iter()
+        .filter(|a| filter.keeps(a))
+        .collect();
+    achievements.sort_by(|a, b| sort.compare(a, b));
+    achievements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "cli")]
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn app(app_id: u32, name: &str, counts: Option<(u32, u32)>) -> AppModel {
+        AppModel {
+            app_id,
+            app_name: name.to_string(),
+            image_url: None,
+            app_type: AppModelType::App,
+            developer: String::new(),
+            metacritic_score: None,
+            playtime_minutes: None,
+            last_played: Some(1),
+            achievement_count: counts.map(|(total, _)| total),
+            unlocked_achievement_count: counts.map(|(_, unlocked)| unlocked),
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn achievement(id: &str, achieved: bool, percent: Option<f32>) -> AchievementInfo {
+        AchievementInfo {
+            id: id.to_string(),
+            is_achieved: achieved,
+            unlock_time: achieved.then(|| UNIX_EPOCH + Duration::from_secs(1_000)),
+            permission: 0,
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            name: id.to_string(),
+            description: String::new(),
+            global_achieved_percent: percent,
+            is_hidden: false,
+        }
+    }
+
+    #[test]
+    fn hide_fully_unlocked_keeps_apps_without_achievements() {
+        let filter = AppFilter {
+            hide_fully_unlocked: true,
+            ..Default::default()
+        };
+        assert!(!filter.keeps(&app(1, "Done", Some((10, 10)))));
+        assert!(filter.keeps(&app(2, "Half", Some((10, 5)))));
+        assert!(filter.keeps(&app(3, "Empty", Some((0, 0)))));
+    }
+
+    #[test]
+    fn junk_and_never_launched_need_no_counts() {
+        let mut junk = app(1, "Tool", None);
+        junk.app_type = AppModelType::Junk;
+        let mut unplayed = app(2, "Backlog", None);
+        unplayed.last_played = None;
+
+        let filter = AppFilter {
+            junk: true,
+            hide_never_launched: true,
+            ..Default::default()
+        };
+        assert!(!filter.keeps(&junk));
+        assert!(!filter.keeps(&unplayed));
+        assert!(filter.keeps(&app(3, "Played", None)));
+        assert!(!filter.depends_on_counts());
+    }
+
+    #[test]
+    fn remaining_puts_finished_and_unknown_apps_last() {
+        let apps = vec![
+            app(1, "Done", Some((10, 10))),
+            app(2, "Unknown", None),
+            app(3, "Close", Some((10, 9))),
+            app(4, "Far", Some((10, 1))),
+        ];
+        let sorted = query_apps(apps, &AppFilter::default(), "", AppSort::Remaining);
+        let ids: Vec<u32> = sorted.iter().map(|a| a.app_id).collect();
+        assert_eq!(ids, vec![3, 4, 1, 2]);
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn the_average_only_counts_started_games() {
+        let mut junk = app(4, "Tool", Some((10, 10)));
+        junk.app_type = AppModelType::Junk;
+        let summary = summarize(&[
+            app(1, "Done", Some((10, 10))),
+            app(2, "Half", Some((4, 2))),
+            app(3, "Backlog", Some((20, 0))),
+            app(5, "Unknown", None),
+            junk,
+        ]);
+        assert_eq!(summary.apps, 4);
+        assert_eq!(summary.measured, 3);
+        assert_eq!(summary.achievements_unlocked, 12);
+        assert_eq!(summary.achievements_total, 34);
+        assert_eq!(summary.perfect, 1);
+        assert_eq!(summary.started, 2);
+        assert_eq!(summary.average_completion, 75.0);
+    }
+
+    #[test]
+    fn sort_keys_accept_both_spellings() {
+        assert_eq!("last_played".parse(), Ok(AppSort::LastPlayed));
+        assert_eq!("last-played".parse(), Ok(AppSort::LastPlayed));
+        assert!("newest".parse::<AppSort>().is_err());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn rarity_threshold_skips_unknown_percentages() {
+        let filter = AchievementFilter {
+            rarer_than: Some(5.0),
+            ..Default::default()
+        };
+        assert!(filter.keeps(&achievement("rare", false, Some(1.5))));
+        assert!(!filter.keeps(&achievement("common", false, Some(40.0))));
+        assert!(!filter.keeps(&achievement("unknown", false, None)));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn date_bounds_never_match_locked_achievements() {
+        let filter = AchievementFilter {
+            unlocked_after: Some(UNIX_EPOCH),
+            ..Default::default()
+        };
+        assert!(filter.keeps(&achievement("got", true, None)));
+        assert!(!filter.keeps(&achievement("missing", false, None)));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn rarity_sort_is_rarest_first() {
+        let sorted = query_achievements(
+            vec![
+                achievement("a", false, None),
+                achievement("b", false, Some(30.0)),
+                achievement("c", false, Some(2.0)),
+            ],
+            &AchievementFilter::default(),
+            AchievementSort::Rarity,
+        );
+        let ids: Vec<&str> = sorted.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "b", "a"]);
+    }
+}
@@ -97,3 +97,66 @@ pub fn parse_localconfig(path: &Path) -> Result<PlaytimeMap, SamError> {
 
     Ok(map)
 }
+
+#[derive(Deserialize)]
+struct LoginUser {
+    #[serde(rename = "MostRecent", alias = "mostrecent", default)]
+    most_recent: Option<String>,
+    #[serde(rename = "Timestamp", alias = "timestamp", default)]
+    timestamp: Option<u64>,
+}
+
+/// The SteamID64 of the account last signed in on this machine, from
+/// `loginusers.vdf`. Only `MostRecent` says who that was; the timestamps break
+/// the tie when no entry carries it.
+pub fn most_recent_login(path: &Path) -> Result<u64, SamError> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        dev_println!(
+            "ORCH",
+            "Failed to read loginusers.vdf at {}: {e}",
+            path.display()
+        );
+        SamError::UnknownError
+    })?;
+    parse_login_users(&contents)
+}
+
+fn parse_login_users(contents: &str) -> Result<u64, SamError> {
+    let users: HashMap<String, LoginUser> = keyvalues_serde::from_str(contents).map_err(|e| {
+        dev_println!("ORCH", "Failed to parse loginusers.vdf: {e}");
+        SamError::UnknownError
+    })?;
+
+    users
+        .into_iter()
+        .filter_map(|(id, user)| Some((id.parse::<u64>().ok()?, user)))
+        .max_by_key(|(_, user)| (user.most_recent.as_deref() == Some("1"), user.timestamp))
+        .map(|(id, _)| id)
+        .ok_or(SamError::UnknownError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_most_recent_login_wins_over_the_latest_timestamp() {
+        let vdf = r#""users"
+{
+	"76561197960287930"
+	{
+		"AccountName"		"older"
+		"MostRecent"		"1"
+		"Timestamp"		"1700000000"
+	}
+	"76561197960287931"
+	{
+		"AccountName"		"newer"
+		"MostRecent"		"0"
+		"Timestamp"		"1800000000"
+	}
+}
+"#;
+        assert_eq!(parse_login_users(vdf), Ok(76561197960287930));
+    }
+}
@@ -79,6 +79,7 @@ impl LocalIndex {
         self.read_one(app_id)
     }
 
+    #[cfg(any(feature = "cli", feature = "gui"))]
     pub fn read_all(&self) -> HashMap<u32, (u32, u32)> {
         let ids: Vec<u32> = self
             .schemas_present
@@ -117,41 +118,70 @@ impl LocalIndex {
             .stats_dir
             .join(format!("UserGameStats_{}_{app_id}.bin", self.account_id));
 
-        let bits = cached_schema_bits(app_id, &schema_path)?;
+        let schema = cached_schema(app_id, &schema_path)?;
         let user_stats = KeyValue::load_as_binary(&user_path).ok()?;
 
-        let (total, unlocked) = count_from_bits(&bits, &user_stats);
+        let (total, unlocked) = count_from_bits(&schema.bits, &user_stats);
         (total != 0 && unlocked <= total).then_some((total, unlocked))
     }
+
+    /// The name the schema was published under, which is all there is to go on
+    /// for a title with Steam closed. Free after `read_all` or `try_read`.
+    #[cfg(feature = "cli")]
+    pub fn game_name(&self, app_id: u32) -> Option<String> {
+        if !self.schemas_present.contains(&app_id) {
+            return None;
+        }
+        let schema_path = self
+            .stats_dir
+            .join(format!("UserGameStatsSchema_{app_id}.bin"));
+        let name = cached_schema(app_id, &schema_path)?.game_name.clone();
+        (!name.is_empty()).then_some(name)
+    }
 }
 
+#[cfg(any(feature = "cli", feature = "gui"))]
 const SWEEP_THREADS: usize = 4;
 
-/// Only the bit layout is kept: schemas dwarf the user-stats files beside them
-/// (47 MB against 3.3 MB here) and change only when Steam re-downloads them.
-fn cached_schema_bits(app_id: u32, schema_path: &Path) -> Option<Arc<SchemaBits>> {
-    type BitsCache = Mutex<HashMap<u32, (Option<SystemTime>, Arc<SchemaBits>)>>;
-    static CACHE: LazyLock<BitsCache> = LazyLock::new(|| Mutex::new(HashMap::new()));
+/// What is kept of a schema between reads.
+struct CachedSchema {
+    #[cfg(feature = "cli")]
+    game_name: String,
+    bits: SchemaBits,
+}
+
+/// Only the bit layout and name are kept: schemas dwarf the user-stats files
+/// beside them (47 MB against 3.3 MB here) and change only when Steam
+/// re-downloads them.
+fn cached_schema(app_id: u32, schema_path: &Path) -> Option<Arc<CachedSchema>> {
+    type SchemaCache = Mutex<HashMap<u32, (Option<SystemTime>, Arc<CachedSchema>)>>;
+    static CACHE: LazyLock<SchemaCache> = LazyLock::new(|| Mutex::new(HashMap::new()));
 
     let mtime = std::fs::metadata(schema_path)
         .and_then(|m| m.modified())
         .ok();
-    if let Some((cached_mtime, bits)) = CACHE
+    if let Some((cached_mtime, cached)) = CACHE
         .lock()
         .unwrap_or_else(PoisonError::into_inner)
         .get(&app_id)
         && *cached_mtime == mtime
     {
-        return Some(Arc::clone(bits));
+        return Some(Arc::clone(cached));
     }
 
     let schema = KeyValue::load_as_binary(schema_path).ok()?;
-    let bits = Arc::new(schema_bits(&schema, app_id));
+    let cached = Arc::new(CachedSchema {
+        #[cfg(feature = "cli")]
+        game_name: find_first(&schema, "gamename")
+            .map(|n| n.as_string(""))
+            .unwrap_or_default(),
+        bits: schema_bits(&schema, app_id),
+    });
     CACHE
         .lock()
         .unwrap_or_else(PoisonError::into_inner)
-        .insert(app_id, (mtime, Arc::clone(&bits)));
-    Some(bits)
+        .insert(app_id, (mtime, Arc::clone(&cached)));
+    Some(cached)
 }
 
 /// Empty when the schema is missing or unparseable, leaving only the game default.
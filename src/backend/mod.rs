@@ -17,12 +17,18 @@ pub mod app;
 pub mod app_lister;
 pub mod app_manager;
 pub mod connected_steam;
+#[cfg(target_os = "linux")]
+pub mod daemon;
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub mod diagnostics;
 pub mod key_value;
+pub mod library_query;
 pub mod local_config;
 pub mod local_stats;
 pub mod orchestrator;
 pub mod orchestrator_client;
 pub mod progress_io;
+mod scheduler;
 pub mod stat_definitions;
 mod tests;
 pub mod types;
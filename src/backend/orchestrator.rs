@@ -15,19 +15,22 @@
 
 use crate::backend::app_lister::{AppLister, fetch_achievement_counts};
 use crate::backend::connected_steam::ConnectedSteam;
-use crate::backend::local_config::parse_localconfig;
+use crate::backend::local_config::{most_recent_login, parse_localconfig};
 use crate::backend::local_stats::{LocalIndex, read_schema_languages};
 use crate::backend::orchestrator_client::AppProgress;
-use crate::backend::progress_io::{MAX_CONCURRENT_APPS, run_command_on_apps_concurrent};
+use crate::backend::progress_io::run_command_on_apps_concurrent;
+use crate::backend::scheduler;
 use crate::backend::stat_definitions::{AchievementInfo, StatInfo};
 use crate::backend::user_unlock_times;
 use crate::dev_println;
 use crate::utils::app_paths::get_executable_path;
 use crate::utils::bidir_child::BidirChild;
 use crate::utils::ipc_client::IpcClient;
+use crate::utils::ipc_recording::{self, Direction, Peer, Replay};
 use crate::utils::ipc_types::{
-    AppExport, ImportSummary, ProgressMsg, SamError, SteamCommand, SteamResponse, frame_message,
-    parse_response_bytes, read_message, write_message,
+    AppExport, AppServerStatus, FanOutPolicy, HEADER_LEN, ImportSummary, OrchestratorEvent,
+    Process, ProgressMsg, SamError, Snapshotted, SteamCommand, SteamResponse, Stored,
+    answer_handshake, frame_tagged, parse_response_bytes, read_tagged, retag, write_tagged,
 };
 use crate::utils::steam_locator::SteamLocator;
 use interprocess::unnamed_pipe::{Recver, Sender};
@@ -36,66 +39,540 @@ use serde::de::DeserializeOwned;
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::process::Command;
-use std::sync::{LazyLock, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Forward `command` to the app server and return the framed response bytes
-/// (length prefix + JSON) suitable for proxying straight back to the parent.
+/// (header + JSON) suitable for proxying straight back to the parent.
 fn send_app_command(ipc: &mut IpcClient, command: SteamCommand) -> Result<Vec<u8>, SamError> {
     ipc.send(&command)?;
     ipc.recv_frame()
 }
 
-fn send<T: Serialize>(tx: &mut Sender, msg: &T) {
-    write_message(tx, msg).expect("[ORCHESTRATOR] Failed to send response");
+/// Where a command's response goes, the parent's pipe or a daemon client, and
+/// the request it answers. Commands run side by side, so each frame is written
+/// whole under the lock.
+pub struct Reply<'a> {
+    id: u32,
+    out: &'a Mutex<dyn Write + Send + 'a>,
+    cancels: &'a Cancels,
+    holds: Option<&'a Holds>,
 }
 
-fn send_raw(tx: &mut Sender, bytes: &[u8]) {
-    tx.write_all(bytes)
-        .expect("[ORCHESTRATOR] Failed to send response");
+impl<'a> Reply<'a> {
+    pub fn new(id: u32, out: &'a Mutex<dyn Write + Send + 'a>, cancels: &'a Cancels) -> Self {
+        Self {
+            id,
+            out,
+            cancels,
+            holds: None,
+        }
+    }
+
+    /// Charge the launches and stops of the command to a daemon session.
+    fn holding(self, holds: &'a Holds) -> Self {
+        Self {
+            holds: Some(holds),
+            ..self
+        }
+    }
+
+    fn took(&self, app_id: u32) {
+        if let Some(holds) = self.holds {
+            holds.took(app_id);
+        }
+    }
+
+    fn dropped(&self, app_id: u32) {
+        if let Some(holds) = self.holds {
+            holds.dropped(app_id);
+        }
+    }
+}
+
+/// A frontend's bulk fan-outs in flight, by request id, each with the flag that
+/// stops it. Tracked from the moment the request is read, so a `Cancel` that
+/// overtakes it on the way to its thread still lands.
+#[derive(Default)]
+pub struct Cancels(Mutex<HashMap<u32, Arc<AtomicBool>>>);
+
+impl Cancels {
+    pub fn track(&self, id: u32, command: &SteamCommand) {
+        if matches!(
+            command,
+            SteamCommand::ExportApps(..)
+                | SteamCommand::ImportApps(..)
+                | SteamCommand::UnlockAllApps(..)
+                | SteamCommand::ResetApps(..)
+        ) {
+            self.flag(id);
+        }
+    }
+
+    fn flag(&self, id: u32) -> Arc<AtomicBool> {
+        self.0.lock().unwrap().entry(id).or_default().clone()
+    }
+
+    fn forget(&self, id: u32) {
+        self.0.lock().unwrap().remove(&id);
+    }
+
+    /// Whether `id` was still running.
+    pub fn cancel(&self, id: u32) -> bool {
+        match self.0.lock().unwrap().get(&id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The launches a daemon session holds, by app, so that they can be stopped
+/// for it once it goes. Its commands run side by side, so each charges its own
+/// rather than the session diffing the refcounts around it.
+#[derive(Default)]
+pub struct Holds(Mutex<HashMap<u32, usize>>);
+
+impl Holds {
+    fn took(&self, app_id: u32) {
+        *self.0.lock().unwrap().entry(app_id).or_default() += 1;
+    }
+
+    /// A stop for an app the session never launched takes nothing from it.
+    fn dropped(&self, app_id: u32) {
+        let mut held = self.0.lock().unwrap();
+        if let Some(holds) = held.get_mut(&app_id) {
+            *holds -= 1;
+            if *holds == 0 {
+                held.remove(&app_id);
+            }
+        }
+    }
+
+    /// Forget the apps nobody holds anymore, after a `StopApps` from anyone.
+    pub fn retain_running(&self, running: &HashMap<u32, usize>) {
+        self.0
+            .lock()
+            .unwrap()
+            .retain(|app_id, _| running.contains_key(app_id));
+    }
+
+    pub fn take(&self) -> HashMap<u32, usize> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Frontends following `SubscribeEvents`, and what was last seen of Steam, to
+/// tell changes by.
+#[derive(Default)]
+pub struct Events {
+    followers: Mutex<Vec<mpsc::Sender<OrchestratorEvent>>>,
+    /// Set as the orchestrator winds down, so that a late follower is not left
+    /// waiting.
+    closed: AtomicBool,
+    seen: Mutex<Option<SteamState>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct SteamState {
+    running: bool,
+    /// Running, but out of this orchestrator's reach.
+    stranded: bool,
+    user: Option<u64>,
+}
+
+impl SteamState {
+    fn look() -> Self {
+        let stranded = stranded();
+        Self {
+            running: !stranded && steam_is_running(),
+            stranded,
+            user: SteamLocator::get_login_users_path()
+                .and_then(|path| most_recent_login(&path).ok()),
+        }
+    }
+
+    fn events(self) -> impl Iterator<Item = OrchestratorEvent> {
+        let steam = if self.stranded {
+            OrchestratorEvent::Stranded
+        } else if self.running {
+            OrchestratorEvent::SteamConnected
+        } else {
+            OrchestratorEvent::SteamDisconnected
+        };
+        let user = self
+            .user
+            .map(|steam_id64| OrchestratorEvent::UserChanged { steam_id64 });
+        std::iter::once(steam).chain(user)
+    }
+}
+
+impl Events {
+    /// Everything from now on, starting with how Steam is doing.
+    pub fn subscribe(&self) -> mpsc::Receiver<OrchestratorEvent> {
+        let now = self.watch();
+        let (tx, rx) = mpsc::channel();
+        let mut followers = self.followers.lock().unwrap();
+        if !self.closed.load(Ordering::Relaxed) {
+            for event in now.events() {
+                let _ = tx.send(event);
+            }
+            followers.push(tx);
+        }
+        rx
+    }
+
+    fn emit(&self, event: OrchestratorEvent) {
+        dev_println!("ORCH", "Event: {event:?}");
+        self.followers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    /// Look at Steam again, and tell followers what changed.
+    fn watch(&self) -> SteamState {
+        let now = SteamState::look();
+        let Some(before) = self.seen.lock().unwrap().replace(now) else {
+            return now;
+        };
+        if (before.running, before.stranded) != (now.running, now.stranded) {
+            #[cfg(target_os = "linux")]
+            if !now.running && !now.stranded {
+                crate::utils::steam_ns::recheck_flatpak_steam();
+            }
+            self.emit(now.events().next().unwrap());
+        }
+        if let Some(steam_id64) = now.user
+            && before.user.is_some_and(|user| user != steam_id64)
+        {
+            self.emit(OrchestratorEvent::UserChanged { steam_id64 });
+        }
+        now
+    }
+
+    /// Ends every subscription.
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.followers.lock().unwrap().clear();
+    }
+}
+
+/// Whether Steam runs, short of connecting to it.
+#[cfg(target_os = "linux")]
+fn steam_is_running() -> bool {
+    crate::utils::steam_ns::loaded_install_is_running()
+}
+
+#[cfg(target_os = "linux")]
+fn stranded() -> bool {
+    crate::utils::steam_ns::flatpak_steam_started_after_us()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn stranded() -> bool {
+    false
+}
+
+/// Steam keeps its pid under `ActiveProcess` while it runs.
+#[cfg(target_os = "windows")]
+fn steam_is_running() -> bool {
+    use winreg::RegKey;
+    use winreg::enums::HKEY_CURRENT_USER;
+
+    RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("SOFTWARE\\Valve\\Steam\\ActiveProcess")
+        .and_then(|key| key.get_value::<u32, _>("pid"))
+        .is_ok_and(|pid| pid != 0)
+}
+
+/// Whether `command` answering `true` means Steam stored the app's stats.
+fn stores(command: &SteamCommand) -> bool {
+    matches!(
+        command,
+        SteamCommand::SetAchievement(_, _, _, true)
+            | SteamCommand::SetIntStat(_, _, _, true)
+            | SteamCommand::SetFloatStat(_, _, _, true)
+            | SteamCommand::StoreStatsAndAchievements(_)
+            | SteamCommand::UnlockAllAchievements(_)
+            | SteamCommand::ResetStats(_, _)
+    )
+}
+
+/// Whether a storing command's answer says Steam stored: a bare `true`, or a
+/// `Stored` carrying one.
+fn answered_stored(response: &[u8]) -> bool {
+    parse_response_bytes::<bool>(response) == Ok(true)
+        || parse_response_bytes::<Stored<bool>>(response).is_ok_and(|stored| stored.result)
+}
+
+// A failed write means the frontend is gone, which the command reader finds out
+// too and winds down for.
+fn send<T: Serialize>(tx: &Reply, msg: &T) {
+    ipc_recording::record(Peer::Frontend, Direction::Response, tx.id, msg);
+    if write_tagged(&mut *tx.out.lock().unwrap(), tx.id, msg).is_err() {
+        eprintln!("[ORCHESTRATOR] Failed to send response");
+    }
+}
+
+fn send_raw(tx: &Reply, frame: &[u8]) {
+    ipc_recording::record_payload(
+        Peer::Frontend,
+        Direction::Response,
+        tx.id,
+        &frame[HEADER_LEN..],
+    );
+    let mut frame = frame.to_vec();
+    retag(&mut frame, tx.id);
+    if let Err(e) = tx.out.lock().unwrap().write_all(&frame) {
+        eprintln!("[ORCHESTRATOR] Failed to send response: {e}");
+    }
+}
+
+/// Commands running at once, at most, on a pipe or on the daemon. The rest
+/// queue for a free worker: a frontend that sends many at once gets its
+/// answers later rather than a thread for each.
+pub const WORKERS: usize = 8;
+
+pub type Job<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Start `WORKERS` threads with `spawn`, each running the queue's jobs until
+/// it closes. A job must not wait on one queued behind it.
+pub fn worker_pool<'a>(spawn: impl Fn(Job<'a>)) -> mpsc::Sender<Job<'a>> {
+    let (jobs, queue) = mpsc::channel::<Job<'a>>();
+    let queue = Arc::new(Mutex::new(queue));
+    for _ in 0..WORKERS {
+        let queue = queue.clone();
+        spawn(Box::new(move || {
+            loop {
+                let job = queue.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    Err(_) => return,
+                }
+            }
+        }));
+    }
+    jobs
+}
+
+/// Commands served from the orchestrator's own Steam connection, which stays on
+/// one thread. The rest are about app servers, or need no Steam at all.
+fn uses_own_connection(command: &SteamCommand) -> bool {
+    matches!(
+        command,
+        SteamCommand::GetSubscribedAppList(..)
+            | SteamCommand::GetAchievementCounts(..)
+            | SteamCommand::GetFriends
+            | SteamCommand::GetCurrentUser
+            | SteamCommand::GetUserAvatar(_)
+            | SteamCommand::GetUserPersonaName(_)
+    )
 }
 
 pub fn orchestrator(parent_tx: &mut Sender, parent_rx: &mut Recver) -> u8 {
+    if let Err(e) = answer_handshake(parent_tx, parent_rx) {
+        eprintln!("[ORCHESTRATOR] Handshake with the frontend failed: {e}");
+        return 1;
+    }
+    if let Some(path) = ipc_recording::replay_path() {
+        eprintln!("[ORCHESTRATOR] Answering from {}", path.display());
+        return match Replay::load(&path) {
+            Ok(replay) => replay.serve(parent_tx, parent_rx),
+            Err(_) => 1,
+        };
+    }
+    ipc_recording::start();
+
     // Lazy: only the app-list and achievement-count commands use the
     // orchestrator's own connection. Per-app commands go to child processes, so
     // a one-shot CLI call that forwards to a child pays for one handshake, not two.
     let mut connected_steam: Option<ConnectedSteam> = None;
-    let mut children_processes: HashMap<u32, (IpcClient, usize)> = HashMap::new();
+    let servers = AppServers::default();
+    let cancels = Cancels::default();
+    let out: Mutex<&mut Sender> = Mutex::new(parent_tx);
+    let (out, servers, cancels) = (&out, &servers, &cancels);
+    let (own_tx, own_queue) = mpsc::channel::<(u32, SteamCommand)>();
+    let (stop_sweeping, sweeps) = mpsc::channel::<()>();
+
+    // Requests carry ids, so they need not be answered in order. The
+    // orchestrator's own connection stays on this thread and its commands run
+    // one after another. Every other command goes to a worker, and only waits
+    // for those before it on the same app server: opening a game does not
+    // queue behind a library-wide achievement count.
+    let shutdown = thread::scope(|scope| {
+        let jobs = worker_pool(|worker| {
+            scope.spawn(worker);
+        });
+        scope.spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = sweeps.recv_timeout(SWEEP_INTERVAL) {
+                servers.sweep();
+                servers.run_schedule();
+            }
+        });
+        let reader = scope.spawn(move || {
+            loop {
+                dev_println!("ORCH", "Main loop...");
 
-    loop {
-        dev_println!("ORCH", "Main loop...");
+                let (id, message) = match read_tagged::<SteamCommand>(parent_rx) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        eprintln!("[ORCHESTRATOR] Parent pipe error: {e}. Shutting down");
+                        return None;
+                    }
+                };
 
-        let message: SteamCommand = match read_message(parent_rx) {
-            Ok(m) => m,
-            Err(e) => {
-                eprintln!("[ORCHESTRATOR] Parent pipe error: {e}. Shutting down");
-                for (_, (ipc, _)) in children_processes.iter_mut() {
-                    let _ = send_app_command(ipc, SteamCommand::Shutdown);
-                    let _ = ipc.wait();
+                dev_println!("ORCH", "Received message {id}: {message:?}");
+                ipc_recording::record(Peer::Frontend, Direction::Request, id, &message);
+                cancels.track(id, &message);
+
+                match message {
+                    // Answered once everything in flight is.
+                    SteamCommand::Shutdown => return Some(id),
+                    message if uses_own_connection(&message) => {
+                        let _ = own_tx.send((id, message));
+                    }
+                    // Here, as every worker may be busy with the fan-out it stops.
+                    message @ SteamCommand::Cancel(_) => {
+                        process_app_command(message, &Reply::new(id, out, cancels), servers);
+                    }
+                    // Held for as long as the orchestrator runs, so not a worker's.
+                    message @ SteamCommand::SubscribeEvents(_) => {
+                        scope.spawn(move || {
+                            process_app_command(message, &Reply::new(id, out, cancels), servers)
+                        });
+                    }
+                    message => {
+                        let _ = jobs.send(Box::new(move || {
+                            process_app_command(message, &Reply::new(id, out, cancels), servers)
+                        }));
+                    }
                 }
-                return 1;
             }
-        };
+        });
+        for (id, message) in own_queue {
+            process_own_command(message, &Reply::new(id, out, cancels), &mut connected_steam);
+        }
+        let shutdown = reader.join().ok().flatten();
+        drop(stop_sweeping);
+        servers.events.close();
+        shutdown
+    });
+
+    match shutdown {
+        Some(id) => {
+            process_app_command(
+                SteamCommand::Shutdown,
+                &Reply::new(id, out, cancels),
+                servers,
+            );
+            0
+        }
+        None => {
+            servers.stop_all();
+            1
+        }
+    }
+}
 
-        dev_println!("ORCH", "Received message: {message:?}");
+/// Where a daemon session's answers go, shared by its commands in flight.
+#[cfg(target_os = "linux")]
+pub type Out = Arc<Mutex<dyn Write + Send>>;
+
+/// The orchestrator's state, for the daemon: it serves many clients rather
+/// than one parent pipe. As with the pipe, commands on its own Steam
+/// connection run one after another on the thread that owns it, and the rest
+/// side by side.
+#[cfg(target_os = "linux")]
+pub struct Orchestrator {
+    own: mpsc::Sender<(u32, SteamCommand, Out, Arc<Cancels>)>,
+    servers: AppServers,
+}
 
-        let continue_running = process_command(
-            message,
-            parent_tx,
-            &mut children_processes,
-            &mut connected_steam,
-        );
-        if !continue_running {
-            break 0;
+#[cfg(target_os = "linux")]
+impl Orchestrator {
+    /// With a thread for its own connection and one that sweeps every
+    /// `SWEEP_INTERVAL`, both for as long as the process lives.
+    pub fn start() -> Arc<Self> {
+        let (own, queue) = mpsc::channel::<(u32, SteamCommand, Out, Arc<Cancels>)>();
+        thread::spawn(move || {
+            let mut connected_steam: Option<ConnectedSteam> = None;
+            for (id, command, out, cancels) in queue {
+                let tx = Reply::new(id, &*out, &cancels);
+                process_own_command(command, &tx, &mut connected_steam);
+            }
+        });
+        let orchestrator = Arc::new(Self {
+            own,
+            servers: AppServers::default(),
+        });
+        let sweeping = Arc::downgrade(&orchestrator);
+        thread::spawn(move || {
+            loop {
+                thread::sleep(SWEEP_INTERVAL);
+                let Some(orchestrator) = sweeping.upgrade() else {
+                    return;
+                };
+                orchestrator.servers.sweep();
+                orchestrator.servers.run_schedule();
+            }
+        });
+        orchestrator
+    }
+
+    /// Run `command` on this thread, for a session whose launches and stops
+    /// are charged to `holds`. One on the orchestrator's own connection is
+    /// queued for its thread instead, and answered from there.
+    pub fn process(
+        &self,
+        id: u32,
+        command: SteamCommand,
+        cancels: &Arc<Cancels>,
+        out: &Out,
+        holds: &Holds,
+    ) {
+        if uses_own_connection(&command) {
+            let _ = self.own.send((id, command, out.clone(), cancels.clone()));
+            return;
         }
+        let tx = Reply::new(id, &**out, cancels).holding(holds);
+        process_app_command(command, &tx, &self.servers);
+    }
+
+    /// How many holders each running app server has.
+    pub fn refcounts(&self) -> HashMap<u32, usize> {
+        self.servers.refcounts()
+    }
+
+    /// The daemon follows the schedule whether or not a frontend is attached.
+    pub fn follow_schedule(&self) {
+        self.servers.follows_schedule.store(true, Ordering::Relaxed);
+    }
+
+    /// Events for every frontend at once; `current` is what a newcomer is
+    /// told first.
+    pub fn subscribe(&self) -> mpsc::Receiver<OrchestratorEvent> {
+        self.servers.events.subscribe()
+    }
+
+    /// How Steam is doing, as events.
+    pub fn current(&self) -> Vec<OrchestratorEvent> {
+        self.servers.events.watch().events().collect()
     }
 }
 
 fn ensure_connected(slot: &mut Option<ConnectedSteam>) -> Result<&mut ConnectedSteam, ()> {
     if slot.is_none() {
         // Refuse to connect unless Steam is running from our install
-        // connecting to another live Steam crashes on the first app call.
+        // connecting to another live Steam crashes on the first app call, and
+        // so does connecting from outside Flatpak Steam's namespace.
         #[cfg(target_os = "linux")]
-        if !crate::utils::steam_ns::loaded_install_is_running() {
+        if !crate::utils::steam_ns::loaded_install_is_running() || stranded() {
             return Err(());
         }
         match ConnectedSteam::new(false) {
@@ -119,47 +596,460 @@ fn orchestrator_connection(slot: &mut Option<ConnectedSteam>) -> Result<&mut Con
     ensure_connected(slot)
 }
 
-fn ensure_app_launched(
-    app_id: u32,
-    children_processes: &mut HashMap<u32, (IpcClient, usize)>,
-) -> Result<(), SamError> {
-    // If a process for this app is already alive, just bump the refcount.
-    if let Some((_, refcount)) = children_processes.get_mut(&app_id) {
-        *refcount += 1;
-        dev_println!("ORCH", "App {} refcount now {}", app_id, *refcount);
-        return Ok(());
+/// How often `AppServers::sweep` looks in on the servers.
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a server has to answer the sweep's `Status`, which holds its lock
+/// meanwhile. One that takes longer is killed and started again.
+const STATUS_DEADLINE: Duration = Duration::from_secs(5);
+
+/// `SAM_APP_SERVER_IDLE_TIMEOUT`, in seconds: how long a held app server may
+/// sit unused before it is stopped, to start again on its next command. Unset,
+/// they stay up, as a held server is also what keeps a game idling.
+fn idle_timeout() -> Option<Duration> {
+    let raw = std::env::var("SAM_APP_SERVER_IDLE_TIMEOUT").ok()?;
+    match raw.parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => {
+            eprintln!("[ORCHESTRATOR] Ignoring SAM_APP_SERVER_IDLE_TIMEOUT={raw}");
+            None
+        }
+    }
+}
+
+enum AppServer {
+    /// Registered, and started by whoever locks it first.
+    Starting,
+    Running(IpcClient),
+    /// Exited on its own while held. Started again by the next sweep, or by
+    /// whoever locks it first, with its holds.
+    Exited,
+    /// Stopped for want of use while held, and started again by whoever locks
+    /// it next.
+    Idle,
+    /// Failed to start, or stopped since.
+    Gone(SamError),
+}
+
+/// What `GetAppServers` reports. Kept beside the server rather than behind its
+/// lock, so that asking does not wait on a long exchange.
+struct Health {
+    pid: Option<u32>,
+    up_since: Option<Instant>,
+    last_used: Instant,
+    restarts: u32,
+    last_error: Option<SamError>,
+}
+
+struct Slot {
+    server: Mutex<AppServer>,
+    health: Mutex<Health>,
+}
+
+impl Slot {
+    fn new() -> Self {
+        Self {
+            server: Mutex::new(AppServer::Starting),
+            health: Mutex::new(Health {
+                pid: None,
+                up_since: None,
+                last_used: Instant::now(),
+                restarts: 0,
+                last_error: None,
+            }),
+        }
+    }
+
+    /// Start `server`, or start it again. A failure is kept as its last error.
+    fn start(&self, app_id: u32, server: &mut AppServer) -> Result<(), SamError> {
+        let again = matches!(server, AppServer::Exited);
+        let started = start_app_server(app_id);
+        let mut health = self.health.lock().unwrap();
+        match started {
+            Ok(ipc) => {
+                let now = Instant::now();
+                health.pid = ipc.pid();
+                health.up_since = Some(now);
+                health.last_used = now;
+                if again {
+                    health.restarts += 1;
+                    eprintln!("[ORCHESTRATOR] Restarted the app server for {app_id}");
+                }
+                *server = AppServer::Running(ipc);
+                Ok(())
+            }
+            Err(e) => {
+                health.last_error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    fn went_down(&self, server: &mut AppServer, down: AppServer, error: Option<SamError>) {
+        *server = down;
+        let mut health = self.health.lock().unwrap();
+        health.pid = None;
+        health.up_since = None;
+        if error.is_some() {
+            health.last_error = error;
+        }
+    }
+
+    /// Catch a server that died since its last exchange.
+    fn check_exit(&self, app_id: u32, server: &mut AppServer, events: &Events) {
+        let AppServer::Running(ipc) = server else {
+            return;
+        };
+        let Some(status) = ipc.exited() else {
+            return;
+        };
+        eprintln!("[ORCHESTRATOR] The app server for {app_id} exited: {status}");
+        let error = SamError::SocketCommunicationFailed
+            .app(app_id)
+            .in_process(Process::AppServer)
+            .context(format!("The app server stopped ({status})"));
+        self.went_down(server, AppServer::Exited, Some(error.clone()));
+        events.emit(OrchestratorEvent::AppServerDied { app_id, error });
+    }
+
+    fn client<'s>(&self, server: &'s mut AppServer) -> Result<&'s mut IpcClient, SamError> {
+        match server {
+            AppServer::Running(ipc) => Ok(ipc),
+            AppServer::Gone(e) => Err(e.clone()),
+            AppServer::Exited | AppServer::Idle => Err(self
+                .health
+                .lock()
+                .unwrap()
+                .last_error
+                .clone()
+                .unwrap_or(SamError::SocketCommunicationFailed)),
+            AppServer::Starting => unreachable!("locking starts the server"),
+        }
+    }
+}
+
+struct Held {
+    slot: Arc<Slot>,
+    refcount: usize,
+}
+
+/// The running app servers, by app, with how many holders each has. Every
+/// server has a lock of its own, held for a whole exchange with it: commands
+/// for one app queue up, as its pipe carries one request at a time, while those
+/// for different apps run side by side.
+///
+/// A server that dies while held is started again, holds and all: by the next
+/// command for it, or by the next sweep.
+struct AppServers {
+    held: Mutex<HashMap<u32, Held>>,
+    idle_timeout: Option<Duration>,
+    events: Events,
+    /// Set by `FollowSchedule`.
+    follows_schedule: AtomicBool,
+}
+
+impl Default for AppServers {
+    fn default() -> Self {
+        Self {
+            held: Mutex::default(),
+            idle_timeout: idle_timeout(),
+            events: Events::default(),
+            follows_schedule: AtomicBool::new(false),
+        }
+    }
+}
+
+impl AppServers {
+    fn is_running(&self, app_id: u32) -> bool {
+        self.held.lock().unwrap().contains_key(&app_id)
+    }
+
+    fn running(&self) -> Vec<u32> {
+        self.held.lock().unwrap().keys().copied().collect()
+    }
+
+    fn refcounts(&self) -> HashMap<u32, usize> {
+        self.held
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(app_id, held)| (*app_id, held.refcount))
+            .collect()
+    }
+
+    fn statuses(&self) -> Vec<AppServerStatus> {
+        let mut statuses: Vec<AppServerStatus> = self
+            .held
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(app_id, held)| {
+                let health = held.slot.health.lock().unwrap();
+                AppServerStatus {
+                    app_id: *app_id,
+                    holders: held.refcount,
+                    pid: health.pid,
+                    uptime_secs: health.up_since.map(|since| since.elapsed().as_secs()),
+                    idle_secs: health.last_used.elapsed().as_secs(),
+                    restarts: health.restarts,
+                    last_error: health.last_error.clone(),
+                }
+            })
+            .collect();
+        statuses.sort_by_key(|status| status.app_id);
+        statuses
+    }
+
+    /// Lock `slot`'s server, starting it if nobody has yet, or again if it went
+    /// down. One that fails its first start is forgotten, with every hold on
+    /// it; one that fails to come back keeps them, for the next try.
+    fn lock<'a>(&self, app_id: u32, slot: &'a Arc<Slot>) -> MutexGuard<'a, AppServer> {
+        let mut server = slot.server.lock().unwrap();
+        slot.check_exit(app_id, &mut server, &self.events);
+        match *server {
+            AppServer::Starting => {
+                if let Err(e) = slot.start(app_id, &mut server) {
+                    let mut held = self.held.lock().unwrap();
+                    if held
+                        .get(&app_id)
+                        .is_some_and(|h| Arc::ptr_eq(&h.slot, slot))
+                    {
+                        held.remove(&app_id);
+                    }
+                    *server = AppServer::Gone(e);
+                }
+            }
+            AppServer::Exited | AppServer::Idle => {
+                let _ = slot.start(app_id, &mut server);
+            }
+            AppServer::Running(_) | AppServer::Gone(_) => {}
+        }
+        server
     }
 
-    // Otherwise launch a new process with refcount = 1.
+    /// Take a hold on `app_id`'s server, starting it for the first holder.
+    fn launch(&self, app_id: u32) -> Result<(), SamError> {
+        let (slot, first) = {
+            let mut held = self.held.lock().unwrap();
+            let held = held.entry(app_id).or_insert_with(|| Held {
+                slot: Arc::new(Slot::new()),
+                refcount: 0,
+            });
+            held.refcount += 1;
+            dev_println!("ORCH", "App {} refcount now {}", app_id, held.refcount);
+            (held.slot.clone(), held.refcount == 1)
+        };
+        let mut server = self.lock(app_id, &slot);
+        let Err(e) = slot.client(&mut server).map(drop) else {
+            if first {
+                self.events.emit(OrchestratorEvent::IdleStarted { app_id });
+            }
+            return Ok(());
+        };
+        // Down and not coming back: the holds before this one wait for it, but
+        // this one failed.
+        if !matches!(*server, AppServer::Gone(_)) {
+            let mut held = self.held.lock().unwrap();
+            if let Some(entry) = held
+                .get_mut(&app_id)
+                .filter(|h| Arc::ptr_eq(&h.slot, &slot))
+            {
+                entry.refcount -= 1;
+                if entry.refcount == 0 {
+                    held.remove(&app_id);
+                    *server = AppServer::Gone(e.clone());
+                }
+            }
+        }
+        Err(e)
+    }
+
+    /// Run `exchange` with `app_id`'s server, with nothing else talking to it.
+    fn with<R>(
+        &self,
+        app_id: u32,
+        exchange: impl FnOnce(&mut IpcClient) -> R,
+    ) -> Result<R, SamError> {
+        let Some(slot) = self
+            .held
+            .lock()
+            .unwrap()
+            .get(&app_id)
+            .map(|held| held.slot.clone())
+        else {
+            return Err(SamError::AppMismatchError.app(app_id));
+        };
+        let mut server = self.lock(app_id, &slot);
+        let result = exchange(slot.client(&mut server)?);
+        // One that died on this exchange is started again by the next.
+        slot.check_exit(app_id, &mut server, &self.events);
+        slot.health.lock().unwrap().last_used = Instant::now();
+        Ok(result)
+    }
+
+    /// Look in on every server nobody is talking to: start again those that
+    /// exited, stop those unused for longer than the idle timeout, and make
+    /// sure the rest still answer.
+    fn sweep(&self) {
+        self.events.watch();
+        let slots: Vec<(u32, Arc<Slot>)> = self
+            .held
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(app_id, held)| (*app_id, held.slot.clone()))
+            .collect();
+        for (app_id, slot) in slots {
+            // One in use answers for itself.
+            let Ok(mut server) = slot.server.try_lock() else {
+                continue;
+            };
+            slot.check_exit(app_id, &mut server, &self.events);
+            let unused = slot.health.lock().unwrap().last_used.elapsed();
+            match &mut *server {
+                AppServer::Running(ipc) if self.idle_timeout.is_some_and(|t| unused >= t) => {
+                    dev_println!(
+                        "ORCH",
+                        "Stopping app {app_id}, unused for {}s",
+                        unused.as_secs()
+                    );
+                    let _ = send_app_command(ipc, SteamCommand::Shutdown);
+                    let _ = ipc.wait();
+                    slot.went_down(&mut server, AppServer::Idle, None);
+                }
+                AppServer::Running(ipc) => {
+                    if let Err(e) =
+                        ipc.request_within::<bool, _>(&SteamCommand::Status, STATUS_DEADLINE)
+                    {
+                        eprintln!("[ORCHESTRATOR] The app server for {app_id} stopped answering");
+                        ipc.kill();
+                        let error = e.app(app_id).context("The app server stopped answering");
+                        slot.went_down(&mut server, AppServer::Exited, Some(error.clone()));
+                        self.events
+                            .emit(OrchestratorEvent::AppServerDied { app_id, error });
+                        let _ = slot.start(app_id, &mut server);
+                    }
+                }
+                AppServer::Exited => {
+                    let _ = slot.start(app_id, &mut server);
+                }
+                AppServer::Starting | AppServer::Idle | AppServer::Gone(_) => {}
+            }
+        }
+    }
+
+    /// Drop a hold on `app_id`'s server, stopping it with the last. Returns the
+    /// server's answer to `Shutdown` then, and `None` while others hold it.
+    fn release(&self, app_id: u32) -> Result<Option<Vec<u8>>, SamError> {
+        let slot = {
+            let mut held = self.held.lock().unwrap();
+            let Some(entry) = held.get_mut(&app_id) else {
+                eprintln!("[ORCHESTRATOR] App {} is not running", app_id);
+                return Err(SamError::UnknownError
+                    .app(app_id)
+                    .context("Stopping the app server"));
+            };
+            entry.refcount -= 1;
+            if entry.refcount > 0 {
+                dev_println!(
+                    "ORCH",
+                    "App {} still wanted, refcount now {}",
+                    app_id,
+                    entry.refcount
+                );
+                return Ok(None);
+            }
+            held.remove(&app_id).unwrap().slot
+        };
+        self.events.emit(OrchestratorEvent::IdleStopped { app_id });
+        stop_app_server(app_id, &slot).map(Some)
+    }
+
+    /// Run the schedule's due entries, if this orchestrator follows it. They
+    /// go through `process_app_command` like a frontend's, and their launches
+    /// are held by the schedule until one of its entries stops them.
+    fn run_schedule(&self) {
+        if !self.follows_schedule.load(Ordering::Relaxed) {
+            return;
+        }
+        let ran = scheduler::run_due(|command| {
+            let mut frames = Vec::new();
+            {
+                let out: Mutex<&mut (dyn Write + Send)> = Mutex::new(&mut frames);
+                process_app_command(command, &Reply::new(0, &out, &Cancels::default()), self);
+            }
+            frames
+        });
+        for (id, ok) in ran {
+            self.events.emit(OrchestratorEvent::ScheduleRan { id, ok });
+        }
+    }
+
+    fn stop_all(&self) {
+        let held: Vec<_> = self.held.lock().unwrap().drain().collect();
+        for (app_id, held) in held {
+            let _ = stop_app_server(app_id, &held.slot);
+            dev_println!("ORCH", "Sent shutdown command to app {app_id}");
+            self.events.emit(OrchestratorEvent::IdleStopped { app_id });
+        }
+    }
+}
+
+fn start_app_server(app_id: u32) -> Result<IpcClient, SamError> {
     let current_exe = get_executable_path();
     let child =
         BidirChild::new(Command::new(current_exe).arg(format!("--app={app_id}"))).map_err(|e| {
             eprintln!("[ORCHESTRATOR] Failed to spawn app server for {app_id}: {e}");
             SamError::SocketCommunicationFailed
+                .app(app_id)
+                .in_process(Process::Orchestrator)
+                .context("Starting the app server")
         })?;
 
     // Probe the child to verify it actually connected to Steam. The app
     // server's connect attempt happens before its main loop runs, so a Status
     // reply distinguishes a healthy child from one that failed to attach (e.g.
     // user-entered AppId they don't own).
-    let mut ipc = IpcClient::new(child);
+    let mut ipc = IpcClient::app_server(child, app_id);
     match ipc.request_response::<bool, _>(&SteamCommand::Status) {
-        Ok(true) => {
-            children_processes.insert(app_id, (ipc, 1));
-            Ok(())
-        }
-        Ok(false) | Err(_) => {
+        Ok(true) => Ok(ipc),
+        answer => {
             dev_println!(
                 "ORCH",
                 "App server for {app_id} failed Steam handshake, tearing down"
             );
             let _ = send_app_command(&mut ipc, SteamCommand::Shutdown);
             let _ = ipc.wait();
-            Err(SamError::SteamConnectionFailed)
+            // The server's own answer says more, when it gave one.
+            let error = answer.err().unwrap_or(SamError::SteamConnectionFailed);
+            Err(error.app(app_id).context("Starting the app server"))
         }
     }
 }
 
+/// Shut a server down once whatever talks to it is done, leaving it `Gone` for
+/// anyone still waiting on its lock.
+fn stop_app_server(app_id: u32, slot: &Slot) -> Result<Vec<u8>, SamError> {
+    let stopped = std::mem::replace(
+        &mut *slot.server.lock().unwrap(),
+        AppServer::Gone(SamError::AppMismatchError),
+    );
+    let mut ipc = match stopped {
+        AppServer::Running(ipc) => ipc,
+        // Down already, with nothing left to stop.
+        AppServer::Exited | AppServer::Idle => {
+            return Ok(frame_tagged(0, &SteamResponse::Success(true)));
+        }
+        AppServer::Starting | AppServer::Gone(_) => return Err(SamError::AppMismatchError),
+    };
+    let response = send_app_command(&mut ipc, SteamCommand::Shutdown).inspect_err(|_| {
+        dev_println!("ORCH", "Error sending shutdown command to app {app_id}");
+    })?;
+    ipc.wait()
+        .expect("[ORCHESTRATOR] Failed to wait child process");
+    Ok(response)
+}
+
 /// Fetch a running child's achievements and stats in a single back-to-back
 /// exchange, so nothing can interleave between the two on the parent channel.
 fn fetch_child_progress(
@@ -188,51 +1078,110 @@ fn fetch_child_progress(
 fn forward_to_child(
     app_id: u32,
     command: SteamCommand,
-    tx: &mut Sender,
-    children_processes: &mut HashMap<u32, (IpcClient, usize)>,
+    tx: &Reply,
+    servers: &AppServers,
     op_name: &str,
 ) {
-    if let Some((ipc, _)) = children_processes.get_mut(&app_id) {
-        match send_app_command(ipc, command) {
-            Ok(response) => send_raw(tx, &response),
-            Err(_) => {
-                dev_println!("ORCH", "Failed to {op_name} for app {app_id}");
-                send_raw(tx, &SOCKET_ERROR_RESPONSE);
+    let stored = stores(&command);
+    match servers.with(app_id, |ipc| send_app_command(ipc, command)) {
+        Ok(Ok(response)) => {
+            if stored && answered_stored(&response) {
+                servers
+                    .events
+                    .emit(OrchestratorEvent::StatsStored { app_id });
             }
+            send_raw(tx, &response);
+        }
+        Ok(Err(_)) => {
+            dev_println!("ORCH", "Failed to {op_name} for app {app_id}");
+            send(tx, &lost(app_id));
         }
-    } else {
-        let _ = write_message(tx, &SteamResponse::<()>::Error(SamError::AppMismatchError));
+        Err(e) => send(tx, &SteamResponse::<()>::Error(e)),
     }
 }
 
-static SOCKET_ERROR_RESPONSE: LazyLock<Vec<u8>> = LazyLock::new(|| {
-    frame_message(&SteamResponse::<()>::Error(
-        SamError::SocketCommunicationFailed,
-    ))
-});
+/// What the frontend hears when the link to `app_id`'s server broke.
+fn lost(app_id: u32) -> SteamResponse<()> {
+    SteamResponse::Error(
+        SamError::SocketCommunicationFailed
+            .app(app_id)
+            .in_process(Process::Orchestrator),
+    )
+}
+
+/// The orchestrator's own Steam connection is missing.
+fn not_connected() -> SamError {
+    SamError::SteamConnectionFailed.in_process(Process::Orchestrator)
+}
 
 /// Children are spawned by us, so they inherit our namespace. Streams a
-/// `ProgressMsg::Progress { done, total }` frame as each child completes, then
+/// `ProgressMsg::Progress` frame as each child completes, then
 /// writes a single terminal `ProgressMsg::Done(SteamResponse::Success(results))`
 /// — the GUI's `request_with_progress` reads frames in a loop until `Done`.
-fn fan_out_streaming<T>(items: Vec<(u32, SteamCommand)>, tx: &mut Sender)
-where
-    T: DeserializeOwned + Serialize,
+/// A `Cancel` for the request cuts the results short at the apps processed.
+///
+/// `succeeded` tells from an app's result whether it did what was asked, and
+/// `stored` whether Steam stored its stats.
+fn fan_out_streaming<T>(
+    items: Vec<(u32, SteamCommand)>,
+    policy: &FanOutPolicy,
+    tx: &Reply,
+    events: &Events,
+    succeeded: fn(&T) -> bool,
+    stored: fn(&T) -> bool,
+) where
+    T: DeserializeOwned + Serialize + Send,
 {
-    let tx_lock = Mutex::new(tx);
-    let progress = |done: usize, total: usize, _app_id: u32| {
-        let mut guard = tx_lock.lock().unwrap();
-        let _ = write_message(&mut **guard, &ProgressMsg::<()>::Progress { done, total });
+    let results = Mutex::new(Vec::<(u32, Result<T, SamError>)>::new());
+    let progress = |done: usize,
+                    total: usize,
+                    app_id: u32,
+                    retries: u32,
+                    outcome: &Result<Vec<u8>, SamError>| {
+        let result = match outcome {
+            Ok(bytes) => parse_response_bytes::<T>(bytes),
+            Err(e) => Err(e.clone()),
+        }
+        .map_err(|e| e.app(app_id));
+        if result.as_ref().is_ok_and(stored) {
+            events.emit(OrchestratorEvent::StatsStored { app_id });
+        }
+        send(
+            tx,
+            &ProgressMsg::<()>::Progress {
+                done,
+                total,
+                app_id,
+                retries,
+                ok: result.as_ref().is_ok_and(succeeded),
+            },
+        );
+        results.lock().unwrap().push((app_id, result));
     };
-    let raw = run_command_on_apps_concurrent(items, MAX_CONCURRENT_APPS, Some(&progress));
-    let results: Vec<(u32, Result<T, SamError>)> = raw
-        .into_iter()
-        .map(|(id, res)| (id, res.and_then(|bytes| parse_response_bytes::<T>(&bytes))))
-        .collect();
-    let tx = tx_lock.into_inner().unwrap();
+    let cancel = tx.cancels.flag(tx.id);
+    run_command_on_apps_concurrent(items, policy, Some(&progress), &cancel);
+    tx.cancels.forget(tx.id);
+    let results = results.into_inner().unwrap();
+    if cancel.load(Ordering::Relaxed) {
+        dev_println!(
+            "ORCH",
+            "Request {} cancelled after {} app(s)",
+            tx.id,
+            results.len()
+        );
+    }
     send(tx, &ProgressMsg::Done(SteamResponse::Success(results)));
 }
 
+/// A bulk fan-out's per-app command, answered with what it replaced, so the
+/// frontend can journal the change as undoable.
+fn snapshot_then(app_id: u32, command: SteamCommand) -> (u32, SteamCommand) {
+    (
+        app_id,
+        SteamCommand::SnapshotThen(app_id, Box::new(command)),
+    )
+}
+
 #[cfg(debug_assertions)]
 fn debug_counts_delay() {
     let Ok(raw) = std::env::var("ACHIEVEMENTS_COUNT_DELAY") else {
@@ -248,47 +1197,13 @@ fn debug_counts_delay() {
     }
 }
 
-fn process_command(
+/// The commands `uses_own_connection` picks out, one at a time on the thread
+/// that owns `connected_steam`.
+fn process_own_command(
     command: SteamCommand,
-    tx: &mut Sender,
-    children_processes: &mut HashMap<u32, (IpcClient, usize)>,
+    tx: &Reply,
     connected_steam: &mut Option<ConnectedSteam>,
-) -> bool {
-    /// One-shot: spawn an ephemeral app server, send `command`, then shut it
-    /// down. Proxies the framed response (or a `SocketCommunicationFailed`
-    /// envelope) back to `tx`. Used for commands the user can issue against
-    /// apps that aren't currently being held open (unlock-all, reset-stats).
-    fn run_ephemeral(tx: &mut Sender, app_id: u32, command: SteamCommand, op_name: &str) {
-        let current_exe = get_executable_path();
-        let child = match BidirChild::new(Command::new(current_exe).arg(format!("--app={app_id}")))
-        {
-            Ok(c) => c,
-            Err(e) => {
-                eprintln!("[ORCHESTRATOR] Failed to spawn app server for {op_name} {app_id}: {e}");
-                send_raw(tx, &SOCKET_ERROR_RESPONSE);
-                return;
-            }
-        };
-        let mut ipc = IpcClient::new(child);
-
-        let response = send_app_command(&mut ipc, command);
-
-        if send_app_command(&mut ipc, SteamCommand::Shutdown).is_err() {
-            dev_println!("ORCH", "Error sending shutdown to {op_name} app {app_id}");
-            send_raw(tx, &SOCKET_ERROR_RESPONSE);
-            return;
-        }
-
-        if let Err(e) = ipc.wait() {
-            eprintln!("[ORCHESTRATOR] Failed to wait on {op_name} app {app_id}: {e}");
-        }
-
-        match response {
-            Ok(resp) => send_raw(tx, &resp),
-            Err(_) => send_raw(tx, &SOCKET_ERROR_RESPONSE),
-        }
-    }
-
+) {
     match command {
         SteamCommand::GetSubscribedAppList(include_playtime, with_achievement_counts) => {
             dev_println!(
@@ -304,11 +1219,8 @@ fn process_command(
             let connected_steam = match ensure_connected(connected_steam) {
                 Ok(cs) => cs,
                 Err(()) => {
-                    send(
-                        tx,
-                        &SteamResponse::<()>::Error(SamError::SteamConnectionFailed),
-                    );
-                    return true;
+                    send(tx, &SteamResponse::<()>::Error(not_connected()));
+                    return;
                 }
             };
 
@@ -345,11 +1257,8 @@ fn process_command(
                 Ok(u) => u,
                 Err(e) => {
                     dev_println!("ORCH", "Could not get IClientUser: {e}");
-                    send(
-                        tx,
-                        &SteamResponse::<()>::Error(SamError::SteamConnectionFailed),
-                    );
-                    return true;
+                    send(tx, &SteamResponse::<()>::Error(not_connected()));
+                    return;
                 }
             };
 
@@ -381,31 +1290,201 @@ fn process_command(
                             Ok(Err(_)) => {
                                 // parse_localconfig already logged via dev_println!
                             }
-                            Err(_) => {
-                                eprintln!("[ORCHESTRATOR] VDF parse thread panicked");
+                            Err(_) => {
+                                eprintln!("[ORCHESTRATOR] VDF parse thread panicked");
+                            }
+                        }
+                    }
+                    send(tx, &SteamResponse::Success(apps));
+                }
+                Err(e) => {
+                    dev_println!("ORCH", "Error getting owned apps: {e}");
+                    send(tx, &SteamResponse::<()>::Error(e));
+                }
+            };
+        }
+
+        // Friends / avatar / persona aren't app-specific (localconfig.vdf + the
+        // global ISteamFriends interface), so the orchestrator serves them from
+        // its own connection instead of forwarding to a child.
+        SteamCommand::GetFriends => {
+            let friends: Result<_, SamError> = match orchestrator_connection(connected_steam) {
+                Ok(cs) => Ok(user_unlock_times::list_friends(&cs.friends)),
+                Err(()) => Err(not_connected()),
+            };
+            send(tx, &SteamResponse::from(friends));
+        }
+
+        SteamCommand::GetCurrentUser => {
+            let steam_id: Result<u64, SamError> = match orchestrator_connection(connected_steam) {
+                Ok(cs) => cs.user.get_steam_id().map(|id| id.m_steamid).map_err(|e| {
+                    eprintln!("[ORCHESTRATOR] Failed to read the current SteamID: {e}");
+                    not_connected()
+                }),
+                Err(()) => Err(not_connected()),
+            };
+            send(tx, &SteamResponse::from(steam_id));
+        }
+
+        SteamCommand::GetUserAvatar(steam_id64) => {
+            let avatar = match orchestrator_connection(connected_steam) {
+                Ok(cs) => user_unlock_times::fetch_user_avatar(&cs.friends, &cs.utils, steam_id64),
+                Err(()) => Err(not_connected()),
+            };
+            send(tx, &SteamResponse::from(avatar));
+        }
+
+        SteamCommand::GetUserPersonaName(steam_id64) => {
+            let name = match orchestrator_connection(connected_steam) {
+                Ok(cs) => user_unlock_times::fetch_user_persona_name(&cs.friends, steam_id64),
+                Err(()) => Err(not_connected()),
+            };
+            send(tx, &SteamResponse::from(name));
+        }
+
+        SteamCommand::GetAchievementCounts(app_ids, force) => {
+            let connected_steam = match ensure_connected(connected_steam) {
+                Ok(cs) => cs,
+                Err(()) => {
+                    send(tx, &SteamResponse::<()>::Error(not_connected()));
+                    return;
+                }
+            };
+
+            let account = connected_steam
+                .user
+                .get_steam_id()
+                .ok()
+                .map(|sid| user_unlock_times::account_id(sid.m_steamid));
+            let local_index = (!force)
+                .then(|| account.and_then(LocalIndex::build))
+                .flatten();
+
+            let mut counts: Vec<(u32, u32, u32)> = Vec::with_capacity(app_ids.len());
+            let mut remaining: Vec<u32> = Vec::new();
+            match local_index.as_ref() {
+                Some(index) => {
+                    for &app_id in &app_ids {
+                        match index.try_read(app_id) {
+                            Some((total, unlocked)) => counts.push((app_id, total, unlocked)),
+                            None => remaining.push(app_id),
+                        }
+                    }
+                }
+                None => remaining = app_ids.clone(),
+            }
+
+            if !remaining.is_empty() {
+                #[cfg(debug_assertions)]
+                debug_counts_delay();
+                let stats_map = match connected_steam.client_user_stats_map() {
+                    Ok(m) => m,
+                    Err(e) => {
+                        dev_println!("ORCH", "Could not create stats map: {e}");
+                        send(tx, &SteamResponse::<()>::Error(not_connected()));
+                        return;
+                    }
+                };
+                counts.extend(fetch_achievement_counts(&stats_map, &remaining));
+
+                // Steam reports an app it could not answer for as zero, and
+                // drops one whose schema never loaded; the files cover both.
+                if force {
+                    let answered: HashSet<u32> = counts.iter().map(|(id, _, _)| *id).collect();
+                    let has_gaps = counts.iter().any(|&(_, total, _)| total == 0)
+                        || remaining.iter().any(|id| !answered.contains(id));
+                    let index = has_gaps
+                        .then(|| account.and_then(LocalIndex::build))
+                        .flatten();
+                    if let Some(index) = index {
+                        for (app_id, total, unlocked) in counts.iter_mut().filter(|c| c.1 == 0) {
+                            if let Some(local) = index.try_read(*app_id) {
+                                dev_println!("ORCH", "app {app_id} unanswered; using local files");
+                                (*total, *unlocked) = local;
+                            }
+                        }
+                        for &app_id in remaining.iter().filter(|id| !answered.contains(id)) {
+                            if let Some((total, unlocked)) = index.try_read(app_id) {
+                                dev_println!("ORCH", "app {app_id} unanswered; using local files");
+                                counts.push((app_id, total, unlocked));
                             }
                         }
                     }
-                    send(tx, &SteamResponse::Success(apps));
                 }
-                Err(e) => {
-                    dev_println!("ORCH", "Error getting owned apps: {e}");
-                    send(tx, &SteamResponse::<()>::Error(e));
+            }
+
+            send(tx, &SteamResponse::Success(counts));
+        }
+
+        other => unreachable!("{other:?} is not served from the orchestrator's own connection"),
+    }
+}
+
+/// Everything but what `uses_own_connection` picks out. Safe to run from many
+/// threads at once.
+fn process_app_command(command: SteamCommand, tx: &Reply, servers: &AppServers) {
+    /// One-shot: spawn an ephemeral app server, send `command`, then shut it
+    /// down. Proxies the framed response (or a `SocketCommunicationFailed`
+    /// envelope) back to `tx`. Used for commands the user can issue against
+    /// apps that aren't currently being held open (unlock-all, reset-stats).
+    fn run_ephemeral(
+        tx: &Reply,
+        app_id: u32,
+        command: SteamCommand,
+        op_name: &str,
+        events: &Events,
+    ) {
+        let current_exe = get_executable_path();
+        let child = match BidirChild::new(Command::new(current_exe).arg(format!("--app={app_id}")))
+        {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("[ORCHESTRATOR] Failed to spawn app server for {op_name} {app_id}: {e}");
+                send(tx, &lost(app_id));
+                return;
+            }
+        };
+        let mut ipc = IpcClient::app_server(child, app_id);
+
+        let stored = stores(&command);
+        let response = send_app_command(&mut ipc, command);
+
+        if send_app_command(&mut ipc, SteamCommand::Shutdown).is_err() {
+            dev_println!("ORCH", "Error sending shutdown to {op_name} app {app_id}");
+            send(tx, &lost(app_id));
+            return;
+        }
+
+        if let Err(e) = ipc.wait() {
+            eprintln!("[ORCHESTRATOR] Failed to wait on {op_name} app {app_id}: {e}");
+        }
+
+        match response {
+            Ok(resp) => {
+                if stored && answered_stored(&resp) {
+                    events.emit(OrchestratorEvent::StatsStored { app_id });
                 }
-            };
+                send_raw(tx, &resp)
+            }
+            Err(_) => send(tx, &lost(app_id)),
         }
+    }
 
+    match command {
         SteamCommand::LaunchApp(app_id) => {
             dev_println!("ORCH", "LaunchApp {}", app_id);
 
             #[cfg(debug_assertions)]
             if app_id == 0 {
                 send(tx, &SteamResponse::<bool>::Success(true));
-                return true;
+                return;
             }
 
-            match ensure_app_launched(app_id, children_processes) {
-                Ok(()) => send(tx, &SteamResponse::Success(true)),
+            match servers.launch(app_id) {
+                Ok(()) => {
+                    tx.took(app_id);
+                    send(tx, &SteamResponse::Success(true))
+                }
                 Err(e) => send(tx, &SteamResponse::<bool>::Error(e)),
             }
         }
@@ -427,27 +1506,23 @@ fn process_command(
                         languages: vec![],
                     }),
                 );
-                return true;
+                return;
             }
 
-            if launch && let Err(e) = ensure_app_launched(app_id, children_processes) {
-                send(tx, &SteamResponse::<AppProgress>::Error(e));
-                return true;
+            if launch {
+                if let Err(e) = servers.launch(app_id) {
+                    send(tx, &SteamResponse::<AppProgress>::Error(e));
+                    return;
+                }
+                tx.took(app_id);
             }
 
-            // One orchestrator command holds the channel for both fetches, so no
-            // other command can wedge in between them.
-            let Some((ipc, _)) = children_processes.get_mut(&app_id) else {
-                send(
-                    tx,
-                    &SteamResponse::<AppProgress>::Error(SamError::AppMismatchError),
-                );
-                return true;
-            };
-
-            match fetch_child_progress(ipc, app_id, &language) {
-                Ok(progress) => send(tx, &SteamResponse::Success(progress)),
-                Err(_) => send_raw(tx, &SOCKET_ERROR_RESPONSE),
+            // One exchange holds the app server for both fetches, so no other
+            // command can wedge in between them.
+            match servers.with(app_id, |ipc| fetch_child_progress(ipc, app_id, &language)) {
+                Ok(Ok(progress)) => send(tx, &SteamResponse::Success(progress)),
+                Ok(Err(_)) => send(tx, &lost(app_id)),
+                Err(e) => send(tx, &SteamResponse::<AppProgress>::Error(e)),
             }
         }
 
@@ -455,70 +1530,29 @@ fn process_command(
             #[cfg(debug_assertions)]
             if app_id == 0 {
                 send(tx, &SteamResponse::<bool>::Success(true));
-                return true;
+                return;
             }
 
-            let Some((_, refcount)) = children_processes.get_mut(&app_id) else {
-                eprintln!("[ORCHESTRATOR] App {} is not running", app_id);
-                send(tx, &SteamResponse::<()>::Error(SamError::UnknownError));
-                return true;
-            };
-
-            *refcount -= 1;
-            if *refcount > 0 {
-                dev_println!(
-                    "ORCH",
-                    "App {} still wanted, refcount now {}",
-                    app_id,
-                    *refcount
-                );
-                send(tx, &SteamResponse::Success(true));
-                return true;
-            }
-
-            // Refcount hit zero — actually shut the process down.
-            let mut ipc_opt = children_processes.remove(&app_id).map(|(b, _)| b);
-            let ipc = ipc_opt.as_mut().unwrap();
-            let response = match send_app_command(ipc, SteamCommand::Shutdown) {
-                Ok(response) => response,
-                Err(_) => {
-                    dev_println!("ORCH", "Error sending shutdown command to app {app_id}");
-                    send_raw(tx, &SOCKET_ERROR_RESPONSE);
-                    return true;
-                }
-            };
-
-            ipc.wait()
-                .expect("[ORCHESTRATOR] Failed to wait child process");
-
-            send_raw(tx, &response);
+            let released = servers.release(app_id);
+            if released.is_ok() {
+                tx.dropped(app_id);
+            }
+            match released {
+                Ok(Some(response)) => send_raw(tx, &response),
+                Ok(None) => send(tx, &SteamResponse::Success(true)),
+                Err(e) => send(tx, &SteamResponse::<()>::Error(e)),
+            }
         }
 
         SteamCommand::StopApps => {
             dev_println!("ORCH", "StopApps");
-
-            for (app_id, (ipc, _)) in children_processes.iter_mut() {
-                let _ = send_app_command(ipc, SteamCommand::Shutdown);
-                dev_println!("ORCH", "Sent shutdown command to app {app_id}");
-                ipc.wait()
-                    .expect("[ORCHESTRATOR] Failed to wait child process");
-            }
-
-            children_processes.clear();
-
+            servers.stop_all();
             send(tx, &SteamResponse::Success(true));
         }
 
         SteamCommand::Shutdown => {
-            for (app_id, (ipc, _)) in children_processes.iter_mut() {
-                let _ = send_app_command(ipc, SteamCommand::Shutdown);
-                dev_println!("ORCH", "Sent shutdown command to app {app_id}");
-                ipc.wait()
-                    .expect("[ORCHESTRATOR] Failed to wait child process");
-            }
-
+            servers.stop_all();
             send(tx, &SteamResponse::Success(true));
-            return false;
         }
 
         SteamCommand::Status => {
@@ -526,8 +1560,29 @@ fn process_command(
         }
 
         SteamCommand::GetRunningApps => {
-            let running: Vec<u32> = children_processes.keys().copied().collect();
-            send(tx, &SteamResponse::Success(running));
+            send(tx, &SteamResponse::Success(servers.running()));
+        }
+
+        SteamCommand::GetAppServers => {
+            send(tx, &SteamResponse::Success(servers.statuses()));
+        }
+
+        // Picked up at the next sweep.
+        SteamCommand::FollowSchedule => {
+            servers.follows_schedule.store(true, Ordering::Relaxed);
+            send(tx, &SteamResponse::Success(true));
+        }
+
+        // Only a daemon has other windows to hand it to.
+        SteamCommand::OpenApp(_) => {
+            send(tx, &SteamResponse::Success(false));
+        }
+
+        // Holds its thread until the orchestrator winds down.
+        SteamCommand::SubscribeEvents(_) => {
+            for event in servers.events.subscribe() {
+                send(tx, &event);
+            }
         }
 
         SteamCommand::GetAchievements(app_id, language) => {
@@ -545,6 +1600,7 @@ fn process_command(
                         icon_locked: "".to_string(),
                         icon_normal: "".to_string(),
                         unlock_time: None,
+                        is_hidden: false,
                     };
                     ach_infos.push(ach_info);
                 }
@@ -553,14 +1609,14 @@ fn process_command(
                     tx,
                     &SteamResponse::<Vec<AchievementInfo>>::Success(ach_infos),
                 );
-                return true;
+                return;
             }
 
             forward_to_child(
                 app_id,
                 SteamCommand::GetAchievements(app_id, language),
                 tx,
-                children_processes,
+                servers,
                 "load achievements",
             );
         }
@@ -569,14 +1625,14 @@ fn process_command(
             #[cfg(debug_assertions)]
             if app_id == 0 {
                 send(tx, &SteamResponse::<Vec<StatInfo>>::Success(vec![]));
-                return true;
+                return;
             }
 
             forward_to_child(
                 app_id,
                 SteamCommand::GetStats(app_id, language),
                 tx,
-                children_processes,
+                servers,
                 "load stats",
             );
         }
@@ -584,15 +1640,21 @@ fn process_command(
         SteamCommand::SetAchievement(app_id, unlocked, achievement_id, store) => {
             #[cfg(debug_assertions)]
             if app_id == 0 {
-                send(tx, &SteamResponse::<bool>::Success(true));
-                return true;
+                send(
+                    tx,
+                    &SteamResponse::Success(Stored {
+                        result: true,
+                        granted: Vec::new(),
+                    }),
+                );
+                return;
             }
 
             forward_to_child(
                 app_id,
                 SteamCommand::SetAchievement(app_id, unlocked, achievement_id, store),
                 tx,
-                children_processes,
+                servers,
                 "set achievement",
             );
         }
@@ -602,7 +1664,7 @@ fn process_command(
                 app_id,
                 SteamCommand::GetFriendUnlockTimes(app_id, friend),
                 tx,
-                children_processes,
+                servers,
                 "get friend unlock times",
             );
         }
@@ -612,101 +1674,70 @@ fn process_command(
                 app_id,
                 SteamCommand::GetFriendAchievementCount(app_id, steam_id64),
                 tx,
-                children_processes,
+                servers,
                 "get friend achievement count",
             );
         }
 
-        // Friends / avatar / persona aren't app-specific (localconfig.vdf + the
-        // global ISteamFriends interface), so the orchestrator serves them from
-        // its own connection instead of forwarding to a child.
-        SteamCommand::GetFriends => {
-            let friends: Result<_, SamError> = match orchestrator_connection(connected_steam) {
-                Ok(cs) => Ok(user_unlock_times::list_friends(&cs.friends)),
-                Err(()) => Err(SamError::SteamConnectionFailed),
-            };
-            send(tx, &SteamResponse::from(friends));
-        }
-
-        SteamCommand::GetCurrentUser => {
-            let steam_id: Result<u64, SamError> = match orchestrator_connection(connected_steam) {
-                Ok(cs) => cs.user.get_steam_id().map(|id| id.m_steamid).map_err(|e| {
-                    eprintln!("[ORCHESTRATOR] Failed to read the current SteamID: {e}");
-                    SamError::SteamConnectionFailed
-                }),
-                Err(()) => Err(SamError::SteamConnectionFailed),
-            };
-            send(tx, &SteamResponse::from(steam_id));
-        }
-
-        SteamCommand::GetUserAvatar(steam_id64) => {
-            let avatar = match orchestrator_connection(connected_steam) {
-                Ok(cs) => user_unlock_times::fetch_user_avatar(&cs.friends, &cs.utils, steam_id64),
-                Err(()) => Err(SamError::SteamConnectionFailed),
-            };
-            send(tx, &SteamResponse::from(avatar));
-        }
-
-        SteamCommand::GetUserPersonaName(steam_id64) => {
-            let name = match orchestrator_connection(connected_steam) {
-                Ok(cs) => user_unlock_times::fetch_user_persona_name(&cs.friends, steam_id64),
-                Err(()) => Err(SamError::SteamConnectionFailed),
-            };
-            send(tx, &SteamResponse::from(name));
-        }
-
         SteamCommand::UnlockAllAchievements(app_id) => {
             run_ephemeral(
                 tx,
                 app_id,
                 SteamCommand::UnlockAllAchievements(app_id),
                 "unlock-all",
+                &servers.events,
             );
         }
 
         SteamCommand::StoreStatsAndAchievements(app_id) => {
             #[cfg(debug_assertions)]
             if app_id == 0 {
-                send(tx, &SteamResponse::<bool>::Success(true));
-                return true;
+                send(
+                    tx,
+                    &SteamResponse::Success(Stored {
+                        result: true,
+                        granted: Vec::new(),
+                    }),
+                );
+                return;
             }
 
             forward_to_child(
                 app_id,
                 SteamCommand::StoreStatsAndAchievements(app_id),
                 tx,
-                children_processes,
+                servers,
                 "store stats",
             );
         }
 
-        SteamCommand::SetIntStat(app_id, stat_id, value) => {
+        SteamCommand::SetIntStat(app_id, stat_id, value, store) => {
             forward_to_child(
                 app_id,
-                SteamCommand::SetIntStat(app_id, stat_id, value),
+                SteamCommand::SetIntStat(app_id, stat_id, value, store),
                 tx,
-                children_processes,
+                servers,
                 "set int stat",
             );
         }
 
-        SteamCommand::SetFloatStat(app_id, stat_id, value) => {
+        SteamCommand::SetFloatStat(app_id, stat_id, value, store) => {
             forward_to_child(
                 app_id,
-                SteamCommand::SetFloatStat(app_id, stat_id, value),
+                SteamCommand::SetFloatStat(app_id, stat_id, value, store),
                 tx,
-                children_processes,
+                servers,
                 "set float stat",
             );
         }
 
         SteamCommand::ResetStats(app_id, achievements_too) => {
-            if children_processes.contains_key(&app_id) {
+            if servers.is_running(app_id) {
                 forward_to_child(
                     app_id,
                     SteamCommand::ResetStats(app_id, achievements_too),
                     tx,
-                    children_processes,
+                    servers,
                     "reset stats",
                 );
             } else {
@@ -715,134 +1746,210 @@ fn process_command(
                     app_id,
                     SteamCommand::ResetStats(app_id, achievements_too),
                     "reset-stats",
+                    &servers.events,
                 );
             }
         }
 
-        SteamCommand::GetAchievementCounts(app_ids, force) => {
-            let connected_steam = match ensure_connected(connected_steam) {
-                Ok(cs) => cs,
-                Err(()) => {
-                    send(
-                        tx,
-                        &SteamResponse::<()>::Error(SamError::SteamConnectionFailed),
-                    );
-                    return true;
-                }
-            };
-
-            let account = connected_steam
-                .user
-                .get_steam_id()
-                .ok()
-                .map(|sid| user_unlock_times::account_id(sid.m_steamid));
-            let local_index = (!force)
-                .then(|| account.and_then(LocalIndex::build))
-                .flatten();
-
-            let mut counts: Vec<(u32, u32, u32)> = Vec::with_capacity(app_ids.len());
-            let mut remaining: Vec<u32> = Vec::new();
-            match local_index.as_ref() {
-                Some(index) => {
-                    for &app_id in &app_ids {
-                        match index.try_read(app_id) {
-                            Some((total, unlocked)) => counts.push((app_id, total, unlocked)),
-                            None => remaining.push(app_id),
-                        }
-                    }
-                }
-                None => remaining = app_ids.clone(),
-            }
-
-            if !remaining.is_empty() {
-                #[cfg(debug_assertions)]
-                debug_counts_delay();
-                let stats_map = match connected_steam.client_user_stats_map() {
-                    Ok(m) => m,
-                    Err(e) => {
-                        dev_println!("ORCH", "Could not create stats map: {e}");
-                        send(
-                            tx,
-                            &SteamResponse::<()>::Error(SamError::SteamConnectionFailed),
-                        );
-                        return true;
-                    }
-                };
-                counts.extend(fetch_achievement_counts(&stats_map, &remaining));
-
-                // Steam reports an app it could not answer for as zero, and
-                // drops one whose schema never loaded; the files cover both.
-                if force {
-                    let answered: HashSet<u32> = counts.iter().map(|(id, _, _)| *id).collect();
-                    let has_gaps = counts.iter().any(|&(_, total, _)| total == 0)
-                        || remaining.iter().any(|id| !answered.contains(id));
-                    let index = has_gaps
-                        .then(|| account.and_then(LocalIndex::build))
-                        .flatten();
-                    if let Some(index) = index {
-                        for (app_id, total, unlocked) in counts.iter_mut().filter(|c| c.1 == 0) {
-                            if let Some(local) = index.try_read(*app_id) {
-                                dev_println!("ORCH", "app {app_id} unanswered; using local files");
-                                (*total, *unlocked) = local;
-                            }
-                        }
-                        for &app_id in remaining.iter().filter(|id| !answered.contains(id)) {
-                            if let Some((total, unlocked)) = index.try_read(app_id) {
-                                dev_println!("ORCH", "app {app_id} unanswered; using local files");
-                                counts.push((app_id, total, unlocked));
-                            }
-                        }
-                    }
-                }
-            }
-
-            send(tx, &SteamResponse::Success(counts));
-        }
-
-        SteamCommand::ExportApps(app_ids) => {
+        SteamCommand::ExportApps(app_ids, policy) => {
             dev_println!("ORCH", "ExportApps {:?}", app_ids);
             let items = app_ids
                 .into_iter()
                 .map(|id| (id, SteamCommand::ExportAppProgress(id)))
                 .collect();
-            fan_out_streaming::<AppExport>(items, tx);
+            fan_out_streaming::<AppExport>(
+                items,
+                &policy,
+                tx,
+                &servers.events,
+                |_| true,
+                |_| false,
+            );
         }
 
-        SteamCommand::ImportApps(apps) => {
+        SteamCommand::ImportApps(apps, policy) => {
             dev_println!("ORCH", "ImportApps ({} apps)", apps.len());
             let items = apps
                 .into_iter()
-                .map(|a| (a.app_id, SteamCommand::ImportAppProgress(a.app_id, a)))
+                .map(|a| snapshot_then(a.app_id, SteamCommand::ImportAppProgress(a.app_id, a)))
                 .collect();
-            fan_out_streaming::<ImportSummary>(items, tx);
+            fan_out_streaming(
+                items,
+                &policy,
+                tx,
+                &servers.events,
+                |summary: &Snapshotted<ImportSummary>| summary.result.errors.is_empty(),
+                |summary: &Snapshotted<ImportSummary>| {
+                    summary.result.achievements_applied + summary.result.stats_applied > 0
+                },
+            );
         }
 
-        SteamCommand::UnlockAllApps(app_ids) => {
+        SteamCommand::UnlockAllApps(app_ids, policy) => {
             dev_println!("ORCH", "UnlockAllApps {:?}", app_ids);
             let items = app_ids
                 .into_iter()
-                .map(|id| (id, SteamCommand::UnlockAllAchievements(id)))
+                .map(|id| snapshot_then(id, SteamCommand::UnlockAllAchievements(id)))
                 .collect();
-            fan_out_streaming::<bool>(items, tx);
+            fan_out_streaming(
+                items,
+                &policy,
+                tx,
+                &servers.events,
+                |stored: &Snapshotted<bool>| stored.result,
+                |stored: &Snapshotted<bool>| stored.result,
+            );
         }
 
-        SteamCommand::ResetApps(app_ids, achievements_too) => {
+        SteamCommand::ResetApps(app_ids, achievements_too, policy) => {
             dev_println!("ORCH", "ResetApps {:?}", app_ids);
             let items = app_ids
                 .into_iter()
-                .map(|id| (id, SteamCommand::ResetStats(id, achievements_too)))
+                .map(|id| snapshot_then(id, SteamCommand::ResetStats(id, achievements_too)))
                 .collect();
-            fan_out_streaming::<bool>(items, tx);
+            fan_out_streaming(
+                items,
+                &policy,
+                tx,
+                &servers.events,
+                |stored: &Snapshotted<bool>| stored.result,
+                |stored: &Snapshotted<bool>| stored.result,
+            );
+        }
+
+        SteamCommand::Cancel(request) => {
+            dev_println!("ORCH", "Cancel {request}");
+            send(tx, &SteamResponse::Success(tx.cancels.cancel(request)));
         }
 
         // Child-only commands. The orchestrator dispatches these to app
         // server children via `run_command_on_apps_concurrent`; receiving
         // one here means a caller mistakenly addressed the orchestrator.
-        SteamCommand::ExportAppProgress(_) | SteamCommand::ImportAppProgress(_, _) => {
+        SteamCommand::ExportAppProgress(_)
+        | SteamCommand::ImportAppProgress(_, _)
+        | SteamCommand::SnapshotThen(..) => {
             dev_println!("ORCH", "Received child-only command");
-            send_raw(tx, &SOCKET_ERROR_RESPONSE);
+            let error = SamError::SocketCommunicationFailed.in_process(Process::Orchestrator);
+            send(tx, &SteamResponse::<()>::Error(error));
         }
-    };
+        SteamCommand::GetSubscribedAppList(..)
+        | SteamCommand::GetAchievementCounts(..)
+        | SteamCommand::GetFriends
+        | SteamCommand::GetCurrentUser
+        | SteamCommand::GetUserAvatar(_)
+        | SteamCommand::GetUserPersonaName(_) => {
+            unreachable!("served from the orchestrator's own connection")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_cancel_lands_only_on_bulk_requests_still_running() {
+        let cancels = Cancels::default();
+        cancels.track(
+            7,
+            &SteamCommand::UnlockAllApps(vec![480], FanOutPolicy::default()),
+        );
+        cancels.track(8, &SteamCommand::LaunchApp(480));
+
+        // Read, but not yet picked up by its thread.
+        assert!(cancels.cancel(7));
+        assert!(!cancels.cancel(8));
+        assert!(cancels.flag(7).load(Ordering::Relaxed));
 
-    true
+        cancels.forget(7);
+        assert!(!cancels.cancel(7));
+    }
+
+    #[test]
+    fn the_pool_runs_no_more_than_its_workers_at_once() {
+        use std::sync::atomic::AtomicUsize;
+        let (running, peak, done) = (
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        );
+        thread::scope(|scope| {
+            let jobs = worker_pool(|worker| {
+                scope.spawn(worker);
+            });
+            for _ in 0..WORKERS * 3 {
+                let (running, peak, done) = (&running, &peak, &done);
+                let _ = jobs.send(Box::new(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(5));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    done.fetch_add(1, Ordering::SeqCst);
+                }));
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= WORKERS);
+        assert_eq!(done.load(Ordering::SeqCst), WORKERS * 3);
+    }
+
+    #[test]
+    fn a_session_is_charged_only_for_its_own_launches() {
+        let holds = Holds::default();
+        holds.took(480);
+
+        // A stop for an app it never launched takes nothing from it.
+        holds.dropped(10);
+        assert_eq!(holds.0.lock().unwrap().get(&480), Some(&1));
+
+        // Stopped for everyone by someone else.
+        holds.retain_running(&HashMap::new());
+        assert!(holds.take().is_empty());
+
+        holds.took(480);
+        holds.took(480);
+        holds.dropped(480);
+        assert_eq!(holds.take(), HashMap::from([(480, 1)]));
+    }
+
+    #[test]
+    fn a_server_that_went_down_keeps_its_holds_until_the_last_release() {
+        let servers = AppServers {
+            held: Mutex::default(),
+            idle_timeout: None,
+            events: Events::default(),
+            follows_schedule: AtomicBool::new(false),
+        };
+        let (follower, events) = mpsc::channel();
+        servers.events.followers.lock().unwrap().push(follower);
+        let slot = Arc::new(Slot::new());
+        let crash = SamError::SocketCommunicationFailed.app(480);
+        slot.went_down(
+            &mut slot.server.lock().unwrap(),
+            AppServer::Exited,
+            Some(crash.clone()),
+        );
+        servers
+            .held
+            .lock()
+            .unwrap()
+            .insert(480, Held { slot, refcount: 2 });
+
+        let status = &servers.statuses()[0];
+        assert_eq!((status.holders, status.pid), (2, None));
+        assert_eq!(status.last_error, Some(crash));
+
+        assert_eq!(servers.release(480), Ok(None));
+        assert!(events.try_recv().is_err(), "still held once");
+        let stopped = servers.release(480).unwrap().unwrap();
+        assert_eq!(
+            events.try_recv(),
+            Ok(OrchestratorEvent::IdleStopped { app_id: 480 })
+        );
+        assert_eq!(
+            parse_response_bytes::<bool>(&stopped),
+            Ok(true),
+            "nothing was left to stop"
+        );
+        assert!(servers.statuses().is_empty());
+    }
 }
@@ -13,9 +13,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-//! Frontend-agnostic client for talking to the orchestrator process. Both the
-//! GUI and the CLI hold a single orchestrator over IPC and drive it through the
-//! typed `Request` trait below, so neither frontend loads `steamclient.so`
+//! Frontend-agnostic client for talking to the orchestrator process. The GUI,
+//! the CLI and the TUI each hold a single orchestrator over IPC and drive it
+//! through the typed `Request` trait below, so no frontend loads `steamclient.so`
 //! itself — the orchestrator (and the children it spawns) own every Steam
 //! connection.
 
@@ -27,10 +27,13 @@ use crate::dev_println;
 use crate::utils::app_paths::get_executable_path;
 #[cfg(feature = "gui")]
 use crate::utils::bidir_child::BidirChild;
-use crate::utils::ipc_client::IpcClient;
+use crate::utils::ipc_client::{IpcClient, SharedClient};
 use crate::utils::ipc_types::{
-    AppExport, ImportSummary, ProgressMsg, SamError, SteamCommand, SteamResponse,
+    AppExport, AppServerStatus, FanOutPolicy, ImportSummary, OrchestratorEvent, SamError,
+    Snapshotted, SteamCommand, Stored,
 };
+#[cfg(any(feature = "cli", feature = "gui"))]
+use crate::utils::ipc_types::{ProgressMsg, SteamResponse};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -38,21 +41,47 @@ use std::fmt::Debug;
 use std::path::PathBuf;
 #[cfg(feature = "gui")]
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, mpsc};
 
-/// The frontend's handle to the orchestrator IPC. Structurally a `Mutex` (not an
-/// `RwLock`) because every caller needs `&mut IpcClient` to drive a request —
-/// there are no concurrent readers to optimize for.
-pub static ORCHESTRATOR: Mutex<Option<IpcClient>> = Mutex::new(None);
+/// The frontend's handle to the orchestrator IPC. Locked only long enough to
+/// take a reference: requests from several threads are in flight together, each
+/// answered under its own id.
+pub static ORCHESTRATOR: Mutex<Option<Arc<SharedClient>>> = Mutex::new(None);
 
 pub fn set_orchestrator(ipc: IpcClient) {
-    *ORCHESTRATOR.lock().unwrap() = Some(ipc);
+    *ORCHESTRATOR.lock().unwrap() = Some(Arc::new(ipc.shared()));
+}
+
+fn orchestrator() -> Result<Arc<SharedClient>, SamError> {
+    ORCHESTRATOR.lock().unwrap().clone().ok_or_else(|| {
+        eprintln!("[CLIENT] No orchestrator process");
+        SamError::SocketCommunicationFailed
+    })
+}
+
+/// Attach to a running `--daemon` instead of spawning a private orchestrator.
+/// Always `false` where there is no daemon mode, and when the IPC is to be
+/// recorded or replayed, which only a private orchestrator does.
+pub fn attach_to_daemon() -> bool {
+    #[cfg(target_os = "linux")]
+    if !crate::utils::ipc_recording::wanted()
+        && let Some(stream) = crate::backend::daemon::connect()
+    {
+        dev_println!("CLIENT", "Attached to the orchestrator daemon");
+        set_orchestrator(IpcClient::attached(stream));
+        return true;
+    }
+    false
 }
 
 /// Spawn the orchestrator and install it as the global IPC handle. `chosen` pins
 /// the Steam install via `SAM_STEAM_INSTALL_ROOT`; `None` uses the locator default.
 #[cfg(feature = "gui")]
 pub fn spawn_orchestrator(chosen: Option<PathBuf>) -> Result<(), SamError> {
+    // The daemon has its own idea of which Steam to use.
+    if chosen.is_none() && attach_to_daemon() {
+        return Ok(());
+    }
     let mut command = Command::new(get_executable_path());
     command.arg("--orchestrator");
     if let Some(root) = chosen.as_ref() {
@@ -71,13 +100,39 @@ pub fn spawn_orchestrator(chosen: Option<PathBuf>) -> Result<(), SamError> {
     }
 }
 
+/// Replace the orchestrator with the one `spawn` starts, holding every app the
+/// old one held as many times: open games stay open and idles keep idling. For
+/// `OrchestratorEvent::Stranded`. A daemon is left alone, as other frontends
+/// share it.
+#[cfg(any(feature = "gui", feature = "tui"))]
+pub fn respawn_orchestrator(spawn: impl FnOnce() -> Result<(), SamError>) -> Result<(), SamError> {
+    let ipc = orchestrator()?;
+    if ipc.is_daemon() {
+        eprintln!("[CLIENT] Not restarting the daemon; restart it to reach Steam");
+        return Err(SamError::SteamConnectionFailed);
+    }
+    drop(ipc);
+    let held = GetAppServers.request()?;
+    shutdown_and_wait();
+    spawn()?;
+    for server in held {
+        let app_id = server.app_id;
+        for _ in 0..server.holders {
+            if let Err(e) = (LaunchApp { app_id }).request() {
+                eprintln!("[CLIENT] Failed to reopen app {app_id}: {e}");
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Tolerates an already-broken orchestrator pipe (e.g. Flatpak Steam quit and
 /// took the namespace down with it) — errors are logged, not fatal.
 pub fn shutdown_and_wait() {
     if let Err(err) = Shutdown.request() {
         eprintln!("[CLIENT] Failed to send shutdown message: {err}");
     }
-    if let Some(ipc) = ORCHESTRATOR.lock().unwrap().as_mut()
+    if let Some(ipc) = ORCHESTRATOR.lock().unwrap().as_ref()
         && let Err(err) = ipc.wait()
     {
         eprintln!("[CLIENT] Failed to wait on orchestrator to shut down: {err}");
@@ -96,12 +151,7 @@ pub trait Request: Into<SteamCommand> + Debug + Clone {
     type Response: DeserializeOwned;
 
     fn request(self) -> Result<Self::Response, SamError> {
-        let mut guard = ORCHESTRATOR.lock().unwrap();
-        let Some(ipc) = guard.as_mut() else {
-            eprintln!("[CLIENT] No orchestrator process");
-            return Err(SamError::SocketCommunicationFailed);
-        };
-
+        let ipc = orchestrator()?;
         let command: SteamCommand = self.into();
         dev_println!("CLIENT", "Sending command: {:?}", command);
         ipc.request_response::<Self::Response, _>(&command)
@@ -111,29 +161,155 @@ pub trait Request: Into<SteamCommand> + Debug + Clone {
     /// frames into `on_progress(done, total)` until the terminal
     /// `ProgressMsg::Done(SteamResponse<Self::Response>)` arrives. Non-bulk
     /// commands should keep using `request()`
-    fn request_with_progress<F>(self, mut on_progress: F) -> Result<Self::Response, SamError>
+    #[cfg(feature = "gui")]
+    fn request_with_progress<F>(self, on_progress: F) -> Result<Self::Response, SamError>
     where
         F: FnMut(usize, usize),
     {
-        let mut guard = ORCHESTRATOR.lock().unwrap();
-        let Some(ipc) = guard.as_mut() else {
-            eprintln!("[CLIENT] No orchestrator process");
-            return Err(SamError::SocketCommunicationFailed);
-        };
+        self.request_cancellable(&Canceller::default(), on_progress)
+    }
+
+    /// `request_with_progress`, stoppable from another thread through
+    /// `canceller`. A cancelled request still ends in `Done`, whose results
+    /// cover only the apps the orchestrator got to.
+    #[cfg(feature = "gui")]
+    fn request_cancellable<F>(
+        self,
+        canceller: &Canceller,
+        mut on_progress: F,
+    ) -> Result<Self::Response, SamError>
+    where
+        F: FnMut(usize, usize),
+    {
+        self.request_stepped(canceller, |step| on_progress(step.done, step.total))
+    }
 
+    /// `request_cancellable`, telling `on_step` which app finished each time,
+    /// whether it worked and how many retries it took.
+    #[cfg(any(feature = "cli", feature = "gui"))]
+    fn request_stepped<F>(
+        self,
+        canceller: &Canceller,
+        mut on_step: F,
+    ) -> Result<Self::Response, SamError>
+    where
+        F: FnMut(Step),
+    {
+        let ipc = orchestrator()?;
         let command: SteamCommand = self.into();
         dev_println!("CLIENT", "Sending streaming command: {:?}", command);
-        ipc.send(&command)?;
+        let call = ipc.call(&command)?;
+        canceller.started(call.id());
         loop {
-            let msg: ProgressMsg<SteamResponse<Self::Response>> = ipc.recv()?;
+            let msg: ProgressMsg<SteamResponse<Self::Response>> = call.recv()?;
             match msg {
-                ProgressMsg::Progress { done, total } => on_progress(done, total),
+                ProgressMsg::Progress {
+                    done,
+                    total,
+                    app_id,
+                    retries,
+                    ok,
+                } => on_step(Step {
+                    done,
+                    total,
+                    app_id,
+                    retries,
+                    ok,
+                }),
                 ProgressMsg::Done(resp) => return resp.into(),
             }
         }
     }
 }
 
+/// An app of a bulk request finished: the `done`th of `total`.
+#[cfg(any(feature = "cli", feature = "gui"))]
+#[derive(Debug, Clone, Copy)]
+pub struct Step {
+    pub done: usize,
+    pub total: usize,
+    pub app_id: u32,
+    pub retries: u32,
+    /// Unless it failed, or Steam did not store it.
+    pub ok: bool,
+}
+
+/// Stops a bulk request started with `request_cancellable`. Cancelling before
+/// the request goes out stops it as soon as it does.
+#[cfg(any(feature = "cli", feature = "gui"))]
+#[derive(Clone, Default)]
+pub struct Canceller(Arc<Mutex<CancelState>>);
+
+#[cfg(any(feature = "cli", feature = "gui"))]
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    request: Option<u32>,
+}
+
+#[cfg(any(feature = "cli", feature = "gui"))]
+impl Canceller {
+    pub fn cancel(&self) {
+        let mut state = self.0.lock().unwrap();
+        if !state.cancelled {
+            state.cancelled = true;
+            if let Some(request) = state.request {
+                send_cancel(request);
+            }
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.lock().unwrap().cancelled
+    }
+
+    fn started(&self, request: u32) {
+        let mut state = self.0.lock().unwrap();
+        state.request = Some(request);
+        if state.cancelled {
+            send_cancel(request);
+        }
+    }
+}
+
+/// Not waited on: the request's own `Done` is the answer that matters.
+#[cfg(any(feature = "cli", feature = "gui"))]
+fn send_cancel(request: u32) {
+    dev_println!("CLIENT", "Cancelling request {request}");
+    if let Err(e) =
+        orchestrator().and_then(|ipc| ipc.call(&SteamCommand::Cancel(request)).map(drop))
+    {
+        eprintln!("[CLIENT] Failed to cancel request {request}: {e}");
+    }
+}
+
+/// What the orchestrator reports as it happens, starting with how Steam is
+/// doing. The channel closes when the orchestrator goes away. A window passes
+/// `opens_apps`, to be handed the daemon's `OpenApp`s.
+pub fn subscribe_events(opens_apps: bool) -> mpsc::Receiver<OrchestratorEvent> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let ipc = match orchestrator() {
+            Ok(ipc) => ipc,
+            Err(_) => return,
+        };
+        let call = match ipc.call(&SteamCommand::SubscribeEvents(opens_apps)) {
+            Ok(call) => call,
+            Err(e) => {
+                eprintln!("[CLIENT] Failed to follow orchestrator events: {e}");
+                return;
+            }
+        };
+        while let Ok(event) = call.recv::<OrchestratorEvent>() {
+            dev_println!("CLIENT", "Event: {event:?}");
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
+
 /// Declares a request type: the struct, its `Request` impl (response type),
 /// and the `From<X> for SteamCommand` mapping. Two forms — unit, and struct
 /// with fields. The `=> SteamCommand::...` expression sees the struct's fields
@@ -171,27 +347,30 @@ macro_rules! request {
 
 request!(Shutdown -> bool => SteamCommand::Shutdown);
 request!(GetRunningApps -> Vec<u32> => SteamCommand::GetRunningApps);
+request!(GetAppServers -> Vec<AppServerStatus> => SteamCommand::GetAppServers);
+request!(FollowSchedule -> bool => SteamCommand::FollowSchedule);
 
 request!(GetSubscribedAppList { include_playtime: bool, with_achievement_counts: bool }
     -> Vec<AppModel>
     => SteamCommand::GetSubscribedAppList(include_playtime, with_achievement_counts));
 
 request!(LaunchApp { app_id: u32 } -> bool => SteamCommand::LaunchApp(app_id));
+request!(OpenApp { app_id: u32 } -> bool => SteamCommand::OpenApp(app_id));
 request!(StopApp { app_id: u32 } -> bool => SteamCommand::StopApp(app_id));
 
 request!(SetAchievement { app_id: u32, achievement_id: String, unlocked: bool, store: bool }
-    -> bool
+    -> Stored<bool>
     => SteamCommand::SetAchievement(app_id, unlocked, achievement_id, store));
 
-request!(StoreStatsAndAchievements { app_id: u32 } -> bool
+request!(StoreStatsAndAchievements { app_id: u32 } -> Stored<bool>
     => SteamCommand::StoreStatsAndAchievements(app_id));
 request!(UnlockAllAchievements { app_id: u32 } -> bool
     => SteamCommand::UnlockAllAchievements(app_id));
 
-request!(SetIntStat { app_id: u32, stat_id: String, value: i32 } -> bool
-    => SteamCommand::SetIntStat(app_id, stat_id, value));
-request!(SetFloatStat { app_id: u32, stat_id: String, value: f32 } -> bool
-    => SteamCommand::SetFloatStat(app_id, stat_id, value));
+request!(SetIntStat { app_id: u32, stat_id: String, value: i32, store: bool } -> Stored<bool>
+    => SteamCommand::SetIntStat(app_id, stat_id, value, store));
+request!(SetFloatStat { app_id: u32, stat_id: String, value: f32, store: bool } -> Stored<bool>
+    => SteamCommand::SetFloatStat(app_id, stat_id, value, store));
 
 request!(ResetStats { app_id: u32, achievements_too: bool } -> bool
     => SteamCommand::ResetStats(app_id, achievements_too));
@@ -218,11 +397,11 @@ request!(GetUserAvatar { steam_id64: u64 } -> Option<AvatarImage>
 request!(GetUserPersonaName { steam_id64: u64 } -> Option<String>
     => SteamCommand::GetUserPersonaName(steam_id64));
 
-request!(ExportApps { app_ids: Vec<u32> } -> Vec<(u32, Result<AppExport, SamError>)>
-    => SteamCommand::ExportApps(app_ids));
-request!(ImportApps { apps: Vec<AppExport> } -> Vec<(u32, Result<ImportSummary, SamError>)>
-    => SteamCommand::ImportApps(apps));
-request!(UnlockAllApps { app_ids: Vec<u32> } -> Vec<(u32, Result<bool, SamError>)>
-    => SteamCommand::UnlockAllApps(app_ids));
-request!(ResetApps { app_ids: Vec<u32>, achievements_too: bool } -> Vec<(u32, Result<bool, SamError>)>
-    => SteamCommand::ResetApps(app_ids, achievements_too));
+request!(ExportApps { app_ids: Vec<u32>, policy: FanOutPolicy } -> Vec<(u32, Result<AppExport, SamError>)>
+    => SteamCommand::ExportApps(app_ids, policy));
+request!(ImportApps { apps: Vec<AppExport>, policy: FanOutPolicy } -> Vec<(u32, Result<Snapshotted<ImportSummary>, SamError>)>
+    => SteamCommand::ImportApps(apps, policy));
+request!(UnlockAllApps { app_ids: Vec<u32>, policy: FanOutPolicy } -> Vec<(u32, Result<Snapshotted<bool>, SamError>)>
+    => SteamCommand::UnlockAllApps(app_ids, policy));
+request!(ResetApps { app_ids: Vec<u32>, achievements_too: bool, policy: FanOutPolicy } -> Vec<(u32, Result<Snapshotted<bool>, SamError>)>
+    => SteamCommand::ResetApps(app_ids, achievements_too, policy));
@@ -20,44 +20,62 @@ use crate::utils::bidir_child::BidirChild;
 use crate::utils::ipc_client::IpcClient;
 use crate::utils::ipc_types::parse_response_bytes;
 use crate::utils::ipc_types::{
-    AppAchievementExport, AppExport, AppStatExport, AppStatValue, ImportSummary, SamError,
-    SteamCommand,
+    AppAchievementExport, AppExport, AppStatExport, AppStatValue, FanOutPolicy, ImportSummary,
+    SamError, SteamCommand,
 };
 use serde::de::IgnoredAny;
 use std::fmt::Display;
 use std::process::Command;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
-/// Progress callback fired from worker threads as each item completes. Borrowed
-/// (not `'static`) so callers can capture references to thread-bound state
-pub type ProgressCallback<'a> = &'a (dyn Fn(usize, usize, u32) + Sync);
+/// Progress callback fired from worker threads as each item completes, with
+/// `(done, total, app_id, retries, outcome)`. Borrowed (not `'static`) so
+/// callers can capture references to thread-bound state
+pub type ProgressCallback<'a> =
+    &'a (dyn Fn(usize, usize, u32, u32, &Result<Vec<u8>, SamError>) + Sync);
 
-/// Maximum concurrent app-server children for a bulk fan-out (Export / Import /
-/// UnlockAll / Reset). Empirically tuned: each per-app cycle is dominated by
+/// Ceiling on `FanOutPolicy::concurrency`, whatever a frontend asks for. The
+/// default of 4 is empirically tuned: each per-app cycle is dominated by
 /// Steam's serial IPC (the `ConnectedSteam::new` handshake + `UserStatsReceived`
 /// callback round-trip), so workers past ~2 just queue behind it. Bench on a
 /// 50-app library: 17.9 s @ 1 worker → 13.9 s @ 2 (1.28×) → 13.4 s @ 4 (1.33×)
-/// → 13.5 s @ 8 (no further gain). 4 leaves a small buffer over the measured
-/// plateau without spawning needless processes.
-pub const MAX_CONCURRENT_APPS: usize = 4;
+/// → 13.5 s @ 8 (no further gain). Past this many, only memory grows.
+pub const MAX_CONCURRENT_APPS: usize = 16;
+/// Ceiling on `FanOutPolicy::retries`.
+pub const MAX_APP_RETRIES: u32 = 10;
+/// Ceiling on a single backoff wait, however many retries doubled it.
+const MAX_BACKOFF_MS: u64 = 60_000;
 
 /// Spawn one short-lived `samrewritten --app=X` child per `(app_id, command)`
-/// item, run them in parallel with up to `max_concurrent` workers continuously
-/// pulling from a shared queue, and return the per-app raw response bytes (or
-/// a `SamError` if the worker failed).
+/// item, run them in parallel with up to `policy.concurrency` workers
+/// continuously pulling from a shared queue, and return the per-app raw
+/// response bytes (or a `SamError` if the worker failed).
+///
+/// An app Steam answers with a timeout is tried again, up to `policy.retries`
+/// times with an exponential backoff. With `policy.adaptive`, every timeout
+/// also halves how many apps may run at once; it grows back by one after as
+/// many answers in a row as are allowed at once.
+///
+/// Once `cancel` is set, workers take nothing more from the queue and retry
+/// nothing; the apps already running finish, and only they are in the
+/// returned list.
 ///
 /// Callers deserialize the response bytes themselves via `SteamResponse::<T>`.
 /// The helper is intentionally generic.
 pub fn run_command_on_apps_concurrent(
     items: Vec<(u32, SteamCommand)>,
-    max_concurrent: usize,
+    policy: &FanOutPolicy,
     progress: Option<ProgressCallback<'_>>,
+    cancel: &AtomicBool,
 ) -> Vec<(u32, Result<Vec<u8>, SamError>)> {
     let total = items.len();
     if total == 0 {
         return Vec::new();
     }
-    let cap = max_concurrent.max(1).min(MAX_CONCURRENT_APPS).min(total);
+    let cap = policy.concurrency.clamp(1, MAX_CONCURRENT_APPS).min(total);
+    let throttle = Throttle::new(cap, policy.adaptive);
 
     let queue = Arc::new(Mutex::new(items.into_iter()));
     let results = Arc::new(Mutex::new(
@@ -70,28 +88,37 @@ pub fn run_command_on_apps_concurrent(
             let queue = Arc::clone(&queue);
             let results = Arc::clone(&results);
             let done = Arc::clone(&done);
+            let throttle = &throttle;
             s.spawn(move || {
                 loop {
+                    if !throttle.acquire(cancel) {
+                        break;
+                    }
                     let next = queue.lock().unwrap().next();
-                    let Some((app_id, command)) = next else { break };
+                    let Some((app_id, command)) = next else {
+                        throttle.release();
+                        break;
+                    };
                     // A panicking worker would abort the whole scope (and the
                     // orchestrator with it) before the terminal frame is sent,
                     // leaving the frontend waiting forever — degrade to a
                     // per-app error instead.
-                    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        run_one(app_id, command)
-                    }))
-                    .unwrap_or_else(|_| {
-                        eprintln!("[CLIENT] Worker panicked for app {app_id}");
-                        Err(SamError::UnknownError)
-                    });
+                    let (outcome, retries) =
+                        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            run_one(app_id, command, policy, throttle, cancel)
+                        }))
+                        .unwrap_or_else(|_| {
+                            eprintln!("[CLIENT] Worker panicked for app {app_id}");
+                            (Err(SamError::UnknownError), 0)
+                        });
+                    throttle.release();
                     let step = {
                         let mut d = done.lock().unwrap();
                         *d += 1;
                         *d
                     };
                     if let Some(cb) = progress {
-                        cb(step, total, app_id);
+                        cb(step, total, app_id, retries, &outcome);
                     }
                     results.lock().unwrap().push((app_id, outcome));
                 }
@@ -102,21 +129,142 @@ pub fn run_command_on_apps_concurrent(
     Arc::try_unwrap(results).unwrap().into_inner().unwrap()
 }
 
-fn run_one(app_id: u32, command: SteamCommand) -> Result<Vec<u8>, SamError> {
-    let first = run_one_attempt(app_id, command.clone());
-    if let Ok(bytes) = &first
-        && response_is_timeout(bytes)
-    {
-        eprintln!("[CLIENT] Timeout for app {app_id}, retrying once");
-        return run_one_attempt(app_id, command);
+/// The app's outcome, and how many retries it took.
+fn run_one(
+    app_id: u32,
+    command: SteamCommand,
+    policy: &FanOutPolicy,
+    throttle: &Throttle,
+    cancel: &AtomicBool,
+) -> (Result<Vec<u8>, SamError>, u32) {
+    let budget = policy.retries.min(MAX_APP_RETRIES);
+    let mut retries = 0;
+    loop {
+        let outcome = run_one_attempt(app_id, command.clone());
+        let timed_out = outcome
+            .as_ref()
+            .is_ok_and(|bytes| response_is_timeout(bytes));
+        throttle.answered(timed_out);
+        if !timed_out || retries == budget || cancel.load(Ordering::Relaxed) {
+            return (outcome, retries);
+        }
+        let delay = backoff(policy.backoff_ms, retries);
+        retries += 1;
+        eprintln!(
+            "[CLIENT] Timeout for app {app_id}, retry {retries}/{budget} in {} ms",
+            delay.as_millis()
+        );
+        if !sleep_unless_cancelled(delay, cancel) {
+            return (outcome, retries - 1);
+        }
+    }
+}
+
+/// The wait before retry number `retries + 1`.
+fn backoff(base_ms: u64, retries: u32) -> Duration {
+    let factor = 1u64.checked_shl(retries).unwrap_or(u64::MAX);
+    Duration::from_millis(base_ms.saturating_mul(factor).min(MAX_BACKOFF_MS))
+}
+
+/// False if `cancel` was set before `delay` was up.
+fn sleep_unless_cancelled(delay: Duration, cancel: &AtomicBool) -> bool {
+    let until = Instant::now() + delay;
+    while !cancel.load(Ordering::Relaxed) {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(100)));
+    }
+    false
+}
+
+/// How many apps a fan-out may run at once right now: halved on a timeout,
+/// grown back by one after as many answers in a row as it allows.
+struct Throttle {
+    state: Mutex<ThrottleState>,
+    freed: Condvar,
+    cap: usize,
+    adaptive: bool,
+}
+
+struct ThrottleState {
+    limit: usize,
+    running: usize,
+    answers: usize,
+}
+
+impl Throttle {
+    fn new(cap: usize, adaptive: bool) -> Self {
+        Self {
+            state: Mutex::new(ThrottleState {
+                limit: cap,
+                running: 0,
+                answers: 0,
+            }),
+            freed: Condvar::new(),
+            cap,
+            adaptive,
+        }
+    }
+
+    /// Wait for room to run one more app. False once `cancel` is set.
+    fn acquire(&self, cancel: &AtomicBool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            if state.running < state.limit {
+                state.running += 1;
+                return true;
+            }
+            // Cancelling does not notify, so look again now and then.
+            state = self
+                .freed
+                .wait_timeout(state, Duration::from_millis(100))
+                .unwrap()
+                .0;
+        }
+    }
+
+    fn release(&self) {
+        self.state.lock().unwrap().running -= 1;
+        self.freed.notify_all();
+    }
+
+    fn answered(&self, timed_out: bool) {
+        if !self.adaptive {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if timed_out {
+            let limit = (state.limit / 2).max(1);
+            if limit < state.limit {
+                eprintln!("[CLIENT] Steam timed out, down to {limit} app(s) at once");
+            }
+            state.limit = limit;
+            state.answers = 0;
+        } else if state.limit < self.cap {
+            state.answers += 1;
+            if state.answers >= state.limit {
+                state.limit += 1;
+                state.answers = 0;
+                self.freed.notify_all();
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
     }
-    first
 }
 
 fn run_one_attempt(app_id: u32, command: SteamCommand) -> Result<Vec<u8>, SamError> {
     let current_exe = get_executable_path();
     let child = BidirChild::new(Command::new(current_exe).arg(format!("--app={app_id}")))?;
-    let mut ipc = IpcClient::new(child);
+    let mut ipc = IpcClient::app_server(child, app_id);
 
     let response = ipc.send(&command).and_then(|_| ipc.recv_frame());
 
@@ -128,10 +276,7 @@ fn run_one_attempt(app_id: u32, command: SteamCommand) -> Result<Vec<u8>, SamErr
 }
 
 fn response_is_timeout(bytes: &[u8]) -> bool {
-    matches!(
-        parse_response_bytes::<IgnoredAny>(bytes),
-        Err(SamError::Timeout)
-    )
+    parse_response_bytes::<IgnoredAny>(bytes).is_err_and(|e| *e.kind() == SamError::Timeout)
 }
 
 /// Snapshot every achievement and stat for `app_id` into an `AppExport`.
@@ -170,7 +315,45 @@ pub fn collect_app_export(manager: &mut AppManager, app_id: u32) -> Result<AppEx
     })
 }
 
-enum WriteDecision<T> {
+/// What `command` is about to change in `app_id`, as it stands: every value an
+/// undo would write back, and nothing it need not. Protected fields are left
+/// out, since nothing here can write them either way.
+pub fn collect_before_image(
+    manager: &mut AppManager,
+    app_id: u32,
+    command: &SteamCommand,
+) -> Result<AppExport, SamError> {
+    let mut export = collect_app_export(manager, app_id)?;
+    export.achievements.retain(|a| a.permission == 0);
+    export.stats.retain(|s| (s.permission & 2) == 0);
+    match command {
+        SteamCommand::UnlockAllAchievements(_) => {
+            export.achievements.retain(|a| !a.is_achieved);
+            export.stats.clear();
+        }
+        SteamCommand::ResetStats(_, achievements_too) => {
+            if *achievements_too {
+                export.achievements.retain(|a| a.is_achieved);
+            } else {
+                export.achievements.clear();
+            }
+        }
+        SteamCommand::ImportAppProgress(_, payload) => {
+            export
+                .achievements
+                .retain(|a| payload.achievements.iter().any(|p| p.id == a.id));
+            export
+                .stats
+                .retain(|s| payload.stats.iter().any(|p| p.id == s.id));
+        }
+        _ => {}
+    }
+    Ok(export)
+}
+
+/// Whether Steam would take `target` for a stat, judged from its schema rules
+/// and current value. Shared with the CLI's `set-stat`.
+pub enum WriteDecision<T> {
     Write,
     OutOfRangeHigh { max: T },
     OutOfRangeLow { min: T },
@@ -178,7 +361,7 @@ enum WriteDecision<T> {
     IncrementOnlyHard { default: T },
 }
 
-fn classify_stat<T: Copy + PartialOrd>(target: T, state: &StatState<T>) -> WriteDecision<T> {
+pub fn classify_stat<T: Copy + PartialOrd>(target: T, state: &StatState<T>) -> WriteDecision<T> {
     if target > state.max {
         return WriteDecision::OutOfRangeHigh { max: state.max };
     }
@@ -279,7 +462,7 @@ pub fn apply_app_export(manager: &mut AppManager, payload: AppExport) -> ImportS
                     &stat.id,
                     target,
                     decision,
-                    || manager.set_stat_i32(&stat.id, target),
+                    || manager.set_stat_i32(&stat.id, target, true),
                     &mut summary,
                     &mut had_reset_fixable,
                     &mut had_hard_block,
@@ -292,7 +475,7 @@ pub fn apply_app_export(manager: &mut AppManager, payload: AppExport) -> ImportS
                     &stat.id,
                     target,
                     decision,
-                    || manager.set_stat_f32(&stat.id, target),
+                    || manager.set_stat_f32(&stat.id, target, true),
                     &mut summary,
                     &mut had_reset_fixable,
                     &mut had_hard_block,
@@ -340,5 +523,34 @@ pub fn apply_app_export(manager: &mut AppManager, payload: AppExport) -> ImportS
     }
 
     summary.reset_would_help = had_reset_fixable && !had_hard_block;
+    summary.granted = manager.take_granted();
     summary
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_its_ceiling() {
+        assert_eq!(backoff(500, 0), Duration::from_millis(500));
+        assert_eq!(backoff(500, 2), Duration::from_millis(2000));
+        assert_eq!(backoff(500, 40), Duration::from_millis(MAX_BACKOFF_MS));
+    }
+
+    #[test]
+    fn timeouts_throttle_until_steam_answers_again() {
+        let throttle = Throttle::new(8, true);
+        throttle.answered(true);
+        throttle.answered(true);
+        assert_eq!(throttle.limit(), 2);
+        throttle.answered(false);
+        assert_eq!(throttle.limit(), 2);
+        throttle.answered(false);
+        assert_eq!(throttle.limit(), 3);
+
+        let fixed = Throttle::new(8, false);
+        fixed.answered(true);
+        assert_eq!(fixed.limit(), 8);
+    }
+}
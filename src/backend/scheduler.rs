@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runs the schedule's due entries for an orchestrator that follows it, each
+//! as the command a frontend would have sent, and journals what they changed.
+//! Achievement and stat writes are built from the app as it stands when they
+//! run, read through an export first.
+
+use crate::dev_println;
+use crate::utils::action_journal::{Batch, Change};
+use crate::utils::ipc_types::{
+    AppExport, FanOutPolicy, ImportSummary, ProgressMsg, SamError, Snapshotted, SteamCommand,
+    SteamResponse, read_tagged_frame,
+};
+use crate::utils::schedule::{self, Entry, Operation};
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+type Imported = Vec<(u32, Result<Snapshotted<ImportSummary>, SamError>)>;
+type Exported = Vec<(u32, Result<AppExport, SamError>)>;
+
+/// Every entry due now, through `run`, which carries out a command and hands
+/// back the frames it answered with. Returns each entry's id, and whether all
+/// of it went through.
+pub fn run_due(mut run: impl FnMut(SteamCommand) -> Vec<u8>) -> Vec<(u64, bool)> {
+    schedule::claim_due()
+        .into_iter()
+        .map(|claimed| {
+            let entry = claimed.entry().clone();
+            dev_println!("ORCH", "Running scheduled entry {}", entry.id);
+            let (changes, errors) = match command(&entry, &mut run) {
+                Ok(command) => settle(&entry, &run(command)),
+                Err(e) => (Vec::new(), vec![e]),
+            };
+            if entry.journal {
+                Batch::scheduled(entry.account).record_per_app(changes);
+            }
+            let ok = errors.is_empty();
+            if !ok {
+                eprintln!(
+                    "[ORCHESTRATOR] Scheduled entry {} failed: {}",
+                    entry.id,
+                    errors.join("; ")
+                );
+            }
+            claimed.finish(errors);
+            (entry.id, ok)
+        })
+        .collect()
+}
+
+/// The entry's command, built against the app as it stands now.
+fn command(
+    entry: &Entry,
+    run: &mut impl FnMut(SteamCommand) -> Vec<u8>,
+) -> Result<SteamCommand, String> {
+    let current = match entry.operation.reads_first() {
+        Some(app_id) => Some(exported(app_id, run)?),
+        None => None,
+    };
+    entry.operation.command(&entry.app_name, current.as_ref())
+}
+
+/// `app_id`'s achievements and stats, read as an export reads them.
+fn exported(
+    app_id: u32,
+    run: &mut impl FnMut(SteamCommand) -> Vec<u8>,
+) -> Result<AppExport, String> {
+    let frames = run(SteamCommand::ExportApps(
+        vec![app_id],
+        FanOutPolicy::default(),
+    ));
+    let result = match last_frame::<ProgressMsg<SteamResponse<Exported>>>(&frames) {
+        Ok(ProgressMsg::Done(SteamResponse::Success(results))) => results
+            .into_iter()
+            .find(|(id, _)| *id == app_id)
+            .map(|(_, result)| result)
+            .unwrap_or(Err(SamError::SocketCommunicationFailed)),
+        Ok(ProgressMsg::Done(SteamResponse::Error(e))) | Err(e) => Err(e),
+        Ok(ProgressMsg::Progress { .. }) => Err(SamError::SocketCommunicationFailed),
+    };
+    result.map_err(|e| format!("{app_id}: {e}"))
+}
+
+/// The last frame a command answered with: a bulk command's `Done`.
+fn last_frame<T: DeserializeOwned>(mut frames: &[u8]) -> Result<T, SamError> {
+    let mut last = None;
+    while let Ok((_, payload)) = read_tagged_frame(&mut frames) {
+        last = Some(payload);
+    }
+    let last = last.ok_or(SamError::SocketCommunicationFailed)?;
+    serde_json::from_slice(&last).map_err(|_| SamError::SerializationFailed)
+}
+
+/// What to journal of an entry's run, and what failed in it.
+fn settle(entry: &Entry, frames: &[u8]) -> (Vec<(u32, String, Change)>, Vec<String>) {
+    let detail = entry.operation.detail();
+    let app_name = |app_id: u32| match &entry.operation {
+        Operation::Import { apps } => apps
+            .iter()
+            .find(|app| app.app_id == app_id)
+            .map(|app| app.app_name.clone())
+            .unwrap_or_default(),
+        _ => entry.app_name.clone(),
+    };
+    let mut changes = Vec::new();
+    let mut errors = Vec::new();
+    match &entry.operation {
+        Operation::StartIdle { app_id } | Operation::StopIdle { app_id } => {
+            match last_frame::<SteamResponse<IgnoredAny>>(frames) {
+                Ok(SteamResponse::Success(_)) => {
+                    changes.push((*app_id, app_name(*app_id), Change::bulk(detail, None)))
+                }
+                Ok(SteamResponse::Error(e)) | Err(e) => errors.push(e.to_string()),
+            }
+        }
+        Operation::Achievements { .. } | Operation::Stats { .. } | Operation::Import { .. } => {
+            let results = match last_frame::<ProgressMsg<SteamResponse<Imported>>>(frames) {
+                Ok(ProgressMsg::Done(SteamResponse::Success(results))) => results,
+                Ok(ProgressMsg::Done(SteamResponse::Error(e))) | Err(e) => {
+                    return (changes, vec![e.to_string()]);
+                }
+                Ok(ProgressMsg::Progress { .. }) => {
+                    return (
+                        changes,
+                        vec![SamError::SocketCommunicationFailed.to_string()],
+                    );
+                }
+            };
+            for (app_id, result) in results {
+                let kept = match result {
+                    Ok(kept) => kept,
+                    Err(e) => {
+                        errors.push(format!("{app_id}: {e}"));
+                        continue;
+                    }
+                };
+                let summary = kept.result;
+                for error in &summary.errors {
+                    errors.push(format!("{app_id}: {error}"));
+                }
+                // Only an import of a file gets here with protected items,
+                // which it skips by design, as any import does: the entry
+                // refused the others before it ran. The rest were asked for
+                // and did not happen.
+                errors.extend(
+                    summary
+                        .skipped_unwriteable
+                        .iter()
+                        .map(|skipped| format!("{app_id}: {skipped}")),
+                );
+                // As the GUI's import: only a clean, non-empty write is history.
+                if summary.errors.is_empty()
+                    && summary.achievements_applied + summary.stats_applied > 0
+                {
+                    let name = app_name(app_id);
+                    changes.push((app_id, name.clone(), Change::bulk(detail, kept.before)));
+                    changes.extend(
+                        Change::granted(summary.granted)
+                            .map(|change| (app_id, name.clone(), change)),
+                    );
+                }
+            }
+        }
+    }
+    (changes, errors)
+}
@@ -184,6 +184,9 @@ pub struct FloatStatInfo {
     pub float_value: f32,
     pub min_value: f32,
     pub max_value: f32,
+    /// The schema's starting value, which a stats reset goes back to.
+    #[serde(default)]
+    pub default_value: f32,
 }
 
 impl FloatStatInfo {
@@ -229,6 +232,8 @@ pub struct IntStatInfo {
     pub int_value: i32,
     pub min_value: i32,
     pub max_value: i32,
+    #[serde(default)]
+    pub default_value: i32,
 }
 
 impl IntStatInfo {
@@ -307,4 +312,8 @@ pub struct AchievementInfo {
     pub name: String,
     pub description: String,
     pub global_achieved_percent: Option<f32>,
+    /// Hidden until unlocked in Steam's own overlay. Defaulted so older
+    /// serialized listings still parse.
+    #[serde(default)]
+    pub is_hidden: bool,
 }
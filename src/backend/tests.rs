@@ -41,7 +41,7 @@ mod tests {
 
     #[test]
     fn reset_stats_no_message() {
-        let app_manager = AppManager::new_connected(480).expect("Failed to create app manager");
+        let mut app_manager = AppManager::new_connected(480).expect("Failed to create app manager");
         let success = app_manager
             .reset_all_stats(true)
             .expect("Failed to get stats");
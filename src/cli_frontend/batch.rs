@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `samrewritten batch`: many commands over the one orchestrator `main()`
+//! already spawned, so app servers stay up between lines instead of being
+//! relaunched by every invocation.
+
+use super::error::{ErrorKind, Failure};
+use super::{Command, INTERRUPTED, execute, install_interrupt_handler};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::atomic::Ordering;
+
+/// Stopping at a failure exits with that command's code. With `keep_going`,
+/// failures among successes exit as partial.
+pub(super) fn run(file: &Path, keep_going: bool) -> ExitCode {
+    let reader: Box<dyn Read> = if file == Path::new("-") {
+        Box::new(std::io::stdin())
+    } else {
+        match std::fs::File::open(file) {
+            Ok(f) => Box::new(f),
+            Err(e) => {
+                return Failure::invalid(format!("Failed to read {}: {}", file.display(), e))
+                    .report();
+            }
+        }
+    };
+
+    // Ctrl+C lets the running command finish, then stops before the next one.
+    if let Err(e) = install_interrupt_handler() {
+        return Failure::new(format!("Failed to install interrupt handler: {e}")).report();
+    }
+
+    let mut failure: Option<ErrorKind> = None;
+    let mut succeeded = false;
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        if INTERRUPTED.load(Ordering::SeqCst) {
+            return Failure::new("Interrupted; skipped the rest of the batch").report();
+        }
+
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                return Failure::new(format!("Failed to read {}: {}", file.display(), e)).report();
+            }
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let (result, kind) = run_line(index + 1, trimmed);
+        println!("{result}");
+        match kind {
+            None => succeeded = true,
+            Some(kind) if !keep_going => return ExitCode::from(kind as u8),
+            Some(kind) => failure = Some(kind),
+        }
+    }
+
+    match failure {
+        None => ExitCode::SUCCESS,
+        Some(_) if succeeded => ExitCode::from(ErrorKind::Partial as u8),
+        Some(kind) => ExitCode::from(kind as u8),
+    }
+}
+
+/// One compact JSON object per script line, numbered as in the file, and the
+/// kind of failure if it failed.
+fn run_line(line: usize, text: &str) -> (Value, Option<ErrorKind>) {
+    let (mut result, kind) = run_object(text);
+    result["line"] = json!(line);
+    (result, kind)
+}
+
+/// Run one command given as a JSON object, as a script line or an API request.
+pub(super) fn run_object(text: &str) -> (Value, Option<ErrorKind>) {
+    let failed = |name: &Value, failure: Failure| {
+        let result = json!({
+            "command": name,
+            "success": false,
+            "error": failure.to_json(),
+            "result": failure.partial,
+        });
+        (result, Some(failure.kind))
+    };
+
+    let parsed: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return failed(&Value::Null, Failure::invalid(format!("Invalid JSON: {e}"))),
+    };
+    let name = parsed.get("command").cloned().unwrap_or(Value::Null);
+
+    let command = match serde_json::from_value::<Command>(parsed) {
+        Ok(command) => command,
+        Err(e) => return failed(&name, Failure::invalid(e.to_string())),
+    };
+
+    match execute(command) {
+        Ok(result) => (
+            json!({"command": name, "success": true, "result": result}),
+            None,
+        ),
+        Err(failure) => failed(&name, failure),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_lines_read_like_the_flags() {
+        let command: Command = serde_json::from_str(
+            r#"{"command": "list-achievements", "app_id": 480, "locked": true,
+                "rarer_than": "5%", "unlocked_before": "2024-01-01", "sort": "unlock-time"}"#,
+        )
+        .unwrap();
+        let Command::ListAchievements {
+            app_id,
+            filters,
+            sort,
+            ..
+        } = command
+        else {
+            panic!("wrong command");
+        };
+        assert_eq!(app_id, 480);
+        assert!(filters.locked && !filters.unlocked);
+        assert_eq!(filters.rarer_than, Some(5.0));
+        assert!(filters.unlocked_before.is_some());
+        assert_eq!(
+            sort,
+            crate::backend::library_query::AchievementSort::UnlockTime
+        );
+
+        let command: Command =
+            serde_json::from_str(r#"{"command": "unlock", "app_id": 480, "ids": ["A", "B"]}"#)
+                .unwrap();
+        assert!(
+            matches!(command, Command::Unlock { selection, .. } if selection.ids == ["A", "B"])
+        );
+
+        assert!(serde_json::from_str::<Command>(r#"{"command": "idle", "app_id": 480}"#).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! How a failed command is reported: one JSON object on stderr, and an exit
+//! code a wrapper can branch on without reading the message.
+
+use super::print_json;
+use crate::backend::orchestrator_client::{GetSubscribedAppList, Request};
+use crate::utils::ipc_types::SamError;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::process::ExitCode;
+
+/// Also the exit code. 2 matches what clap exits with on a bad command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub(super) enum ErrorKind {
+    Failed = 1,
+    /// The command cannot be carried out as given: an unknown stat, a bad
+    /// pattern, a value outside the schema's range.
+    Invalid = 2,
+    SteamNotRunning = 3,
+    AppNotOwned = 4,
+    Protected = 5,
+    Timeout = 6,
+    /// Some items went through and some did not; what did is on stdout.
+    Partial = 7,
+}
+
+impl From<&SamError> for ErrorKind {
+    fn from(error: &SamError) -> Self {
+        match error.kind() {
+            SamError::SteamConnectionFailed => ErrorKind::SteamNotRunning,
+            SamError::Timeout => ErrorKind::Timeout,
+            _ => ErrorKind::Failed,
+        }
+    }
+}
+
+/// Why a command failed, plus whatever output it still produced: an export
+/// missing one app still prints the others.
+pub(super) struct Failure {
+    pub kind: ErrorKind,
+    pub message: String,
+    sam_error: Option<SamError>,
+    app_id: Option<u32>,
+    /// The achievement or stat being acted on, when there was one.
+    item: Option<String>,
+    pub partial: Option<Value>,
+}
+
+impl Failure {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Failed,
+            message: message.into(),
+            sam_error: None,
+            app_id: None,
+            item: None,
+            partial: None,
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(message).kind(ErrorKind::Invalid)
+    }
+
+    /// `context` leads the message, as in "Failed to export: <error>". The app
+    /// and item the backend blamed are kept, unless the caller names its own.
+    pub fn backend(context: &str, error: SamError) -> Self {
+        let mut failure = Self::new(format!("{context}: {error}")).kind((&error).into());
+        if let Some(detail) = error.detail() {
+            failure.app_id = detail.app_id;
+            failure.item = detail.item.clone();
+        }
+        failure.sam_error = Some(error);
+        failure
+    }
+
+    pub fn kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn app(mut self, app_id: u32) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    pub fn item(mut self, item: impl Into<String>) -> Self {
+        self.item = Some(item.into());
+        self
+    }
+
+    pub fn with_partial(mut self, partial: Value) -> Self {
+        self.partial = Some(partial);
+        self
+    }
+
+    /// `failed` of `total` items went wrong: partial while anything got through.
+    pub fn items(failed: usize, total: usize, message: impl Into<String>) -> Self {
+        let kind = if failed < total {
+            ErrorKind::Partial
+        } else {
+            ErrorKind::Failed
+        };
+        Self::new(message).kind(kind)
+    }
+
+    /// A failed connection for one app is also what an app the account does
+    /// not own produces. Telling them apart takes the library, so only ask for
+    /// it once something has already gone wrong.
+    pub fn diagnose(mut self) -> Self {
+        let Some(app_id) = self.app_id else {
+            return self;
+        };
+        if self.kind != ErrorKind::SteamNotRunning {
+            return self;
+        }
+        let library = (GetSubscribedAppList {
+            include_playtime: false,
+            with_achievement_counts: false,
+        })
+        .request();
+        if let Ok(apps) = library {
+            self.kind = if apps.iter().any(|app| app.app_id == app_id) {
+                ErrorKind::Failed
+            } else {
+                ErrorKind::AppNotOwned
+            };
+        }
+        self
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut error = json!({"kind": self.kind, "message": self.message});
+        if let Some(sam_error) = &self.sam_error {
+            error["sam_error"] = json!(sam_error.kind());
+        }
+        if let Some(app_id) = self.app_id {
+            error["app_id"] = json!(app_id);
+        }
+        if let Some(item) = &self.item {
+            error["item"] = json!(item);
+        }
+        if let Some(detail) = self.sam_error.as_ref().and_then(SamError::detail) {
+            if let Some(eresult) = detail.eresult {
+                error["eresult"] = json!(eresult);
+            }
+            if let Some(process) = detail.process {
+                error["process"] = json!(process);
+            }
+            if !detail.context.is_empty() {
+                error["context"] = json!(detail.context);
+            }
+        }
+        error
+    }
+
+    /// Whatever output survived goes to stdout as usual, the error to stderr.
+    pub fn report(self) -> ExitCode {
+        if let Some(partial) = &self.partial {
+            print_json(partial);
+        }
+        eprintln!("{}", json!({"error": self.to_json()}));
+        ExitCode::from(self.kind as u8)
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! `jobs`: the bulk jobs kept in the cache folder, listed, resumed and
+//! discarded.
+
+use super::error::Failure;
+use super::{Finished, Outcome, cancellable, to_json};
+use crate::backend::orchestrator_client::{ImportApps, ResetApps, UnlockAllApps};
+use crate::utils::bulk_jobs::{self, Job, Kind, ResumeError, Running, Status};
+use crate::utils::ipc_types::FanOutPolicy;
+use serde_json::{Value, json};
+
+fn summary(job: &Job) -> Value {
+    json!({
+        "id": job.id,
+        "kind": job.kind,
+        "state": job.state,
+        "created_at": job.created_at,
+        "updated_at": job.updated_at,
+        "total": job.items.len(),
+        "done": job.count(Status::Done),
+        "failed": job.count(Status::Failed),
+        "pending": job.count(Status::Pending),
+    })
+}
+
+pub(super) fn list() -> Outcome {
+    Ok(Value::Array(
+        bulk_jobs::load().iter().map(summary).collect(),
+    ))
+}
+
+pub(super) fn discard(id: u64) -> Outcome {
+    if bulk_jobs::get(id).is_none() {
+        return Err(Failure::invalid(format!("No job {id}")));
+    }
+    if !bulk_jobs::discard(id) {
+        return Err(Failure::new(ResumeError::Busy.to_string()));
+    }
+    Ok(json!({ "discarded": id }))
+}
+
+/// Prints the job as it ends up, with each app's answer but not the exports an
+/// import wrote, which are in the file it came from, nor the before-images the
+/// GUI journals.
+pub(super) fn resume(id: u64, policy: FanOutPolicy) -> Outcome {
+    let mut job = Running::resume(id).map_err(|e| match e {
+        ResumeError::NotFound => Failure::invalid(format!("No job {id}")),
+        e => Failure::new(e.to_string()),
+    })?;
+    let items = job.job().remaining();
+    let app_ids: Vec<u32> = items.iter().map(|item| item.app_id).collect();
+    let ran = match job.job().kind {
+        Kind::UnlockAll => {
+            let request = UnlockAllApps { app_ids, policy };
+            cancellable(request, "Unlocked", Some(&mut job)).map(ignore_results)
+        }
+        Kind::Lock => {
+            let request = ResetApps {
+                app_ids,
+                achievements_too: true,
+                policy,
+            };
+            cancellable(request, "Reset", Some(&mut job)).map(ignore_results)
+        }
+        Kind::Import => {
+            let apps = items.into_iter().filter_map(|item| item.export).collect();
+            let request = ImportApps { apps, policy };
+            cancellable(request, "Imported", Some(&mut job)).map(ignore_results)
+        }
+    };
+    ran.map_err(|e| Failure::backend("Failed to resume the job", e))?;
+
+    let job = job.job();
+    let items: Vec<_> = job
+        .items
+        .iter()
+        .map(|item| {
+            let mut item = item.clone();
+            item.export = None;
+            if let Some(Value::Object(answer)) = &mut item.result {
+                answer.remove("before");
+            }
+            item
+        })
+        .collect();
+    let mut value = summary(job);
+    value["items"] = to_json(&items)?;
+    let failed = job.count(Status::Failed) + job.count(Status::Pending);
+    if failed > 0 {
+        let message = format!(
+            "{failed} of {} apps in the job are not done",
+            job.items.len()
+        );
+        Err(Failure::items(failed, job.items.len(), message).with_partial(value))
+    } else {
+        Ok(value)
+    }
+}
+
+/// The job holds what each app answered.
+fn ignore_results<T>(_: Finished<T>) {}
@@ -13,24 +13,54 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::backend::app_lister::AppModel;
+use crate::backend::diagnostics::{self, Handshake};
+use crate::backend::library_query::{
+    AchievementFilter, AchievementSort, AppFilter, AppSort, query_achievements, query_apps,
+    summarize,
+};
 use crate::backend::local_stats::read_schema_languages;
 use crate::backend::orchestrator_client::{
-    AppProgress, ExportApps, GetAchievementsAndStats, GetSubscribedAppList, ImportApps, LaunchApp,
-    Request, ResetStats, SetAchievement, SetFloatStat, SetIntStat, StoreStatsAndAchievements,
-    UnlockAllAchievements, set_orchestrator, shutdown_and_wait,
+    AppProgress, Canceller, ExportApps, GetAchievementsAndStats, GetAppServers,
+    GetSubscribedAppList, ImportApps, LaunchApp, Request, ResetStats, SetAchievement, SetFloatStat,
+    SetIntStat, StoreStatsAndAchievements, UnlockAllAchievements, attach_to_daemon,
+    set_orchestrator, shutdown_and_wait, subscribe_events,
 };
+use crate::backend::progress_io::{MAX_APP_RETRIES, MAX_CONCURRENT_APPS};
 use crate::backend::stat_definitions::StatInfo;
+use crate::utils::action_journal;
 use crate::utils::app_paths::get_executable_path;
 use crate::utils::bidir_child::BidirChild;
-use crate::utils::export_file::{ExportFile, FORMAT_VERSION, iso8601_utc_now};
+use crate::utils::bulk_jobs::{Item, Kind, Running};
+use crate::utils::export_file::{
+    self, ExportFile, FORMAT_VERSION, ReadError, iso8601_utc_now, parse_iso8601_utc,
+};
 use crate::utils::ipc_client::IpcClient;
-use crate::utils::ipc_types::{AppExport, ImportSummary, SamError};
+use crate::utils::ipc_types::{AppExport, FanOutPolicy, ImportSummary, SamError, Stored};
+use clap::builder::RangedU64ValueParser;
 use clap::{Args, Parser, Subcommand};
-use serde::Serialize;
-use serde_json::json;
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::process::{Command as ProcessCommand, ExitCode};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, SystemTime};
+
+mod batch;
+mod error;
+mod jobs;
+mod offline;
+mod schedule;
+mod select;
+mod serve;
+mod stat_edit;
+
+use error::{ErrorKind, Failure};
+use select::Selection;
+use stat_edit::{Target, parse_assignments};
 
 static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
@@ -42,7 +72,8 @@ static INTERRUPTED: AtomicBool = AtomicBool::new(false);
     long_about = "Steam Achievements Manager Rewritten\n\
                   Manage Steam achievements and stats for the apps your account owns: \
                   list, unlock, lock, idle, and import/export progress as JSON.\n\
-                  Requires the Steam client to be running and signed in.\n\
+                  Requires the Steam client to be running and signed in, except for \
+                  --offline listings read from its on-disk cache.\n\
                   Licensed under GNU GPLv3, Copyright (C) 2026"
 )]
 struct Cli {
@@ -50,20 +81,38 @@ struct Cli {
     command: Command,
 }
 
-#[derive(Subcommand)]
+/// Also deserialized from the JSON lines of a `batch` script, tagged by
+/// subcommand name and with the flags as fields.
+#[derive(Subcommand, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
 enum Command {
     /// List all achievements for an app, with their current unlock status, as JSON.
     ListAchievements {
         /// Steam AppID of the game to query.
         app_id: u32,
         #[command(flatten)]
+        #[serde(flatten)]
         language: Language,
+        #[command(flatten)]
+        #[serde(flatten)]
+        filters: AchievementFilters,
+        /// Order of the listing: schema, id, name, rarity (rarest first) or
+        /// unlock-time (oldest first).
+        #[arg(long, default_value = "schema")]
+        #[serde(default)]
+        sort: AchievementSort,
+        /// Read Steam's on-disk cache instead, which works with Steam closed.
+        /// English names only, and no rarity, protection or hidden flags.
+        #[arg(long)]
+        #[serde(default)]
+        offline: bool,
     },
     /// List all stats defined for an app, with their current values, as JSON.
     ListStatistics {
         /// Steam AppID of the game to query.
         app_id: u32,
         #[command(flatten)]
+        #[serde(flatten)]
         language: Language,
     },
     /// List the languages an app's schema offers for --language, as JSON.
@@ -74,87 +123,571 @@ enum Command {
     /// List all apps owned by the logged-in Steam user as JSON.
     ListApps {
         /// Also include per-app achievement counts (total and unlocked).
-        /// Slower: requires querying stats for every owned app.
+        /// Slower: requires querying stats for every owned app. Implied by the
+        /// count filters and sorts.
         #[arg(long)]
+        #[serde(default)]
         with_achievements: bool,
-        /// Also include playtime and last-played time for every app.
+        /// Also include playtime and last-played time for every app. Implied by
+        /// --filter-hide-never-launched and the playtime sorts.
         #[arg(long)]
+        #[serde(default)]
         with_playtime: bool,
+        #[command(flatten)]
+        #[serde(flatten)]
+        filters: AppFilters,
+        /// Order of the listing, as in the GUI: app-id, alphabetical,
+        /// last-played, playtime, completion or remaining.
+        #[arg(long, default_value = "app-id")]
+        #[serde(default)]
+        sort: AppSort,
+        /// Read Steam's on-disk cache instead, which works with Steam closed.
+        /// Lists only apps Steam has cached stats for, always with their counts.
+        #[arg(long)]
+        #[serde(default)]
+        offline: bool,
+    },
+    /// Summarize achievement completion across the library, as on the GUI's
+    /// profile page, as JSON.
+    Summary {
+        #[command(flatten)]
+        #[serde(flatten)]
+        filters: AppFilters,
+        /// Read Steam's on-disk cache instead, which works with Steam closed.
+        /// Covers only apps Steam has cached stats for.
+        #[arg(long)]
+        #[serde(default)]
+        offline: bool,
     },
-    /// Unlock one or more achievements for an app.
+    /// Unlock achievements for an app, named by API name or selected by
+    /// pattern and metadata. A selection is listed for confirmation first.
     Unlock {
         /// Steam AppID of the game.
         app_id: u32,
         #[command(flatten)]
-        ids: Ids,
+        #[serde(flatten)]
+        selection: Selection,
     },
     /// Unlock every achievement defined for an app.
     UnlockAll {
         /// Steam AppID of the game.
         app_id: u32,
     },
-    /// Lock (re-lock) one or more achievements for an app.
+    /// Lock (re-lock) achievements for an app, selected as for `unlock`.
     Lock {
         /// Steam AppID of the game.
         app_id: u32,
         #[command(flatten)]
-        ids: Ids,
+        #[serde(flatten)]
+        selection: Selection,
     },
     /// Reset every achievement and stat for an app to its locked/default state.
     LockAll {
         /// Steam AppID of the game.
         app_id: u32,
     },
-    /// Set the value of a stat for an app.
+    /// Reset every stat for an app to its default, keeping its achievements.
+    ResetStats {
+        /// Steam AppID of the game.
+        app_id: u32,
+    },
+    /// Set one or more stats for an app, saved together once all are checked.
     SetStat {
         /// Steam AppID of the game.
         app_id: u32,
-        /// Stat API name, as printed by `list-statistics`.
-        stat_id: String,
-        /// New value, an integer or a decimal depending on the stat's type.
-        value: String,
+        /// Stat API names, as printed by `list-statistics`, with their change:
+        /// ID=VALUE, ID+=N, ID-=N, ID=min, ID=max or ID=default.
+        #[arg(required = true, value_name = "ASSIGNMENT")]
+        stats: Vec<String>,
     },
     /// Idle an app (appear in-game) until interrupted with Ctrl+C.
+    #[serde(skip)]
     Idle {
         /// Steam AppID of the game to idle.
         app_id: u32,
     },
     /// Export achievements and stats for one or more apps to stdout as JSON.
+    /// Ctrl+C stops it starting more apps and exports those already done.
     Export {
         /// One or more Steam AppIDs to export.
         #[arg(required = true)]
         app_ids: Vec<u32>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        pacing: Pacing,
     },
     /// Import achievements and stats from a JSON file produced by `export`
     /// (or by the GUI). Protected fields are skipped. Prints a JSON summary.
+    /// Ctrl+C stops it starting more apps; the summary covers those done.
     Import {
         /// Path to a JSON file previously produced by `export` or the GUI.
+        #[serde(default)]
         file: PathBuf,
         /// Only import the app with this ID (skip the rest).
         #[arg(long)]
+        #[serde(default)]
+        app_id: Option<u32>,
+        /// Scripts and the API can pass the export itself instead of a file.
+        #[arg(skip)]
+        #[serde(default)]
+        progress: Option<ExportFile>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        pacing: Pacing,
+    },
+    /// List what the GUI's action journal recorded, newest first, as JSON.
+    /// Works with Steam closed.
+    Journal {
+        /// Only changes to this app.
+        #[arg(long)]
+        #[serde(default)]
         app_id: Option<u32>,
+        /// At most this many entries.
+        #[arg(long)]
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    /// List, resume or discard the bulk jobs kept in the cache folder while
+    /// they run: `import`, and the GUI's unlock, lock and import of several
+    /// apps. A job a crash cut short reads as interrupted.
+    Jobs {
+        #[command(subcommand)]
+        #[serde(flatten)]
+        action: JobsAction,
+    },
+    /// List, add or cancel operations queued for a later time. A `--daemon`
+    /// runs them as they fall due, and so does the GUI while a window is open;
+    /// with neither running then, an entry runs late, once one starts.
+    Schedule {
+        #[command(subcommand)]
+        #[serde(flatten)]
+        action: ScheduleAction,
+    },
+    /// List the app servers the backend holds open, with process id, uptime,
+    /// restarts and last error, as JSON. Only a `--daemon` keeps any between
+    /// commands.
+    Servers,
+    /// Print what the backend reports as it happens, one JSON object per line,
+    /// until Ctrl+C: Steam starting or quitting, another account signing in,
+    /// an app server dying, idling starting or stopping, stats being stored.
+    /// Most useful against a `--daemon`, which other frontends share.
+    #[serde(skip)]
+    Events,
+    /// Run a script of commands over a single backend session. Each line is a
+    /// JSON object naming a subcommand and its arguments, e.g.
+    /// {"command": "unlock", "app_id": 480, "ids": ["ACH_WIN_ONE_GAME"]}.
+    /// Prints one JSON result per line.
+    #[serde(skip)]
+    Batch {
+        /// Script to run, or - for stdin. Blank lines and lines starting with # are skipped.
+        file: PathBuf,
+        /// Carry on after a failing command instead of stopping there.
+        #[arg(long)]
+        keep_going: bool,
+    },
+    /// Report the Steam installs found, which one is running and which library
+    /// would be loaded, the account, the caches, and a test connection, as JSON.
+    #[serde(skip)]
+    Doctor,
+    /// Serve the `batch` commands over HTTP on localhost until interrupted with
+    /// Ctrl+C. POST one command object to /v1/command with the bearer token
+    /// from `api_token` in the cache folder; the response is its batch result.
+    #[serde(skip)]
+    Serve {
+        /// Loopback address and port to listen on.
+        #[arg(long, default_value = "127.0.0.1:4917")]
+        listen: SocketAddr,
     },
 }
 
-#[derive(Args)]
-struct Ids {
-    /// One or more achievement API names to act on.
-    #[arg(required = true)]
-    ids: Vec<String>,
+#[derive(Subcommand, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+enum JobsAction {
+    /// List every job, newest first, with how many of its apps are done,
+    /// failed and pending, as JSON. Works with Steam closed.
+    List,
+    /// Run the apps a job has not done yet, failed ones included, and print
+    /// the job as JSON. Ctrl+C stops it starting more apps.
+    Resume {
+        /// The job's id, as `jobs list` prints it.
+        id: u64,
+        #[command(flatten)]
+        #[serde(flatten)]
+        pacing: Pacing,
+    },
+    /// Delete a job that is not running. Works with Steam closed.
+    Discard {
+        /// The job's id, as `jobs list` prints it.
+        id: u64,
+    },
 }
 
-#[derive(Args)]
+#[derive(Subcommand, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+enum ScheduleAction {
+    /// List every entry, soonest due first, with its status, as JSON. Works
+    /// with Steam closed.
+    List,
+    /// Cancel an entry that has not run yet. Works with Steam closed.
+    Cancel {
+        /// The entry's id, as `schedule list` prints it.
+        id: u64,
+    },
+    /// Queue unlocking achievements, named by API name. They are checked
+    /// against the app now, so Steam must be running.
+    Unlock {
+        /// Steam AppID of the game.
+        app_id: u32,
+        /// Achievement API names, as printed by `list-achievements`.
+        #[arg(required = true)]
+        ids: Vec<String>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+    /// Queue locking achievements, named by API name. They are checked
+    /// against the app now, so Steam must be running.
+    Lock {
+        /// Steam AppID of the game.
+        app_id: u32,
+        /// Achievement API names, as printed by `list-achievements`.
+        #[arg(required = true)]
+        ids: Vec<String>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+    /// Queue setting stats. Assignments are those of `set-stat`, checked
+    /// against the app's values now, so Steam must be running. `ID+=N` and
+    /// `ID-=N` add to what the stat holds when the entry runs.
+    SetStat {
+        /// Steam AppID of the game.
+        app_id: u32,
+        /// ID=VALUE, ID+=N, ID-=N, ID=min, ID=max or ID=default.
+        #[arg(required = true, value_name = "ASSIGNMENT")]
+        stats: Vec<String>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+    /// Queue importing a file produced by `export` or the GUI. The file is read
+    /// now; later changes to it are not picked up.
+    Import {
+        /// Path to a JSON file previously produced by `export` or the GUI.
+        file: PathBuf,
+        /// Only import the app with this ID (skip the rest).
+        #[arg(long)]
+        #[serde(default)]
+        app_id: Option<u32>,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+    /// Queue idling an app. It idles until a `stop-idle` entry runs, or the
+    /// backend running it exits.
+    Idle {
+        /// Steam AppID of the game.
+        app_id: u32,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+    /// Queue stopping an idle a scheduled `idle` started.
+    StopIdle {
+        /// Steam AppID of the game.
+        app_id: u32,
+        #[command(flatten)]
+        #[serde(flatten)]
+        when: When,
+    },
+}
+
+#[derive(Args, Deserialize)]
+struct When {
+    /// When to run it: a UTC date or date-time (YYYY-MM-DD or
+    /// YYYY-MM-DDTHH:MM:SSZ).
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = parse_date,
+        required_unless_present = "delay",
+        conflicts_with = "delay"
+    )]
+    #[serde(default, deserialize_with = "deserialize_date")]
+    at: Option<SystemTime>,
+    /// Or how long from now: a number of seconds, or of minutes, hours or days
+    /// with an m, h or d after it, e.g. 90m.
+    #[arg(long = "in", value_name = "DELAY", value_parser = parse_delay)]
+    #[serde(default, rename = "in", deserialize_with = "deserialize_delay")]
+    delay: Option<u64>,
+    /// Record the run in the action journal, as the GUI records its changes.
+    #[arg(long)]
+    #[serde(default)]
+    journal: bool,
+}
+
+/// Named after the GUI sidebar's settings keys, with the same meaning.
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct AppFilters {
+    /// Hide junk entries (tools, soundtracks, servers…), like unticking "Show junk".
+    #[arg(long)]
+    filter_junk: bool,
+    /// Hide apps with every achievement unlocked.
+    #[arg(long)]
+    filter_hide_fully_unlocked: bool,
+    /// Hide apps with no achievement unlocked.
+    #[arg(long)]
+    filter_hide_no_unlocked: bool,
+    /// Hide apps that define no achievements.
+    #[arg(long)]
+    filter_hide_without_achievements: bool,
+    /// Hide apps that were never launched.
+    #[arg(long)]
+    filter_hide_never_launched: bool,
+    /// Only apps whose name contains this text, ignoring case.
+    #[arg(long)]
+    search: Option<String>,
+}
+
+impl From<&AppFilters> for AppFilter {
+    fn from(flags: &AppFilters) -> Self {
+        AppFilter {
+            junk: flags.filter_junk,
+            hide_fully_unlocked: flags.filter_hide_fully_unlocked,
+            hide_never_launched: flags.filter_hide_never_launched,
+            hide_no_unlocked: flags.filter_hide_no_unlocked,
+            hide_without_achievements: flags.filter_hide_without_achievements,
+        }
+    }
+}
+
+/// How `export` and `import` pace the apps, like the GUI's bulk-action settings.
+#[derive(Args, Deserialize)]
+#[serde(default)]
+struct Pacing {
+    /// Apps processed at once, at most (1 to 16).
+    #[arg(long, default_value_t = FanOutPolicy::default().concurrency,
+          value_parser = RangedU64ValueParser::<usize>::new().range(1..=MAX_CONCURRENT_APPS as u64))]
+    concurrency: usize,
+    /// Retries for an app Steam times out on (0 to 10).
+    #[arg(long, default_value_t = FanOutPolicy::default().retries,
+          value_parser = RangedU64ValueParser::<u32>::new().range(0..=MAX_APP_RETRIES as u64))]
+    retries: u32,
+    /// Wait before an app's first retry, in milliseconds; doubled for each
+    /// retry after it.
+    #[arg(long, default_value_t = FanOutPolicy::default().backoff_ms)]
+    backoff_ms: u64,
+    /// Keep the same number of apps at once even while Steam times out.
+    #[arg(long)]
+    no_adaptive: bool,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        let policy = FanOutPolicy::default();
+        Self {
+            concurrency: policy.concurrency,
+            retries: policy.retries,
+            backoff_ms: policy.backoff_ms,
+            no_adaptive: !policy.adaptive,
+        }
+    }
+}
+
+impl From<&Pacing> for FanOutPolicy {
+    fn from(pacing: &Pacing) -> Self {
+        FanOutPolicy {
+            concurrency: pacing.concurrency,
+            retries: pacing.retries,
+            backoff_ms: pacing.backoff_ms,
+            adaptive: !pacing.no_adaptive,
+        }
+    }
+}
+
+#[derive(Args, Deserialize, Default)]
+#[serde(default)]
+struct AchievementFilters {
+    /// Only locked achievements.
+    #[arg(long, conflicts_with = "unlocked")]
+    locked: bool,
+    /// Only unlocked achievements.
+    #[arg(long)]
+    unlocked: bool,
+    /// Only achievements Steam protects from being set.
+    #[arg(long, conflicts_with = "not_protected")]
+    protected: bool,
+    /// Only achievements that can be set.
+    #[arg(long)]
+    not_protected: bool,
+    /// Only achievements hidden until unlocked.
+    #[arg(long, conflicts_with = "not_hidden")]
+    hidden: bool,
+    /// Only achievements that are not hidden.
+    #[arg(long)]
+    not_hidden: bool,
+    /// Only achievements fewer than this percentage of players have, e.g. 5 or 5%.
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    #[serde(deserialize_with = "deserialize_percent")]
+    rarer_than: Option<f32>,
+    /// Only achievements unlocked at or after this UTC date or date-time
+    /// (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ).
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    #[serde(deserialize_with = "deserialize_date")]
+    unlocked_after: Option<SystemTime>,
+    /// Only achievements unlocked at or before this UTC date or date-time. A
+    /// bare date takes in the whole of that day.
+    #[arg(long, value_name = "DATE", value_parser = parse_end_date)]
+    #[serde(deserialize_with = "deserialize_end_date")]
+    unlocked_before: Option<SystemTime>,
+}
+
+impl From<&AchievementFilters> for AchievementFilter {
+    fn from(flags: &AchievementFilters) -> Self {
+        let either = |yes: bool, no: bool| match (yes, no) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        AchievementFilter {
+            achieved: either(flags.unlocked, flags.locked),
+            protected: either(flags.protected, flags.not_protected),
+            hidden: either(flags.hidden, flags.not_hidden),
+            rarer_than: flags.rarer_than,
+            unlocked_after: flags.unlocked_after,
+            unlocked_before: flags.unlocked_before,
+        }
+    }
+}
+
+fn parse_percent(text: &str) -> Result<f32, String> {
+    let number = text.trim().trim_end_matches('%');
+    match number.parse::<f32>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+        _ => Err(format!("'{text}' is not a percentage between 0 and 100")),
+    }
+}
+
+fn parse_date(text: &str) -> Result<SystemTime, String> {
+    parse_iso8601_utc(text)
+        .ok_or_else(|| format!("'{text}' is not a date (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ)"))
+}
+
+/// A bare date as its last second, for a bound that should take the day in.
+fn parse_end_date(text: &str) -> Result<SystemTime, String> {
+    let at = parse_date(text)?;
+    if text.trim().contains(['T', ' ']) {
+        return Ok(at);
+    }
+    Ok(at + Duration::from_secs(86_399))
+}
+
+/// Batch scripts spell percentages and dates the way the flags do, so `"5%"`
+/// works there too.
+fn deserialize_percent<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f32>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Percent {
+        Number(f32),
+        Text(String),
+    }
+    match Option::<Percent>::deserialize(d)? {
+        None => Ok(None),
+        Some(Percent::Number(n)) => parse_percent(&n.to_string())
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        Some(Percent::Text(text)) => parse_percent(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(d: D) -> Result<Option<SystemTime>, D::Error> {
+    match Option::<String>::deserialize(d)? {
+        None => Ok(None),
+        Some(text) => parse_date(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+fn deserialize_end_date<'de, D: Deserializer<'de>>(d: D) -> Result<Option<SystemTime>, D::Error> {
+    match Option::<String>::deserialize(d)? {
+        None => Ok(None),
+        Some(text) => parse_end_date(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// In seconds.
+fn parse_delay(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, unit) = match text.char_indices().last() {
+        Some((i, 's')) => (&text[..i], 1),
+        Some((i, 'm')) => (&text[..i], 60),
+        Some((i, 'h')) => (&text[..i], 60 * 60),
+        Some((i, 'd')) => (&text[..i], 24 * 60 * 60),
+        _ => (text, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit))
+        .ok_or_else(|| format!("'{text}' is not a delay such as 30, 90m, 2h or 1d"))
+}
+
+fn deserialize_delay<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Delay {
+        Seconds(u64),
+        Text(String),
+    }
+    match Option::<Delay>::deserialize(d)? {
+        None => Ok(None),
+        Some(Delay::Seconds(seconds)) => Ok(Some(seconds)),
+        Some(Delay::Text(text)) => parse_delay(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+#[derive(Args, Deserialize)]
 struct Language {
     /// Steam schema language for achievement and stat names, e.g. 'french'.
     /// Defaults to the game's own language; see `list-languages`.
     #[arg(long)]
+    #[serde(default)]
     language: Option<String>,
 }
 
 /// The orchestrator owns every Steam connection, so the CLI process itself
-/// never loads `steamclient.so`.
+/// never loads `steamclient.so`. A running `--daemon` serves instead when
+/// there is one.
+///
+/// It gets a process group of its own, so Ctrl+C in the terminal reaches only
+/// the CLI, which then winds the orchestrator down rather than losing it
+/// mid-write.
 fn spawn_orchestrator() -> Result<(), SamError> {
-    let child = BidirChild::new(ProcessCommand::new(get_executable_path()).arg("--orchestrator"))?;
+    if attach_to_daemon() {
+        return Ok(());
+    }
+    let mut command = ProcessCommand::new(get_executable_path());
+    command.arg("--orchestrator");
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    #[cfg(windows)]
+    {
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        std::os::windows::process::CommandExt::creation_flags(
+            &mut command,
+            CREATE_NEW_PROCESS_GROUP,
+        );
+    }
+    let child = BidirChild::new(&mut command)?;
     set_orchestrator(IpcClient::new(child));
     Ok(())
 }
@@ -163,9 +696,14 @@ fn spawn_orchestrator() -> Result<(), SamError> {
 pub fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    // Nothing to shut down either: an offline command never touches the backend,
+    // and `doctor` starts its own so that failing is part of its report.
+    if cli.command.is_offline() || matches!(cli.command, Command::Doctor) {
+        return run_command(cli.command);
+    }
+
     if let Err(e) = spawn_orchestrator() {
-        eprintln!("Failed to start the backend process: {e}");
-        return ExitCode::FAILURE;
+        return Failure::backend("Failed to start the backend process", e).report();
     }
 
     let code = run_command(cli.command);
@@ -173,111 +711,276 @@ pub fn main() -> ExitCode {
     code
 }
 
+impl Command {
+    fn is_offline(&self) -> bool {
+        match self {
+            Command::ListAchievements { offline, .. }
+            | Command::ListApps { offline, .. }
+            | Command::Summary { offline, .. } => *offline,
+            Command::Journal { .. } => true,
+            Command::Jobs { action } => !matches!(action, JobsAction::Resume { .. }),
+            Command::Schedule { action } => !matches!(action, ScheduleAction::SetStat { .. }),
+            _ => false,
+        }
+    }
+}
+
+/// A command's JSON result. Per-item diagnostics still go to stderr as they
+/// happen; only the result comes back, so `batch` can put it on a single line.
+type Outcome = Result<Value, Failure>;
+
 fn run_command(command: Command) -> ExitCode {
     match command {
-        Command::ListAchievements { app_id, language } => {
-            match fetch_progress(app_id, language, "achievements") {
-                Ok(progress) => print_json(&progress.achievements),
-                Err(code) => code,
-            }
+        Command::Idle { app_id } => idle(app_id),
+        Command::Events => events(),
+        Command::Batch { file, keep_going } => batch::run(&file, keep_going),
+        Command::Doctor => doctor(),
+        Command::Serve { listen } => serve::run(listen),
+        command => match execute(command) {
+            Ok(value) => print_json(&value),
+            Err(failure) => failure.report(),
+        },
+    }
+}
+
+fn execute(command: Command) -> Outcome {
+    run(command).map_err(Failure::diagnose)
+}
+
+fn run(command: Command) -> Outcome {
+    match command {
+        Command::ListAchievements {
+            app_id,
+            language,
+            filters,
+            sort,
+            offline,
+        } => {
+            let achievements = if offline {
+                offline::check_achievement_flags(&filters, sort, &language.language)?;
+                offline::achievements(app_id)?
+            } else {
+                fetch_progress(app_id, language, "achievements")?.achievements
+            };
+            to_json(&query_achievements(
+                achievements,
+                &AchievementFilter::from(&filters),
+                sort,
+            ))
         }
 
         Command::ListStatistics { app_id, language } => {
-            match fetch_progress(app_id, language, "statistics") {
-                Ok(progress) => print_json(&progress.stats),
-                Err(code) => code,
-            }
+            to_json(&fetch_progress(app_id, language, "statistics")?.stats)
         }
 
-        Command::ListLanguages { app_id } => print_json(&read_schema_languages(app_id)),
+        Command::ListLanguages { app_id } => to_json(&read_schema_languages(app_id)),
 
         Command::ListApps {
             with_achievements,
             with_playtime,
+            filters,
+            sort,
+            offline,
         } => {
-            let apps = match (GetSubscribedAppList {
-                include_playtime: with_playtime,
-                with_achievement_counts: with_achievements,
-            })
-            .request()
-            {
-                Ok(apps) => apps,
-                Err(e) => {
-                    eprintln!("Failed to get owned apps: {e}");
-                    return ExitCode::FAILURE;
-                }
+            let filter = AppFilter::from(&filters);
+            let include_playtime =
+                with_playtime || filter.hide_never_launched || sort.needs_playtime();
+            let apps = if offline {
+                offline::check_app_flags(&filters)?;
+                offline::apps(include_playtime)?
+            } else {
+                owned_apps(
+                    include_playtime,
+                    with_achievements || filter.depends_on_counts() || sort.needs_counts(),
+                )?
             };
-            print_json(&apps)
+            let search = filters.search.as_deref().unwrap_or_default();
+            to_json(&query_apps(apps, &filter, search, sort))
         }
 
-        Command::Unlock { app_id, ids } => set_achievements(app_id, ids.ids, true),
+        Command::Summary { filters, offline } => {
+            let apps = if offline {
+                offline::check_app_flags(&filters)?;
+                offline::apps(true)?
+            } else {
+                owned_apps(true, true)?
+            };
+            let search = filters.search.as_deref().unwrap_or_default();
+            let apps = query_apps(apps, &AppFilter::from(&filters), search, AppSort::AppId);
+            to_json(&summarize(&apps))
+        }
 
-        Command::Lock { app_id, ids } => set_achievements(app_id, ids.ids, false),
+        Command::Unlock { app_id, selection } => set_achievements(app_id, selection, true),
 
-        Command::UnlockAll { app_id } => match (UnlockAllAchievements { app_id }).request() {
-            Ok(true) => {
-                println!("{}", json!({"success": true}));
-                ExitCode::SUCCESS
-            }
-            Ok(false) => {
-                eprintln!("Steam did not store the unlocked achievements");
-                ExitCode::FAILURE
-            }
-            Err(e) => {
-                eprintln!("Failed to unlock all achievements: {e}");
-                ExitCode::FAILURE
-            }
-        },
+        Command::Lock { app_id, selection } => set_achievements(app_id, selection, false),
 
-        Command::LockAll { app_id } => match (ResetStats {
+        Command::UnlockAll { app_id } => stored(
             app_id,
-            achievements_too: true,
-        })
-        .request()
-        {
-            Ok(true) => {
-                println!("{}", json!({"success": true}));
-                ExitCode::SUCCESS
-            }
-            Ok(false) => {
-                eprintln!("Steam did not store the reset");
-                ExitCode::FAILURE
+            (UnlockAllAchievements { app_id }).request(),
+            "Steam did not store the unlocked achievements",
+            "Failed to unlock all achievements",
+        ),
+
+        Command::LockAll { app_id } => stored(
+            app_id,
+            (ResetStats {
+                app_id,
+                achievements_too: true,
+            })
+            .request(),
+            "Steam did not store the reset",
+            "Failed to reset all achievements",
+        ),
+
+        Command::ResetStats { app_id } => stored(
+            app_id,
+            (ResetStats {
+                app_id,
+                achievements_too: false,
+            })
+            .request(),
+            "Steam did not store the reset",
+            "Failed to reset stats",
+        ),
+
+        Command::SetStat { app_id, stats } => set_stats(app_id, &stats),
+
+        Command::Export { app_ids, pacing } => export(app_ids, (&pacing).into()),
+
+        Command::Import {
+            file,
+            app_id,
+            progress,
+            pacing,
+        } => import(file, app_id, progress, (&pacing).into()),
+
+        Command::Journal { app_id, limit } => journal(app_id, limit),
+
+        Command::Jobs { action } => match action {
+            JobsAction::List => jobs::list(),
+            JobsAction::Resume { id, pacing } => jobs::resume(id, (&pacing).into()),
+            JobsAction::Discard { id } => jobs::discard(id),
+        },
+
+        Command::Schedule { action } => match action {
+            ScheduleAction::List => schedule::list(),
+            ScheduleAction::Cancel { id } => schedule::cancel(id),
+            ScheduleAction::Unlock { app_id, ids, when } => {
+                schedule::achievements(app_id, ids, true, &when)
             }
-            Err(e) => {
-                eprintln!("Failed to reset all achievements: {e}");
-                ExitCode::FAILURE
+            ScheduleAction::Lock { app_id, ids, when } => {
+                schedule::achievements(app_id, ids, false, &when)
             }
+            ScheduleAction::SetStat {
+                app_id,
+                stats,
+                when,
+            } => schedule::stats(app_id, &stats, &when),
+            ScheduleAction::Import { file, app_id, when } => schedule::import(&file, app_id, &when),
+            ScheduleAction::Idle { app_id, when } => schedule::idle(app_id, true, &when),
+            ScheduleAction::StopIdle { app_id, when } => schedule::idle(app_id, false, &when),
         },
 
-        Command::SetStat {
-            app_id,
-            stat_id,
-            value,
-        } => set_stat(app_id, stat_id, value),
-
-        Command::Idle { app_id } => {
-            if let Err(e) = (LaunchApp { app_id }).request() {
-                eprintln!("Failed to connect to Steam: {e}");
-                return ExitCode::FAILURE;
-            }
+        Command::Servers => servers(),
 
-            if let Err(e) = install_interrupt_handler() {
-                eprintln!("Failed to install interrupt handler: {}", e);
-                return ExitCode::FAILURE;
-            }
+        // These hold the session on their own terms; `batch` cannot parse them.
+        Command::Idle { .. }
+        | Command::Events
+        | Command::Batch { .. }
+        | Command::Doctor
+        | Command::Serve { .. } => Err(Failure::invalid("This command cannot run inside a batch")),
+    }
+}
+
+fn owned_apps(
+    include_playtime: bool,
+    with_achievement_counts: bool,
+) -> Result<Vec<AppModel>, Failure> {
+    (GetSubscribedAppList {
+        include_playtime,
+        with_achievement_counts,
+    })
+    .request()
+    .map_err(|e| Failure::backend("Failed to get owned apps", e))
+}
 
-            eprintln!("Idling app {}. Press Ctrl+C to stop.", app_id);
-            while !INTERRUPTED.load(Ordering::SeqCst) {
-                std::thread::sleep(std::time::Duration::from_millis(200));
+/// `{"success": true}` once Steam has stored the change.
+fn stored(app_id: u32, result: Result<bool, SamError>, refused: &str, failed: &str) -> Outcome {
+    match result {
+        Ok(true) => Ok(json!({"success": true})),
+        Ok(false) => Err(Failure::new(refused).app(app_id)),
+        Err(e) => Err(Failure::backend(failed, e).app(app_id)),
+    }
+}
+
+fn idle(app_id: u32) -> ExitCode {
+    if let Err(e) = (LaunchApp { app_id }).request() {
+        return Failure::backend("Failed to connect to Steam", e)
+            .app(app_id)
+            .diagnose()
+            .report();
+    }
+
+    if let Err(e) = install_interrupt_handler() {
+        return Failure::new(format!("Failed to install interrupt handler: {e}")).report();
+    }
+
+    eprintln!("Idling app {}. Press Ctrl+C to stop.", app_id);
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    eprintln!("Stopping idle for app {}...", app_id);
+    // The app-server child is torn down by the orchestrator shutdown in main().
+    ExitCode::SUCCESS
+}
+
+fn events() -> ExitCode {
+    let events = subscribe_events(false);
+    if let Err(e) = install_interrupt_handler() {
+        return Failure::new(format!("Failed to install interrupt handler: {e}")).report();
+    }
+
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        match events.recv_timeout(std::time::Duration::from_millis(200)) {
+            Ok(event) => println!("{}", json!(event)),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                return Failure::new("The backend went away").report();
             }
-            eprintln!("Stopping idle for app {}...", app_id);
-            // The app-server child is torn down by the orchestrator shutdown in main().
-            ExitCode::SUCCESS
         }
+    }
+    ExitCode::SUCCESS
+}
 
-        Command::Export { app_ids } => export(app_ids),
+/// The report goes to stdout either way. Only a failed test connection fails
+/// the command: the other problems may be harmless, like an empty cache on a
+/// fresh install.
+fn doctor() -> ExitCode {
+    let local = diagnostics::collect();
+    let handshake = match spawn_orchestrator() {
+        Ok(()) => {
+            let handshake = diagnostics::handshake();
+            shutdown_and_wait();
+            handshake
+        }
+        Err(e) => Handshake {
+            ok: false,
+            steam_id64: None,
+            error: Some(format!("Failed to start the backend process: {e}")),
+            millis: 0,
+        },
+    };
+    let report = local.with_handshake(handshake);
 
-        Command::Import { file, app_id } => import(file, app_id),
+    let connected = report.handshake.as_ref().is_some_and(|h| h.ok);
+    let summary = report.problems.first().cloned();
+    match to_json(&report) {
+        Ok(value) if connected => print_json(&value),
+        Ok(value) => Failure::new(summary.unwrap_or_default())
+            .kind(ErrorKind::SteamNotRunning)
+            .with_partial(value)
+            .report(),
+        Err(failure) => failure.report(),
     }
 }
 
@@ -299,14 +1002,9 @@ fn resolve_language(app_id: u32, language: Option<String>) -> Result<String, Str
     }
 }
 
-fn fetch_progress(app_id: u32, language: Language, what: &str) -> Result<AppProgress, ExitCode> {
-    let language = match resolve_language(app_id, language.language) {
-        Ok(language) => language,
-        Err(e) => {
-            eprintln!("{e}");
-            return Err(ExitCode::FAILURE);
-        }
-    };
+fn fetch_progress(app_id: u32, language: Language, what: &str) -> Result<AppProgress, Failure> {
+    let language =
+        resolve_language(app_id, language.language).map_err(|e| Failure::invalid(e).app(app_id))?;
 
     (GetAchievementsAndStats {
         app_id,
@@ -314,71 +1012,103 @@ fn fetch_progress(app_id: u32, language: Language, what: &str) -> Result<AppProg
         language,
     })
     .request()
-    .map_err(|e| {
-        eprintln!("Failed to get {what}: {e}");
-        ExitCode::FAILURE
-    })
+    .map_err(|e| Failure::backend(&format!("Failed to get {what}"), e).app(app_id))
 }
 
 /// The schema decides whether a stat is written as an integer or a float, so look the
-/// stat up rather than making the caller declare it.
-fn set_stat(app_id: u32, stat_id: String, value: String) -> ExitCode {
-    let progress = match fetch_progress(app_id, Language { language: None }, "statistics") {
-        Ok(progress) => progress,
-        Err(code) => return code,
-    };
+/// stat up rather than making the caller declare it. Every assignment is checked
+/// before the first is sent, and all of them are saved by one store.
+fn set_stats(app_id: u32, assignments: &[String]) -> Outcome {
+    #[derive(Serialize)]
+    struct StatResult {
+        id: String,
+        previous: Value,
+        value: Value,
+        success: bool,
+    }
 
-    let Some(stat) = progress.stats.iter().find(|s| s.id() == stat_id) else {
-        eprintln!("App {app_id} has no stat named {stat_id}");
-        return ExitCode::FAILURE;
-    };
+    let assignments = parse_assignments(assignments).map_err(Failure::invalid)?;
+    let progress = fetch_progress(app_id, Language { language: None }, "statistics")?;
 
-    if (stat.permission() & 2) != 0 {
-        eprintln!("Stat {stat_id} is protected by Steam and cannot be changed");
-        return ExitCode::FAILURE;
+    let mut writes = Vec::with_capacity(assignments.len());
+    for assignment in &assignments {
+        let Some(stat) = progress.stats.iter().find(|s| s.id() == assignment.id) else {
+            return Err(Failure::invalid(format!(
+                "App {app_id} has no stat named {}",
+                assignment.id
+            ))
+            .app(app_id)
+            .item(&assignment.id));
+        };
+        let target = assignment.resolve(stat).map_err(|e| e.app(app_id))?;
+        let previous = match stat {
+            StatInfo::Integer(i) => Target::Int(i.int_value),
+            StatInfo::Float(f) => Target::Float(f.float_value),
+        };
+        writes.push((assignment.id.clone(), previous, target));
     }
 
-    let result = match stat {
-        StatInfo::Integer(_) => match value.parse::<i32>() {
-            Ok(value) => (SetIntStat {
+    let set = |id: &str, target: &Target| {
+        let stat_id = id.to_owned();
+        let sent = match *target {
+            Target::Int(value) => (SetIntStat {
                 app_id,
-                stat_id: stat_id.clone(),
+                stat_id,
                 value,
+                store: false,
             })
             .request(),
-            Err(e) => {
-                eprintln!("Stat {stat_id} takes an integer: {e}");
-                return ExitCode::FAILURE;
-            }
-        },
-        StatInfo::Float(_) => match value.parse::<f32>() {
-            Ok(value) => (SetFloatStat {
+            Target::Float(value) => (SetFloatStat {
                 app_id,
-                stat_id: stat_id.clone(),
+                stat_id,
                 value,
+                store: false,
             })
             .request(),
+        };
+        match sent {
+            Ok(set) => set.result,
             Err(e) => {
-                eprintln!("Stat {stat_id} takes a number: {e}");
-                return ExitCode::FAILURE;
+                eprintln!("Failed to set stat {id}: {e}");
+                false
             }
-        },
+        }
     };
 
-    match result {
-        Ok(true) => {
-            println!("{}", json!({"id": stat_id, "success": true}));
-            ExitCode::SUCCESS
-        }
-        other => {
-            eprintln!("Failed to set stat {stat_id}: {other:?}");
-            ExitCode::FAILURE
+    let mut results = Vec::with_capacity(writes.len());
+    for (id, previous, target) in &writes {
+        let success = set(id, target);
+        results.push(StatResult {
+            id: id.clone(),
+            previous: previous.to_json(),
+            value: target.to_json(),
+            success,
+        });
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed > 0 {
+        // All or nothing: the ones that went through are put back unstored,
+        // so that no later store commits half of the command.
+        for ((id, previous, _), result) in writes.iter().zip(&results) {
+            if result.success {
+                set(id, previous);
+            }
         }
+        return Err(Failure::new(format!(
+            "{failed} of {} stats could not be set, so none were stored",
+            results.len()
+        ))
+        .app(app_id)
+        .with_partial(to_json(&results)?));
     }
+
+    store(app_id, "stats")?;
+    to_json(&results)
 }
 
 /// Output mirrors the legacy in-process CLI: a JSON array of `{id, success}`.
-fn set_achievements(app_id: u32, ids: Vec<String>, unlocked: bool) -> ExitCode {
+fn set_achievements(app_id: u32, selection: Selection, unlocked: bool) -> Outcome {
     #[derive(Serialize)]
     struct AchievedResult {
         id: String,
@@ -386,10 +1116,59 @@ fn set_achievements(app_id: u32, ids: Vec<String>, unlocked: bool) -> ExitCode {
     }
     let verb = if unlocked { "unlock" } else { "lock" };
 
-    if let Err(e) = (LaunchApp { app_id }).request() {
-        eprintln!("Failed to connect to Steam: {e}");
-        return ExitCode::FAILURE;
-    }
+    let ids = if selection.is_exact() {
+        if selection.ids.is_empty() {
+            return Err(Failure::invalid(format!(
+                "Name the achievements to {verb}, or select them with --glob, --regex or a filter"
+            )));
+        }
+        let progress = fetch_progress(app_id, Language { language: None }, "achievements")?;
+        // Refused before anything is written, as a protected stat is.
+        if let Some(protected) = progress
+            .achievements
+            .iter()
+            .find(|a| a.permission != 0 && selection.ids.contains(&a.id))
+        {
+            return Err(Failure::new(format!(
+                "Achievement {} is protected by Steam and cannot be changed",
+                protected.id
+            ))
+            .kind(ErrorKind::Protected)
+            .app(app_id)
+            .item(&protected.id));
+        }
+        selection.ids.clone()
+    } else {
+        let progress = fetch_progress(app_id, Language { language: None }, "achievements")?;
+        let (chosen, protected): (Vec<_>, Vec<_>) = selection
+            .resolve(progress.achievements, unlocked)
+            .map_err(|e| e.app(app_id))?
+            .into_iter()
+            .partition(|a| a.permission == 0);
+        if !protected.is_empty() {
+            let ids: Vec<&str> = protected.iter().map(|a| a.id.as_str()).collect();
+            eprintln!(
+                "Skipping {} protected achievement(s): {}",
+                ids.len(),
+                ids.join(", ")
+            );
+            if chosen.is_empty() {
+                return Err(Failure::new(format!(
+                    "Every achievement the selection matches is protected by Steam and cannot be {verb}ed"
+                ))
+                .kind(ErrorKind::Protected)
+                .app(app_id));
+            }
+        }
+        if chosen.is_empty() {
+            eprintln!("No achievement left to {verb} matches the selection");
+            return to_json(&Vec::<AchievedResult>::new());
+        }
+        selection
+            .confirm(&chosen, verb)
+            .map_err(|e| e.app(app_id))?;
+        chosen.into_iter().map(|a| a.id).collect()
+    };
 
     let mut results: Vec<AchievedResult> = vec![];
     for id in ids {
@@ -400,105 +1179,197 @@ fn set_achievements(app_id: u32, ids: Vec<String>, unlocked: bool) -> ExitCode {
             store: false,
         })
         .request()
-        .unwrap_or(false);
+        .is_ok_and(|set| set.result);
         if !success {
-            println!("Failed to {verb} achievement {id}");
+            eprintln!("Failed to {verb} achievement {id}");
         }
         results.push(AchievedResult { id, success });
     }
 
     // Every set above used `store: false`, so none of them exist outside
     // Steam's client until this returns true.
+    store(app_id, "achievements")?;
+
+    let output = to_json(&results)?;
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed == 0 {
+        Ok(output)
+    } else {
+        Err(Failure::items(
+            failed,
+            results.len(),
+            format!(
+                "{failed} of {} achievements could not be {verb}ed",
+                results.len()
+            ),
+        )
+        .app(app_id)
+        .with_partial(output))
+    }
+}
+
+/// Steam's own unlocks go to stderr with the other notes, leaving the output
+/// about what was asked for.
+fn store(app_id: u32, what: &str) -> Result<(), Failure> {
     match (StoreStatsAndAchievements { app_id }).request() {
-        Ok(true) => {}
-        Ok(false) => {
-            eprintln!("Steam did not store the achievements");
-            return ExitCode::FAILURE;
-        }
-        Err(e) => {
-            eprintln!("Failed to store stats and achievements: {e:?}");
-            return ExitCode::FAILURE;
+        Ok(Stored {
+            result: true,
+            granted,
+        }) => {
+            if !granted.is_empty() {
+                let names: Vec<String> = granted
+                    .iter()
+                    .map(|a| format!("{} ({})", a.name, a.id))
+                    .collect();
+                eprintln!(
+                    "Steam also unlocked {} achievement(s) by itself: {}",
+                    granted.len(),
+                    names.join(", ")
+                );
+            }
+            Ok(())
         }
+        Ok(_) => Err(Failure::new(format!("Steam did not store the {what}")).app(app_id)),
+        Err(e) => Err(Failure::backend("Failed to store stats and achievements", e).app(app_id)),
     }
+}
 
-    print_json(&results)
+/// How a bulk request ended.
+struct Finished<T> {
+    results: T,
+    /// Whether Ctrl+C cut it short.
+    cancelled: bool,
+    /// Retries per app, for those that needed any.
+    retried: HashMap<u32, u32>,
 }
 
-fn export(app_ids: Vec<u32>) -> ExitCode {
-    let results = match (ExportApps {
-        app_ids: app_ids.clone(),
-    })
-    .request_with_progress(|done, total| eprintln!("Exported {done}/{total}"))
-    {
-        Ok(results) => results,
-        Err(e) => {
-            eprintln!("Failed to export: {e}");
-            return ExitCode::FAILURE;
+/// A bulk request that Ctrl+C cancels: the apps already running finish, the
+/// rest are never started. `job`, if any, follows each app as it finishes.
+fn cancellable<T, R>(
+    request: R,
+    verb: &str,
+    mut job: Option<&mut Running>,
+) -> Result<Finished<R::Response>, SamError>
+where
+    T: Serialize,
+    R: Request<Response = Vec<(u32, Result<T, SamError>)>>,
+{
+    if let Err(e) = install_interrupt_handler() {
+        eprintln!("Failed to install interrupt handler: {e}");
+    }
+    let canceller = Canceller::default();
+    let finished = AtomicBool::new(false);
+    std::thread::scope(|scope| {
+        // The signal handler can only set a flag, so the IPC happens here.
+        scope.spawn(|| {
+            while !finished.load(Ordering::SeqCst) {
+                if INTERRUPTED.load(Ordering::SeqCst) {
+                    eprintln!("Interrupted; waiting for the apps already started...");
+                    canceller.cancel();
+                    return;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+        });
+        let mut retried = HashMap::new();
+        let response = request.request_stepped(&canceller, |step| {
+            if let Some(job) = job.as_deref_mut() {
+                job.step(step.app_id, step.ok);
+            }
+            if step.retries > 0 {
+                eprintln!(
+                    "{verb} {}/{} (app {} after {} retries)",
+                    step.done, step.total, step.app_id, step.retries
+                );
+                retried.insert(step.app_id, step.retries);
+            } else {
+                eprintln!("{verb} {}/{}", step.done, step.total);
+            }
+        });
+        finished.store(true, Ordering::SeqCst);
+        if let Some(job) = job {
+            job.conclude(&response, canceller.is_cancelled());
         }
-    };
+        response.map(|results| Finished {
+            results,
+            cancelled: canceller.is_cancelled(),
+            retried,
+        })
+    })
+}
+
+fn export(app_ids: Vec<u32>, policy: FanOutPolicy) -> Outcome {
+    let Finished {
+        results, cancelled, ..
+    } = cancellable(
+        ExportApps {
+            app_ids: app_ids.clone(),
+            policy,
+        },
+        "Exported",
+        None,
+    )
+    .map_err(|e| Failure::backend("Failed to export", e))?;
 
     let mut by_id: std::collections::HashMap<u32, Result<AppExport, SamError>> =
         results.into_iter().collect();
 
     let mut apps: Vec<AppExport> = Vec::new();
-    let mut failed = false;
-    for app_id in app_ids {
-        match by_id.remove(&app_id) {
+    let mut failed = 0;
+    for app_id in &app_ids {
+        match by_id.remove(app_id) {
             Some(Ok(export)) => apps.push(export),
             Some(Err(e)) => {
                 eprintln!("App {app_id}: {e}");
-                failed = true;
+                failed += 1;
+            }
+            None if cancelled => {
+                eprintln!("App {app_id}: skipped, export cancelled");
+                failed += 1;
             }
             None => {
                 eprintln!("App {app_id}: missing from batch result");
-                failed = true;
+                failed += 1;
             }
         }
     }
 
-    let file = ExportFile {
+    let file = to_json(&ExportFile {
         format_version: FORMAT_VERSION,
         exported_at: iso8601_utc_now(),
         apps,
-    };
-
-    match serde_json::to_string_pretty(&file) {
-        Ok(out) => println!("{}", out),
-        Err(e) => {
-            eprintln!("Failed to serialize export: {}", e);
-            return ExitCode::FAILURE;
-        }
-    }
+    })?;
 
-    if failed {
-        ExitCode::FAILURE
+    if failed > 0 {
+        let message = if cancelled {
+            format!(
+                "Cancelled; {failed} of {} apps were not exported",
+                app_ids.len()
+            )
+        } else {
+            format!("{failed} of {} apps could not be exported", app_ids.len())
+        };
+        Err(Failure::items(failed, app_ids.len(), message).with_partial(file))
     } else {
-        ExitCode::SUCCESS
+        Ok(file)
     }
 }
 
-fn import(file: PathBuf, app_id: Option<u32>) -> ExitCode {
-    let contents = match std::fs::read_to_string(&file) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Failed to read {}: {}", file.display(), e);
-            return ExitCode::FAILURE;
-        }
-    };
-    let parsed: ExportFile = match serde_json::from_str(&contents) {
-        Ok(p) => p,
-        Err(e) => {
-            eprintln!("Failed to parse {}: {}", file.display(), e);
-            return ExitCode::FAILURE;
+fn import(
+    file: PathBuf,
+    app_id: Option<u32>,
+    progress: Option<ExportFile>,
+    policy: FanOutPolicy,
+) -> Outcome {
+    let parsed = match progress {
+        Some(progress) => progress
+            .checked()
+            .map_err(|e| Failure::invalid(e.to_string()))?,
+        None if file.as_os_str().is_empty() => {
+            return Err(Failure::invalid("Pass a file or the progress to import"));
         }
+        None => read_export(&file)?,
     };
-    if parsed.format_version != FORMAT_VERSION {
-        eprintln!(
-            "Unsupported format version: {} (this build expects {})",
-            parsed.format_version, FORMAT_VERSION
-        );
-        return ExitCode::FAILURE;
-    }
 
     #[derive(Serialize)]
     struct AppResult {
@@ -507,6 +1378,9 @@ fn import(file: PathBuf, app_id: Option<u32>) -> ExitCode {
         summary: ImportSummary,
         #[serde(skip_serializing_if = "Option::is_none")]
         error: Option<String>,
+        /// Retries Steam's timeouts cost this app.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        retries: Option<u32>,
     }
 
     let apps: Vec<AppExport> = parsed
@@ -516,70 +1390,124 @@ fn import(file: PathBuf, app_id: Option<u32>) -> ExitCode {
         .collect();
 
     if apps.is_empty() {
-        eprintln!("No matching apps to import.");
-        return ExitCode::FAILURE;
+        return Err(Failure::invalid("No matching apps to import."));
     }
 
     let app_ids: Vec<u32> = apps.iter().map(|a| a.app_id).collect();
-    let results = match (ImportApps { apps })
-        .request_with_progress(|done, total| eprintln!("Imported {done}/{total}"))
-    {
-        Ok(results) => results,
-        Err(e) => {
-            eprintln!("Failed to import: {e}");
-            return ExitCode::FAILURE;
-        }
-    };
+    let items = apps.iter().cloned().map(Item::import).collect();
+    let mut job = Running::start(Kind::Import, items);
+    let Finished {
+        results,
+        cancelled,
+        retried,
+    } = cancellable(ImportApps { apps, policy }, "Imported", Some(&mut job))
+        .map_err(|e| Failure::backend("Failed to import", e))?;
 
-    let mut by_id: std::collections::HashMap<u32, Result<ImportSummary, SamError>> =
-        results.into_iter().collect();
+    // The before-images are for the GUI's journal, which the CLI does not write.
+    let mut by_id: std::collections::HashMap<u32, Result<ImportSummary, SamError>> = results
+        .into_iter()
+        .map(|(id, result)| (id, result.map(|kept| kept.result)))
+        .collect();
 
     let mut results: Vec<AppResult> = Vec::new();
-    let mut any_failure = false;
-    for id in app_ids {
+    let mut failed = 0;
+    for id in &app_ids {
+        let id = *id;
         match by_id.remove(&id) {
             Some(Ok(summary)) => {
                 if !summary.errors.is_empty() {
-                    any_failure = true;
+                    failed += 1;
                 }
                 results.push(AppResult {
                     app_id: id,
                     summary,
                     error: None,
+                    retries: retried.get(&id).copied(),
                 });
             }
             Some(Err(e)) => {
-                any_failure = true;
+                failed += 1;
                 results.push(AppResult {
                     app_id: id,
                     summary: ImportSummary::default(),
                     error: Some(e.to_string()),
+                    retries: retried.get(&id).copied(),
                 });
             }
             None => {
-                any_failure = true;
+                failed += 1;
+                let error = if cancelled {
+                    "skipped, import cancelled"
+                } else {
+                    "missing from batch result"
+                };
                 results.push(AppResult {
                     app_id: id,
                     summary: ImportSummary::default(),
-                    error: Some("missing from batch result".to_string()),
+                    error: Some(error.to_string()),
+                    retries: None,
                 });
             }
         }
     }
 
-    match serde_json::to_string_pretty(&results) {
-        Ok(out) => println!("{}", out),
-        Err(e) => {
-            eprintln!("Failed to serialize import summary: {}", e);
-            return ExitCode::FAILURE;
-        }
+    let summary = to_json(&results)?;
+    if failed > 0 {
+        let message = if cancelled {
+            format!(
+                "Cancelled; {failed} of {} apps were not imported",
+                app_ids.len()
+            )
+        } else {
+            format!("{failed} of {} apps did not import cleanly", app_ids.len())
+        };
+        Err(Failure::items(failed, app_ids.len(), message).with_partial(summary))
+    } else {
+        Ok(summary)
     }
+}
 
-    if any_failure {
-        ExitCode::FAILURE
-    } else {
-        ExitCode::SUCCESS
+fn read_export(file: &Path) -> Result<ExportFile, Failure> {
+    export_file::load(file).map_err(|e| {
+        let message = format!("{}: {e}", file.display());
+        match e {
+            ReadError::Read(_) => Failure::new(message),
+            ReadError::Parse(_) | ReadError::Version(_) => Failure::invalid(message),
+        }
+    })
+}
+
+/// Raw entries rather than grouped operations: a dashboard can group by
+/// `batch` itself, and sees exactly what the file holds.
+fn journal(app_id: Option<u32>, limit: Option<usize>) -> Outcome {
+    let entries: Vec<action_journal::Entry> = action_journal::load()
+        .into_iter()
+        .rev()
+        .filter(|entry| app_id.is_none_or(|id| entry.app_id == id))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    to_json(&entries)
+}
+
+/// Errors read as their message here, as everywhere else in the CLI's output.
+fn servers() -> Outcome {
+    let servers = GetAppServers
+        .request()
+        .map_err(|e| Failure::backend("Failed to list the app servers", e))?;
+    let mut value = to_json(&servers)?;
+    if let Some(entries) = value.as_array_mut() {
+        for (entry, server) in entries.iter_mut().zip(&servers) {
+            if let Some(error) = &server.last_error {
+                entry["last_error"] = json!(error.to_string());
+            }
+        }
     }
+    Ok(value)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Outcome {
+    serde_json::to_value(value)
+        .map_err(|e| Failure::new(format!("Failed to serialize output: {e}")))
 }
 
 fn print_json<T: Serialize>(value: &T) -> ExitCode {
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `--offline`: answers read from Steam's on-disk caches alone, so they work
+//! with Steam closed. The files only hold what Steam last wrote there: no
+//! global percentages, no protection, and apps only once Steam has fetched
+//! their stats.
+
+use super::error::Failure;
+use super::{AchievementFilters, AppFilters};
+use crate::backend::app_lister::{AppModel, AppModelType};
+use crate::backend::library_query::AchievementSort;
+use crate::backend::local_config::{most_recent_login, parse_localconfig};
+use crate::backend::local_stats::LocalIndex;
+use crate::backend::stat_definitions::AchievementInfo;
+use crate::backend::user_unlock_times::{account_id, read_unlock_times};
+use crate::utils::steam_locator::SteamLocator;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Whoever signed in last: with Steam closed there is no session to ask.
+fn account() -> Result<u32, Failure> {
+    let path = SteamLocator::get_login_users_path()
+        .ok_or_else(|| Failure::new("No Steam install with a loginusers.vdf was found"))?;
+    most_recent_login(&path)
+        .map(account_id)
+        .map_err(|e| Failure::backend("Failed to read the signed-in account", e))
+}
+
+/// Flags that would silently match nothing, or everything, on cached data.
+pub(super) fn check_achievement_flags(
+    filters: &AchievementFilters,
+    sort: AchievementSort,
+    language: &Option<String>,
+) -> Result<(), Failure> {
+    let unsupported = [
+        (filters.protected || filters.not_protected, "--protected"),
+        (filters.hidden || filters.not_hidden, "--hidden"),
+        (filters.rarer_than.is_some(), "--rarer-than"),
+        (sort == AchievementSort::Rarity, "--sort rarity"),
+        (language.is_some(), "--language"),
+    ];
+    match unsupported.iter().find(|(used, _)| *used) {
+        Some((_, flag)) => Err(Failure::invalid(format!(
+            "{flag} needs Steam running; the on-disk cache does not record it"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The cache does not say what kind of app each is, so junk cannot be told
+/// from games.
+pub(super) fn check_app_flags(filters: &AppFilters) -> Result<(), Failure> {
+    if filters.filter_junk {
+        return Err(Failure::invalid(
+            "--filter-junk needs Steam running; the on-disk cache does not record app types",
+        ));
+    }
+    Ok(())
+}
+
+/// In English and sorted by id: the cache keeps neither translations nor the
+/// schema's order.
+pub(super) fn achievements(app_id: u32) -> Result<Vec<AchievementInfo>, Failure> {
+    let unlocks = read_unlock_times(account()?, app_id).map_err(|e| {
+        Failure::backend("No cached achievements for this app and account", e).app(app_id)
+    })?;
+    let mut achievements: Vec<AchievementInfo> = unlocks
+        .into_iter()
+        .map(|unlock| AchievementInfo {
+            id: unlock.api_name,
+            is_achieved: unlock.achieved,
+            unlock_time: unlock
+                .unlock_time
+                .map(|secs| UNIX_EPOCH + Duration::from_secs(u64::from(secs))),
+            permission: 0,
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            name: unlock.display_name,
+            description: String::new(),
+            global_achieved_percent: None,
+            is_hidden: false,
+        })
+        .collect();
+    achievements.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(achievements)
+}
+
+/// Every app with both a schema and this account's stats on disk, named after
+/// its schema. Achievement counts are always there; playtime when asked for.
+pub(super) fn apps(with_playtime: bool) -> Result<Vec<AppModel>, Failure> {
+    let account = account()?;
+    let index = LocalIndex::build(account)
+        .ok_or_else(|| Failure::new("Steam's appcache/stats folder was not found"))?;
+
+    let playtime = match SteamLocator::get_local_config_path(account) {
+        Some(path) if with_playtime => {
+            parse_localconfig(&path).map_err(|e| Failure::backend("Failed to read playtime", e))?
+        }
+        _ => Default::default(),
+    };
+
+    let mut apps: Vec<AppModel> = index
+        .read_all()
+        .into_iter()
+        .map(|(app_id, (total, unlocked))| {
+            let played = playtime.get(&app_id);
+            AppModel {
+                app_id,
+                app_name: index.game_name(app_id).unwrap_or_default(),
+                image_url: None,
+                app_type: AppModelType::App,
+                developer: String::new(),
+                metacritic_score: None,
+                playtime_minutes: played.and_then(|p| p.playtime_minutes),
+                last_played: played.and_then(|p| p.last_played),
+                achievement_count: Some(total),
+                unlocked_achievement_count: Some(unlocked),
+            }
+        })
+        .collect();
+    apps.sort_by_key(|app| app.app_id);
+    Ok(apps)
+}
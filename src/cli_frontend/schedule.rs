@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! `schedule`: operations queued in the cache folder for a later time, listed,
+//! added and cancelled. The backend that follows the schedule runs them.
+
+use super::error::{ErrorKind, Failure};
+use super::stat_edit::{Target, parse_assignments};
+use super::{Language, Outcome, When, fetch_progress, read_export};
+use crate::utils::ipc_types::AppStatValue;
+use crate::utils::json_records::now_secs;
+use crate::utils::schedule::{self, CancelError, Entry, Operation, ScheduledStat};
+use serde_json::{Value, json};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// The entry without what an import writes, which is in the file it came from.
+fn summary(entry: &Entry) -> Value {
+    let mut value = json!({
+        "id": entry.id,
+        "at": entry.at,
+        "created_at": entry.created_at,
+        "status": entry.status,
+        "app_ids": entry.operation.app_ids(),
+        "app_name": entry.app_name,
+        "journal": entry.journal,
+        "ran_at": entry.ran_at,
+        "error": entry.error,
+    });
+    value["operation"] = match &entry.operation {
+        Operation::Import { .. } => json!({ "kind": "import" }),
+        operation => json!(operation),
+    };
+    value
+}
+
+pub(super) fn list() -> Outcome {
+    Ok(Value::Array(schedule::load().iter().map(summary).collect()))
+}
+
+pub(super) fn cancel(id: u64) -> Outcome {
+    match schedule::cancel(id) {
+        Ok(entry) => Ok(summary(&entry)),
+        Err(CancelError::NotFound) => Err(Failure::invalid(format!("No scheduled operation {id}"))),
+        Err(e) => Err(Failure::new(e.to_string())),
+    }
+}
+
+impl When {
+    /// Unix seconds.
+    fn resolve(&self) -> Result<u64, Failure> {
+        let now = now_secs();
+        match (self.at, self.delay) {
+            (Some(at), _) => Ok(at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)),
+            (None, Some(delay)) => Ok(now.saturating_add(delay)),
+            (None, None) => Err(Failure::invalid("Pass --at or --in")),
+        }
+    }
+}
+
+fn add(app_name: &str, operation: Operation, when: &When) -> Outcome {
+    let at = when.resolve()?;
+    if at < now_secs() {
+        eprintln!("That time has passed: it runs as soon as the backend looks at the schedule.");
+    }
+    // The CLI does not know the account the journal would file it under.
+    let entry = schedule::add(at, app_name, operation, when.journal, 0);
+    Ok(summary(&entry))
+}
+
+/// Checked now, as `unlock` and `lock` check them: an id the app lacks or
+/// protects is refused before anything is queued.
+pub(super) fn achievements(app_id: u32, ids: Vec<String>, unlocked: bool, when: &When) -> Outcome {
+    let progress = fetch_progress(app_id, Language { language: None }, "achievements")?;
+    for id in &ids {
+        let Some(achievement) = progress.achievements.iter().find(|a| &a.id == id) else {
+            return Err(
+                Failure::invalid(format!("App {app_id} has no achievement named {id}"))
+                    .app(app_id)
+                    .item(id),
+            );
+        };
+        if achievement.permission != 0 {
+            return Err(Failure::new(format!(
+                "Achievement {id} is protected by Steam and cannot be changed"
+            ))
+            .kind(ErrorKind::Protected)
+            .app(app_id)
+            .item(id));
+        }
+    }
+    let operation = Operation::Achievements {
+        app_id,
+        ids,
+        unlocked,
+    };
+    add("", operation, when)
+}
+
+/// Checked now, against the values the app has now. `ID+=N` and `ID-=N` are
+/// kept as changes, added to whatever the stat holds when the entry runs.
+pub(super) fn stats(app_id: u32, assignments: &[String], when: &When) -> Outcome {
+    let assignments = parse_assignments(assignments).map_err(Failure::invalid)?;
+    let progress = fetch_progress(app_id, Language { language: None }, "statistics")?;
+    let mut stats = Vec::with_capacity(assignments.len());
+    for assignment in &assignments {
+        let Some(stat) = progress.stats.iter().find(|s| s.id() == assignment.id) else {
+            return Err(Failure::invalid(format!(
+                "App {app_id} has no stat named {}",
+                assignment.id
+            ))
+            .app(app_id)
+            .item(&assignment.id));
+        };
+        let target = assignment.resolve(stat).map_err(|e| e.app(app_id))?;
+        let change = assignment.change(stat).map_err(|e| e.app(app_id))?;
+        let relative = change.is_some();
+        let value = match change.unwrap_or(target) {
+            Target::Int(value) => AppStatValue::Int(value),
+            Target::Float(value) => AppStatValue::Float(value),
+        };
+        stats.push(ScheduledStat {
+            id: assignment.id.clone(),
+            value,
+            relative,
+        });
+    }
+    add("", Operation::Stats { app_id, stats }, when)
+}
+
+pub(super) fn import(file: &Path, app_id: Option<u32>, when: &When) -> Outcome {
+    let parsed = read_export(file)?;
+    let apps: Vec<_> = parsed
+        .apps
+        .into_iter()
+        .filter(|a| app_id.is_none_or(|wanted| wanted == a.app_id))
+        .collect();
+    let app_name = match apps.as_slice() {
+        [] => return Err(Failure::invalid("No matching apps to import.")),
+        [app] => app.app_name.clone(),
+        _ => String::new(),
+    };
+    add(&app_name, Operation::Import { apps }, when)
+}
+
+pub(super) fn idle(app_id: u32, start: bool, when: &When) -> Outcome {
+    let operation = if start {
+        Operation::StartIdle { app_id }
+    } else {
+        Operation::StopIdle { app_id }
+    };
+    add("", operation, when)
+}
@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Which achievements `unlock` and `lock` act on: exact API names, or patterns
+//! and metadata filters resolved against the app's current listing.
+
+use super::AchievementFilters;
+use super::error::Failure;
+use crate::backend::library_query::AchievementFilter;
+use crate::backend::stat_definitions::AchievementInfo;
+use clap::{Args, ValueEnum};
+use regex_lite::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::io::{BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `serve`: whoever sent the command is not at this terminal.
+pub(super) static UNATTENDED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Args, Deserialize)]
+pub(super) struct Selection {
+    /// Achievement API names to act on. With a selector below, only those of
+    /// them that also match it.
+    #[serde(default)]
+    pub ids: Vec<String>,
+    /// Only achievements whose text matches this glob (* and ?), ignoring case.
+    #[arg(long, value_name = "GLOB", conflicts_with = "regex")]
+    #[serde(default)]
+    glob: Option<String>,
+    /// Only achievements whose text matches this regular expression, ignoring case.
+    #[arg(long, value_name = "REGEX")]
+    #[serde(default)]
+    regex: Option<String>,
+    /// What --glob and --regex are matched against.
+    #[arg(long, value_name = "FIELD", default_value = "any")]
+    #[serde(default)]
+    match_in: MatchField,
+    #[command(flatten)]
+    #[serde(flatten)]
+    filters: AchievementFilters,
+    /// Act on a selection without listing it and asking first.
+    #[arg(long)]
+    #[serde(default)]
+    yes: bool,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MatchField {
+    Id,
+    Name,
+    Description,
+    #[default]
+    Any,
+}
+
+impl Selection {
+    /// Plain API names are sent as given, without fetching the listing or asking.
+    pub fn is_exact(&self) -> bool {
+        self.glob.is_none()
+            && self.regex.is_none()
+            && AchievementFilter::from(&self.filters) == AchievementFilter::default()
+    }
+
+    /// The achievements this selection names that are not already `unlocked`,
+    /// in schema order.
+    pub fn resolve(
+        &self,
+        achievements: Vec<AchievementInfo>,
+        unlocked: bool,
+    ) -> Result<Vec<AchievementInfo>, Failure> {
+        let pattern = match (&self.glob, &self.regex) {
+            (Some(glob), _) => Some(compile(&glob_to_regex(glob))?),
+            (_, Some(regex)) => Some(compile(regex)?),
+            _ => None,
+        };
+        let filter = AchievementFilter::from(&self.filters);
+
+        Ok(achievements
+            .into_iter()
+            .filter(|a| a.is_achieved != unlocked)
+            .filter(|a| self.ids.is_empty() || self.ids.contains(&a.id))
+            .filter(|a| filter.keeps(a))
+            .filter(|a| pattern.as_ref().is_none_or(|p| self.matches(p, a)))
+            .collect())
+    }
+
+    fn matches(&self, pattern: &Regex, achievement: &AchievementInfo) -> bool {
+        let id = || pattern.is_match(&achievement.id);
+        let name = || pattern.is_match(&achievement.name);
+        let description = || pattern.is_match(&achievement.description);
+        match self.match_in {
+            MatchField::Id => id(),
+            MatchField::Name => name(),
+            MatchField::Description => description(),
+            MatchField::Any => id() || name() || description(),
+        }
+    }
+
+    /// Lists the selection on stderr and asks before anything is sent. Without a
+    /// terminal to ask on, only `--yes` lets it through.
+    pub fn confirm(&self, chosen: &[AchievementInfo], verb: &str) -> Result<(), Failure> {
+        if self.yes {
+            return Ok(());
+        }
+        eprintln!("Will {verb} {} achievement(s):", chosen.len());
+        for achievement in chosen {
+            match achievement.global_achieved_percent {
+                Some(percent) => {
+                    eprintln!("  {}\t{} ({percent:.1}%)", achievement.id, achievement.name)
+                }
+                None => eprintln!("  {}\t{}", achievement.id, achievement.name),
+            }
+        }
+
+        let stdin = std::io::stdin();
+        if UNATTENDED.load(Ordering::SeqCst) {
+            return Err(Failure::invalid(format!(
+                "Not confirmed: pass \"yes\": true to {verb} a selection over the API"
+            )));
+        }
+        if !stdin.is_terminal() {
+            return Err(Failure::invalid(format!(
+                "Not confirmed: pass --yes to {verb} a selection without a terminal"
+            )));
+        }
+        eprint!("Proceed? [y/N] ");
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        if stdin.lock().read_line(&mut answer).is_err() || !answer.trim().eq_ignore_ascii_case("y")
+        {
+            return Err(Failure::new("Cancelled; nothing was changed"));
+        }
+        Ok(())
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, Failure> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| Failure::invalid(format!("Invalid pattern '{pattern}': {e}")))
+}
+
+/// Anchored, so `ACH_*` does not also match `SECRET_ACH_1`.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex_lite::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn achievement(id: &str, name: &str, is_achieved: bool) -> AchievementInfo {
+        AchievementInfo {
+            id: id.to_owned(),
+            is_achieved,
+            unlock_time: None,
+            permission: 0,
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            name: name.to_owned(),
+            description: String::new(),
+            global_achieved_percent: None,
+            is_hidden: false,
+        }
+    }
+
+    #[test]
+    fn globs_match_whole_text_and_skip_what_is_already_done() {
+        let selection: Selection =
+            serde_json::from_str(r#"{"glob": "ach_win_*", "match_in": "id"}"#).unwrap();
+        assert!(!selection.is_exact());
+        let chosen = selection
+            .resolve(
+                vec![
+                    achievement("ACH_WIN_ONE_GAME", "Winner", false),
+                    achievement("ACH_WIN_100_GAMES", "Champion", true),
+                    achievement("SECRET_ACH_WIN_X", "Hidden", false),
+                ],
+                true,
+            )
+            .unwrap_or_else(|_| panic!("valid pattern"));
+        let ids: Vec<&str> = chosen.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["ACH_WIN_ONE_GAME"]);
+    }
+}
@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `samrewritten serve`: the `batch` commands over HTTP on localhost. A request
+//! body is one script line and the response is its result, so anything a script
+//! can do, a dashboard can too, over the one backend session.
+
+use super::batch::run_object;
+use super::error::{ErrorKind, Failure};
+use super::select::UNATTENDED;
+use super::{INTERRUPTED, install_interrupt_handler};
+use crate::utils::app_paths::get_app_cache_dir;
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const TOKEN_FILE: &str = "api_token";
+
+/// Imports of a whole library run to a few megabytes.
+const MAX_BODY: u64 = 64 * 1024 * 1024;
+
+pub(super) fn run(listen: SocketAddr) -> ExitCode {
+    // Remote dashboards go through something that terminates TLS on this host.
+    if !listen.ip().is_loopback() {
+        return Failure::invalid(format!(
+            "{listen} is not a loopback address; the API only listens on this machine"
+        ))
+        .report();
+    }
+
+    let path = token_path();
+    let token = match load_or_create_token(&path) {
+        Ok(token) => token,
+        Err(e) => {
+            return Failure::new(format!("Failed to set up {}: {e}", path.display())).report();
+        }
+    };
+
+    let server = match Server::http(listen) {
+        Ok(server) => server,
+        Err(e) => return Failure::new(format!("Failed to listen on {listen}: {e}")).report(),
+    };
+    if let Err(e) = install_interrupt_handler() {
+        return Failure::new(format!("Failed to install interrupt handler: {e}")).report();
+    }
+    UNATTENDED.store(true, Ordering::SeqCst);
+    eprintln!(
+        "Listening on http://{listen}/v1/command; the bearer token is in {}",
+        path.display()
+    );
+
+    // One request at a time, as the backend takes them anyway. A request that
+    // is running when Ctrl+C arrives still gets its answer.
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        match server.recv_timeout(Duration::from_millis(250)) {
+            Ok(Some(request)) => handle(request, &token),
+            Ok(None) => {}
+            Err(e) => return Failure::new(format!("Failed to accept a request: {e}")).report(),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn handle(mut request: Request, token: &str) {
+    let (status, body) = route(&mut request, token);
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                .expect("static header is valid"),
+        );
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to answer a request: {e}");
+    }
+}
+
+fn route(request: &mut Request, token: &str) -> (u16, Value) {
+    let authorized = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .and_then(|header| header.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|given| same(given.trim(), token));
+    if !authorized {
+        return refused(401, "unauthorized", "Missing or wrong bearer token");
+    }
+
+    let path = request.url().split('?').next().unwrap_or_default();
+    if path != "/v1/command" {
+        return refused(404, "not_found", "The only endpoint is /v1/command");
+    }
+    if *request.method() != Method::Post {
+        return refused(405, "method_not_allowed", "Send commands with POST");
+    }
+
+    let too_large = || {
+        refused(
+            413,
+            "too_large",
+            &format!("The body is over {} MiB", MAX_BODY / 1024 / 1024),
+        )
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length as u64 > MAX_BODY)
+    {
+        return too_large();
+    }
+    // One byte past the limit, so a chunked body that runs over is told apart
+    // from one that just fits.
+    let mut text = String::new();
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut text)
+    {
+        return refused(400, "invalid", &format!("Failed to read the body: {e}"));
+    }
+    if text.len() as u64 > MAX_BODY {
+        return too_large();
+    }
+
+    let (result, kind) = run_object(text.trim());
+    (kind.map_or(200, status_of), result)
+}
+
+/// Statuses are for proxies and logs; the body's `error.kind` is what to branch on.
+fn status_of(kind: ErrorKind) -> u16 {
+    match kind {
+        ErrorKind::Invalid => 400,
+        ErrorKind::Protected => 403,
+        ErrorKind::AppNotOwned => 404,
+        ErrorKind::Partial => 207,
+        ErrorKind::SteamNotRunning => 503,
+        ErrorKind::Timeout => 504,
+        ErrorKind::Failed => 500,
+    }
+}
+
+/// Shaped like a failed command's result, so clients parse one thing.
+fn refused(status: u16, kind: &str, message: &str) -> (u16, Value) {
+    let body = json!({
+        "success": false,
+        "error": {"kind": kind, "message": message},
+        "result": null,
+    });
+    (status, body)
+}
+
+/// Compares every byte, so timing does not give away how much was right.
+fn same(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn token_path() -> PathBuf {
+    get_app_cache_dir().join(TOKEN_FILE)
+}
+
+/// Kept across runs so a dashboard is configured once. Delete the file for a
+/// new one.
+fn load_or_create_token(path: &Path) -> std::io::Result<String> {
+    if let Ok(token) = std::fs::read_to_string(path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_owned());
+    }
+
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| std::io::Error::other(e.to_string()))?;
+    let token: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{token}")?;
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_map_to_statuses_and_tokens_compare_whole() {
+        assert_eq!(status_of(ErrorKind::SteamNotRunning), 503);
+        assert_eq!(status_of(ErrorKind::Partial), 207);
+        assert!(same("abc123", "abc123"));
+        assert!(!same("abc12", "abc123"));
+        assert!(!same("abc124", "abc123"));
+    }
+}
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `set-stat` assignments: `ID=VALUE`, `ID+=N`, `ID-=N`, and `ID=min`,
+//! `ID=max` or `ID=default` from the schema, each resolved to an absolute
+//! value and checked the same way an import is before anything is sent.
+
+use super::error::{ErrorKind, Failure};
+use crate::backend::app_manager::StatState;
+use crate::backend::progress_io::{WriteDecision, classify_stat};
+use crate::backend::stat_definitions::StatInfo;
+use serde_json::{Value, json};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+enum Op {
+    Set(String),
+    Add(String),
+    Subtract(String),
+    Min,
+    Max,
+    Default,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Assignment {
+    pub id: String,
+    op: Op,
+}
+
+/// A checked write, ready to send.
+pub(super) enum Target {
+    Int(i32),
+    Float(f32),
+}
+
+impl Target {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Target::Int(value) => json!(value),
+            Target::Float(value) => json!(value),
+        }
+    }
+}
+
+impl FromStr for Assignment {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let Some((left, value)) = text.split_once('=') else {
+            return Err(format!("'{text}' is not ID=VALUE, ID+=N or ID-=N"));
+        };
+        let value = value.trim();
+        let (id, op) = if let Some(id) = left.strip_suffix('+') {
+            (id, Op::Add(value.to_owned()))
+        } else if let Some(id) = left.strip_suffix('-') {
+            (id, Op::Subtract(value.to_owned()))
+        } else {
+            let op = match value.to_lowercase().as_str() {
+                "min" => Op::Min,
+                "max" => Op::Max,
+                "default" => Op::Default,
+                _ => Op::Set(value.to_owned()),
+            };
+            (left, op)
+        };
+        let id = id.trim();
+        if id.is_empty() || value.is_empty() {
+            return Err(format!("'{text}' is not ID=VALUE, ID+=N or ID-=N"));
+        }
+        Ok(Assignment {
+            id: id.to_owned(),
+            op,
+        })
+    }
+}
+
+/// `set-stat 480 NumGames 10` predates assignments and still means `NumGames=10`.
+pub(super) fn parse_assignments(args: &[String]) -> Result<Vec<Assignment>, String> {
+    if let [id, value] = args
+        && !id.contains('=')
+        && !value.contains('=')
+    {
+        return Ok(vec![format!("{id}={value}").parse()?]);
+    }
+    let assignments: Vec<Assignment> = args
+        .iter()
+        .map(|arg| arg.parse())
+        .collect::<Result<_, _>>()?;
+    // Each resolves against the value read before any is written, so a second
+    // `ID+=N` would not add to the first.
+    for (at, assignment) in assignments.iter().enumerate() {
+        if assignments[..at].iter().any(|a| a.id == assignment.id) {
+            return Err(format!("Stat {} is assigned more than once", assignment.id));
+        }
+    }
+    Ok(assignments)
+}
+
+trait StatNumber: Copy + PartialOrd + FromStr + Display {
+    const KIND: &'static str;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+}
+
+impl StatNumber for i32 {
+    const KIND: &'static str = "an integer";
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i32::checked_add(self, other)
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        i32::checked_sub(self, other)
+    }
+}
+
+impl StatNumber for f32 {
+    const KIND: &'static str = "a number";
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|sum| sum.is_finite())
+    }
+
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(self - other).filter(|difference| difference.is_finite())
+    }
+}
+
+impl Assignment {
+    /// The value to write, or why Steam would not take it.
+    pub fn resolve(&self, stat: &StatInfo) -> Result<Target, Failure> {
+        if (stat.permission() & 2) != 0 {
+            return Err(Failure::new(format!(
+                "Stat {} is protected by Steam and cannot be changed",
+                self.id
+            ))
+            .kind(ErrorKind::Protected)
+            .item(&self.id));
+        }
+        let resolved = match stat {
+            StatInfo::Integer(i) => self
+                .target(StatState {
+                    min: i.min_value,
+                    max: i.max_value,
+                    increment_only: i.is_increment_only,
+                    default: i.default_value,
+                    current: Some(i.int_value),
+                })
+                .map(Target::Int),
+            StatInfo::Float(f) => self
+                .target(StatState {
+                    min: f.min_value,
+                    max: f.max_value,
+                    increment_only: f.is_increment_only,
+                    default: f.default_value,
+                    current: Some(f.float_value),
+                })
+                .map(Target::Float),
+        };
+        resolved.map_err(|e| Failure::invalid(e).item(&self.id))
+    }
+
+    /// For `ID+=N` and `ID-=N`, the signed amount, which a scheduled entry
+    /// adds to whatever the stat holds when it runs. `None` for the others.
+    pub fn change(&self, stat: &StatInfo) -> Result<Option<Target>, Failure> {
+        let (text, subtract) = match &self.op {
+            Op::Add(text) => (text, false),
+            Op::Subtract(text) => (text, true),
+            _ => return Ok(None),
+        };
+        let id = &self.id;
+        let fail = |e: String| Failure::invalid(e).item(id);
+        let invalid = |kind: &str| fail(format!("Stat {id} takes {kind}, not '{text}'"));
+        let change = match stat {
+            StatInfo::Integer(_) => {
+                let n: i32 = text.parse().map_err(|_| invalid(i32::KIND))?;
+                let n = if subtract { n.checked_neg() } else { Some(n) };
+                Target::Int(n.ok_or_else(|| fail(format!("Stat {id} would overflow")))?)
+            }
+            StatInfo::Float(_) => {
+                let n: f32 = text.parse().map_err(|_| invalid(f32::KIND))?;
+                Target::Float(if subtract { -n } else { n })
+            }
+        };
+        Ok(Some(change))
+    }
+
+    fn target<T: StatNumber>(&self, state: StatState<T>) -> Result<T, String> {
+        let id = &self.id;
+        let current = state.current.unwrap_or(state.default);
+        let number = |text: &str| {
+            text.parse::<T>()
+                .map_err(|_| format!("Stat {id} takes {}, not '{text}'", T::KIND))
+        };
+        let overflow = || format!("Stat {id} would overflow");
+        let target = match &self.op {
+            Op::Set(value) => number(value)?,
+            Op::Add(value) => current.checked_add(number(value)?).ok_or_else(overflow)?,
+            Op::Subtract(value) => current.checked_sub(number(value)?).ok_or_else(overflow)?,
+            Op::Min => state.min,
+            Op::Max => state.max,
+            Op::Default => state.default,
+        };
+
+        match classify_stat(target, &state) {
+            WriteDecision::Write => Ok(target),
+            WriteDecision::OutOfRangeHigh { max } => Err(format!(
+                "Stat {id} cannot be {target}: its maximum is {max}"
+            )),
+            WriteDecision::OutOfRangeLow { min } => Err(format!(
+                "Stat {id} cannot be {target}: its minimum is {min}"
+            )),
+            WriteDecision::IncrementOnlyResetFixable { current } => Err(format!(
+                "Stat {id} only increases and is {current}; run reset-stats first to lower it to {target}"
+            )),
+            WriteDecision::IncrementOnlyHard { default } => Err(format!(
+                "Stat {id} only increases and cannot go below its default of {default}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(current: i32, increment_only: bool) -> StatState<i32> {
+        StatState {
+            min: 0,
+            max: 100,
+            increment_only,
+            default: 0,
+            current: Some(current),
+        }
+    }
+
+    #[test]
+    fn assignments_resolve_against_the_schema() {
+        let resolve = |text: &str, state| text.parse::<Assignment>().unwrap().target(state);
+
+        assert_eq!(resolve("Kills=7", state(3, false)), Ok(7));
+        assert_eq!(resolve("Kills+=5", state(3, false)), Ok(8));
+        assert_eq!(resolve("Kills-=1", state(3, false)), Ok(2));
+        assert_eq!(resolve("Kills=max", state(3, false)), Ok(100));
+        assert!(resolve("Kills+=98", state(3, false)).is_err());
+        assert!(resolve("Kills-=1", state(3, true)).is_err());
+        assert!(resolve("Kills=default", state(3, true)).is_err());
+        assert!(resolve("Kills=1.5", state(3, false)).is_err());
+
+        let legacy = parse_assignments(&["Kills".to_owned(), "7".to_owned()]).unwrap();
+        assert_eq!(legacy, ["Kills=7".parse::<Assignment>().unwrap()]);
+        assert!(parse_assignments(&["Kills".to_owned()]).is_err());
+        assert!(parse_assignments(&["Kills+=1".to_owned(), "Kills+=1".to_owned()]).is_err());
+    }
+}
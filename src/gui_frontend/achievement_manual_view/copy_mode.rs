@@ -275,7 +275,7 @@ pub(super) fn install_copy_mode(
                     });
                 match fetch_copy_source(app_id_val, steam_id64).await {
                     Ok(source) => apply_friend(friend, source),
-                    Err(SamError::ProfilePrivate) => {
+                    Err(e) if *e.kind() == SamError::ProfilePrivate => {
                         let _ = settings.set_string("copy-timing-friend", "");
                     }
                     Err(_) => {}
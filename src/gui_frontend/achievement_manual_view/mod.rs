@@ -328,7 +328,9 @@ pub fn create_achievements_manual_view(
         }
     ));
 
-    header.start_button.connect_clicked(clone!(
+    // Both the start button and the `start_timed_unlock` action, which comes
+    // with its own duration instead of the popover's.
+    let start_unlock: Rc<dyn Fn(u64)> = Rc::new(clone!(
         #[strong]
         queue,
         #[strong]
@@ -341,13 +343,11 @@ pub fn create_achievements_manual_view(
         application,
         #[weak(rename_to = raw_model)]
         raw_model,
-        #[weak(rename_to = duration_spin)]
-        config.duration_spin,
         #[weak(rename_to = spacing_random)]
         config.spacing_random,
         #[weak(rename_to = achievement_views_stack)]
         achievement_views_stack,
-        move |_| {
+        move |desired_minutes: u64| {
             let ids = queue.snapshot();
             if ids.is_empty() {
                 return;
@@ -359,7 +359,6 @@ pub fn create_achievements_manual_view(
                 None => return,
             };
 
-            let desired_minutes = duration_spin.value_as_int().max(0) as u64;
             if desired_minutes == 0 {
                 dev_println!(
                     "CLIENT",
@@ -404,6 +403,43 @@ pub fn create_achievements_manual_view(
         }
     ));
 
+    header.start_button.connect_clicked(clone!(
+        #[strong]
+        start_unlock,
+        #[weak(rename_to = duration_spin)]
+        config.duration_spin,
+        move |_| start_unlock(duration_spin.value_as_int().max(0) as u64)
+    ));
+
+    // `(minutes, count)`: a count stages that many of the commonest locked
+    // achievements first, as auto-fill does, and zero keeps what is staged.
+    let action_start_timed_unlock = SimpleAction::new(
+        "start_timed_unlock",
+        Some(&<(u32, u32)>::static_variant_type()),
+    );
+    action_start_timed_unlock.set_enabled(false);
+    action_start_timed_unlock.connect_activate(clone!(
+        #[strong]
+        queue,
+        #[strong]
+        start_unlock,
+        #[weak(rename_to = raw_model)]
+        raw_model,
+        #[weak(rename_to = queue_label)]
+        header.queue_label,
+        move |_, parameter| {
+            let Some((minutes, count)) = parameter.and_then(|p| p.get::<(u32, u32)>()) else {
+                return;
+            };
+            if count > 0 {
+                queue.auto_fill(&raw_model, count as usize);
+                update_queue_label(&queue_label, &queue);
+            }
+            start_unlock(u64::from(minutes));
+        }
+    ));
+    application.add_action(&action_start_timed_unlock);
+
     install_copy_mode(
         &copy,
         &copy_queue,
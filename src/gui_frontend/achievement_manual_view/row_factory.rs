@@ -148,15 +148,18 @@ pub(super) fn install_row_factory(
                             // Steam accepting the call and then failing to
                             // store it, which is a failure like any other.
                             match result {
-                                Ok(true) => {
-                                    Batch::new(Op::ManualToggle, app_id_val, "").record(vec![
-                                        Change::Achievement {
-                                            id: achievement_object.id(),
-                                            name: achievement_object.name(),
-                                            before: !unlocked,
-                                            after: unlocked,
-                                        },
-                                    ]);
+                                Ok(stored) if stored.result => {
+                                    let toggle = Change::Achievement {
+                                        id: achievement_object.id(),
+                                        name: achievement_object.name(),
+                                        before: !unlocked,
+                                        after: unlocked,
+                                    };
+                                    Batch::new(Op::ManualToggle, app_id_val, "").record(
+                                        std::iter::once(toggle)
+                                            .chain(Change::granted(stored.granted))
+                                            .collect(),
+                                    );
                                     let cur = app_unlocked_achievements_count.get();
                                     let new_unlocked = if unlocked { cur + 1 } else { cur - 1 };
                                     app_unlocked_achievements_count.set(new_unlocked);
@@ -170,7 +173,7 @@ pub(super) fn install_row_factory(
                                         .set_sensitive(new_unlocked != raw_model_len as usize);
                                     update_autofill();
                                 }
-                                Ok(false) => {
+                                Ok(_) => {
                                     eprintln!("[CLIENT] Steam did not store the achievement");
                                     achievement_object.set_is_achieved(!unlocked);
                                 }
@@ -34,7 +34,7 @@ pub fn create_achievements_view(
     app_unlocked_achievements_count: Rc<Cell<usize>>,
     application: &MainApplication,
     app_achievement_count_value: &Label,
-) -> (Stack, ListStore, StringFilter, Arc<AtomicBool>) {
+) -> (Stack, ListStore, StringFilter, Arc<AtomicBool>, ListStore) {
     let app_achievements_model = ListStore::new::<GAchievementObject>();
     let app_timed_achievements_model = ListStore::new::<GAchievementObject>();
 
@@ -98,5 +98,6 @@ pub fn create_achievements_view(
         app_achievements_model,
         app_achievement_string_filter,
         cancel_timed_unlock,
+        app_timed_achievements_model,
     )
 }
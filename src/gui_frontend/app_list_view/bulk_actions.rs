@@ -14,18 +14,42 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::achievement_loader::AchievementLoader;
+use super::report_dialog::{LastReport, show_list_with_report};
 use crate::gui_frontend::MainApplication;
 use crate::gui_frontend::application_actions::{set_app_action_enabled, set_bulk_actions_enabled};
-use crate::gui_frontend::dialogs::show_list_dialog;
 use crate::gui_frontend::gobjects::steam_app::GSteamAppObject;
+use crate::gui_frontend::gsettings::fan_out_policy;
 use crate::gui_frontend::i18n::{tr, tr_noop};
-use crate::gui_frontend::request::{Request, ResetApps, UnlockAllApps};
+use crate::gui_frontend::request::{Canceller, Request, ResetApps, UnlockAllApps};
 use crate::utils::action_journal::{Batch, Change, Op};
+use crate::utils::bulk_jobs::{Item, Kind, Running};
+use crate::utils::bulk_report::Report;
 use gtk::gio::{ListStore, SimpleAction, spawn_blocking};
 use gtk::glib::{MainContext, clone};
 use gtk::prelude::*;
 use gtk::{GridView, Label, MenuButton, glib};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
+
+/// What the loading popover's Cancel button stops. Bulk operations take turns,
+/// so it only ever holds the one running.
+#[derive(Clone, Default)]
+pub struct BulkCancel(Rc<RefCell<Option<Canceller>>>);
+
+impl BulkCancel {
+    pub fn start(&self) -> Canceller {
+        let canceller = Canceller::default();
+        *self.0.borrow_mut() = Some(canceller.clone());
+        canceller
+    }
+
+    fn cancel(&self) {
+        if let Some(canceller) = self.0.borrow().as_ref() {
+            canceller.cancel();
+        }
+    }
+}
 
 /// Never empty: an unnamed app would drop out of the failure list entirely.
 fn app_label(names: &HashMap<u32, String>, app_id: u32) -> String {
@@ -42,6 +66,15 @@ fn all_names(names: HashMap<u32, String>) -> Vec<String> {
     out
 }
 
+/// Apps a cancelled run never got to, which its results leave out.
+pub(super) fn skipped_names<T>(names: &HashMap<u32, String>, results: &[(u32, T)]) -> Vec<String> {
+    let mut pending = names.clone();
+    for (app_id, _) in results {
+        pending.remove(app_id);
+    }
+    all_names(pending)
+}
+
 pub fn create_bulk_actions(
     application: &MainApplication,
     grid_view: &GridView,
@@ -51,7 +84,15 @@ pub fn create_bulk_actions(
     context_menu_button_loading: &MenuButton,
     context_menu_button_loading_progress_label: &Label,
     context_menu_button_info_label: &Label,
-) -> (SimpleAction, SimpleAction, SimpleAction, SimpleAction) {
+    bulk_cancel: &BulkCancel,
+    last_report: &LastReport,
+) -> (
+    SimpleAction,
+    SimpleAction,
+    SimpleAction,
+    SimpleAction,
+    SimpleAction,
+) {
     let action_select_all_apps = SimpleAction::new("select_all_apps", None);
     action_select_all_apps.connect_activate(clone!(
         #[weak]
@@ -104,6 +145,10 @@ pub fn create_bulk_actions(
         context_menu_button_loading_progress_label,
         #[weak]
         context_menu_button_info_label,
+        #[strong]
+        bulk_cancel,
+        #[strong]
+        last_report,
         move |_, _| {
             let Some(selection_model) = grid_view.model() else {
                 return;
@@ -159,35 +204,45 @@ pub fn create_bulk_actions(
                 }
             });
 
+            let canceller = bulk_cancel.start();
+            let cancelled = canceller.clone();
+            let policy = fan_out_policy();
             let handle = spawn_blocking(move || {
                 let names: HashMap<u32, String> = apps_to_unlock.clone();
+                let items = apps_to_unlock.iter().map(|(id, name)| Item::new(*id, name));
+                let mut job = Running::start(Kind::UnlockAll, items.collect());
                 let app_ids: Vec<u32> = apps_to_unlock.into_keys().collect();
                 let mut last_done = 0usize;
-                let results =
-                    match (UnlockAllApps { app_ids }).request_with_progress(|done, total| {
-                        if done == last_done {
-                            return;
-                        }
-                        last_done = done;
-                        let label = progress_label_for_thread.clone();
-                        MainContext::default().invoke(move || {
-                            if let Some(l) = label.upgrade() {
-                                l.set_text(
-                                    &tr("Unlocking {done} / {total} app(s)…")
-                                        .replace("{done}", &done.to_string())
-                                        .replace("{total}", &total.to_string()),
-                                );
-                            }
-                        });
-                    }) {
-                        Ok(results) => results,
-                        Err(e) => {
-                            eprintln!("[CLIENT] Bulk unlock failed: {e}");
-                            return all_names(names);
+                let request = UnlockAllApps { app_ids, policy };
+                let response = request.request_stepped(&canceller, |step| {
+                    job.step(step.app_id, step.ok);
+                    let (done, total) = (step.done, step.total);
+                    if done == last_done {
+                        return;
+                    }
+                    last_done = done;
+                    let label = progress_label_for_thread.clone();
+                    MainContext::default().invoke(move || {
+                        if let Some(l) = label.upgrade() {
+                            l.set_text(
+                                &tr("Unlocking {done} / {total} app(s)…")
+                                    .replace("{done}", &done.to_string())
+                                    .replace("{total}", &total.to_string()),
+                            );
                         }
-                    };
+                    });
+                });
+                job.conclude(&response, canceller.is_cancelled());
+                let report = Report::from_job(job.job(), response.as_ref().err());
+                let results = match response {
+                    Ok(results) => results,
+                    Err(e) => {
+                        eprintln!("[CLIENT] Bulk unlock failed: {e}");
+                        return (all_names(names), report);
+                    }
+                };
 
-                let mut failed_apps = Vec::new();
+                let mut failed_apps = skipped_names(&names, &results);
                 let mut unlocked = Vec::new();
                 for (app_id, res) in results {
                     let name = app_label(&names, app_id);
@@ -198,22 +253,20 @@ pub fn create_bulk_actions(
                         }
                         // The per-app store failing leaves that app untouched,
                         // so it belongs with the outright errors.
-                        Ok(false) => {
+                        Ok(stored) if !stored.result => {
                             eprintln!("[CLIENT] App {app_id} was not stored");
                             failed_apps.push(name);
                         }
-                        Ok(true) => unlocked.push((
+                        Ok(stored) => unlocked.push((
                             app_id,
                             name,
-                            Change::Opaque {
-                                detail: tr_noop("every achievement unlocked").to_string(),
-                            },
+                            Change::bulk(tr_noop("every achievement unlocked"), stored.before),
                         )),
                     }
                 }
                 Batch::across(Op::BulkUnlock).record_per_app(unlocked);
 
-                failed_apps
+                (failed_apps, report)
             });
 
             MainContext::default().spawn_local(clone!(
@@ -229,18 +282,33 @@ pub fn create_bulk_actions(
                 context_menu_button_loading,
                 #[weak]
                 context_menu_button,
+                #[strong]
+                last_report,
                 async move {
-                    let failed_apps = handle
+                    let (failed_apps, report) = handle
                         .await
                         .expect("[CLIENT] Failed to wait for unlock thread to finish");
+                    last_report.set(&application, report);
 
                     if !failed_apps.is_empty()
                         && let Some(parent) = application.active_window()
                     {
-                        show_list_dialog(
+                        let (title, message) = if cancelled.is_cancelled() {
+                            (
+                                tr("Unlock cancelled"),
+                                tr("The following apps were left as they were:"),
+                            )
+                        } else {
+                            (
+                                tr("Unlock incomplete"),
+                                tr("Failed to unlock achievements for the following apps:"),
+                            )
+                        };
+                        show_list_with_report(
+                            &application,
                             &parent,
-                            tr("Unlock incomplete").as_str(),
-                            tr("Failed to unlock achievements for the following apps:").as_str(),
+                            &title,
+                            &message,
                             &failed_apps.join("\n"),
                         );
                     }
@@ -277,6 +345,10 @@ pub fn create_bulk_actions(
         context_menu_button_loading_progress_label,
         #[weak]
         context_menu_button_info_label,
+        #[strong]
+        bulk_cancel,
+        #[strong]
+        last_report,
         move |_, _| {
             let Some(selection_model) = grid_view.model() else {
                 return;
@@ -332,15 +404,23 @@ pub fn create_bulk_actions(
                 }
             });
 
+            let canceller = bulk_cancel.start();
+            let cancelled = canceller.clone();
+            let policy = fan_out_policy();
             let handle = spawn_blocking(move || {
                 let names: HashMap<u32, String> = apps_to_lock.clone();
+                let items = apps_to_lock.iter().map(|(id, name)| Item::new(*id, name));
+                let mut job = Running::start(Kind::Lock, items.collect());
                 let app_ids: Vec<u32> = apps_to_lock.into_keys().collect();
                 let mut last_done = 0usize;
-                match (ResetApps {
+                let request = ResetApps {
                     app_ids,
                     achievements_too: true,
-                })
-                .request_with_progress(|done, total| {
+                    policy,
+                };
+                let response = request.request_stepped(&canceller, |step| {
+                    job.step(step.app_id, step.ok);
+                    let (done, total) = (step.done, step.total);
                     if done == last_done {
                         return;
                     }
@@ -355,10 +435,13 @@ pub fn create_bulk_actions(
                             );
                         }
                     });
-                }) {
+                });
+                job.conclude(&response, canceller.is_cancelled());
+                let report = Report::from_job(job.job(), response.as_ref().err());
+                let failed_apps = match response {
                     Ok(results) => {
                         let mut locked = Vec::new();
-                        let mut failed_apps = Vec::new();
+                        let mut failed_apps = skipped_names(&names, &results);
                         for (app_id, res) in results {
                             let name = app_label(&names, app_id);
                             match res {
@@ -366,17 +449,17 @@ pub fn create_bulk_actions(
                                     eprintln!("[CLIENT] Error locking app {}: {}", app_id, e);
                                     failed_apps.push(name);
                                 }
-                                Ok(false) => {
+                                Ok(stored) if !stored.result => {
                                     eprintln!("[CLIENT] App {app_id} was not reset");
                                     failed_apps.push(name);
                                 }
-                                Ok(true) => locked.push((
+                                Ok(stored) => locked.push((
                                     app_id,
                                     name,
-                                    Change::Opaque {
-                                        detail: tr_noop("every achievement and stat reset")
-                                            .to_string(),
-                                    },
+                                    Change::bulk(
+                                        tr_noop("every achievement and stat reset"),
+                                        stored.before,
+                                    ),
                                 )),
                             }
                         }
@@ -387,7 +470,8 @@ pub fn create_bulk_actions(
                         eprintln!("[CLIENT] Bulk lock failed: {e}");
                         all_names(names)
                     }
-                }
+                };
+                (failed_apps, report)
             });
 
             MainContext::default().spawn_local(clone!(
@@ -403,17 +487,26 @@ pub fn create_bulk_actions(
                 context_menu_button_loading,
                 #[weak]
                 context_menu_button,
+                #[strong]
+                last_report,
                 async move {
-                    let failed_apps = handle
+                    let (failed_apps, report) = handle
                         .await
                         .expect("[CLIENT] Failed to wait for lock thread to finish");
+                    last_report.set(&application, report);
 
                     if !failed_apps.is_empty()
                         && let Some(parent) = application.active_window()
                     {
-                        show_list_dialog(
+                        let title = if cancelled.is_cancelled() {
+                            tr("Reset cancelled")
+                        } else {
+                            tr("Reset incomplete")
+                        };
+                        show_list_with_report(
+                            &application,
                             &parent,
-                            tr("Reset incomplete").as_str(),
+                            &title,
                             tr("The following apps were left as they were:").as_str(),
                             &failed_apps.join("\n"),
                         );
@@ -432,10 +525,27 @@ pub fn create_bulk_actions(
         }
     ));
 
+    // Enabled while a bulk operation runs; see `set_bulk_actions_enabled`.
+    let action_cancel_bulk_operation = SimpleAction::new("cancel_bulk_operation", None);
+    action_cancel_bulk_operation.set_enabled(false);
+    action_cancel_bulk_operation.connect_activate(clone!(
+        #[strong]
+        bulk_cancel,
+        #[weak]
+        context_menu_button_info_label,
+        move |action, _| {
+            bulk_cancel.cancel();
+            action.set_enabled(false);
+            context_menu_button_info_label
+                .set_text(&tr("Cancelling; finishing the apps already started…"));
+        }
+    ));
+
     (
         action_select_all_apps,
         action_unselect_all_apps,
         action_unlock_all_selected,
         action_lock_all_selected,
+        action_cancel_bulk_operation,
     )
 }
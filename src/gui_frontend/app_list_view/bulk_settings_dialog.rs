@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! How bulk actions pace their apps: the `bulk-*` keys, which
+//! `gsettings::fan_out_policy` reads as each one starts.
+
+use crate::gui_frontend::MainApplication;
+use crate::gui_frontend::gsettings::{
+    BULK_BACKOFF_MS, BULK_CONCURRENCY, BULK_RETRIES, get_settings,
+};
+use crate::gui_frontend::i18n::tr;
+use gtk::gio::SimpleAction;
+use gtk::glib::{GString, clone};
+use gtk::prelude::*;
+use gtk::{Adjustment, Align, Grid, Label, Orientation, SpinButton, Switch};
+use std::ops::RangeInclusive;
+
+/// `app.show_bulk_settings`.
+pub fn create_bulk_settings_action(application: &MainApplication) -> SimpleAction {
+    let action = SimpleAction::new("show_bulk_settings", None);
+    action.connect_activate(clone!(
+        #[weak]
+        application,
+        move |_, _| {
+            if let Some(parent) = application.active_window() {
+                show_bulk_settings(&parent);
+            }
+        }
+    ));
+    action
+}
+
+fn show_bulk_settings(parent: &gtk::Window) {
+    let settings = get_settings();
+    let dialog = gtk::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(tr("Bulk action settings"))
+        .destroy_with_parent(true)
+        .resizable(false)
+        .build();
+
+    let content = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .spacing(12)
+        .build();
+
+    let intro = Label::builder()
+        .label(tr(
+            "Steam can time out when it is asked about many apps at once. These apply from the next bulk action.",
+        ))
+        .wrap(true)
+        .max_width_chars(48)
+        .xalign(0.0)
+        .build();
+    content.append(&intro);
+
+    let grid = Grid::builder().row_spacing(10).column_spacing(12).build();
+    let spins: [(GString, &'static str, RangeInclusive<i32>, f64); 3] = [
+        (
+            tr("Apps at once"),
+            "bulk-concurrency",
+            BULK_CONCURRENCY,
+            1.0,
+        ),
+        (tr("Retries per app"), "bulk-retries", BULK_RETRIES, 1.0),
+        (
+            tr("First retry after (ms)"),
+            "bulk-backoff-ms",
+            BULK_BACKOFF_MS,
+            100.0,
+        ),
+    ];
+    for (row, (label, key, range, step)) in (0..).zip(spins) {
+        let adjustment = Adjustment::builder()
+            .lower(*range.start() as f64)
+            .upper(*range.end() as f64)
+            .step_increment(step)
+            .value(settings.int(key).clamp(*range.start(), *range.end()) as f64)
+            .build();
+        let spin = SpinButton::builder()
+            .adjustment(&adjustment)
+            .digits(0)
+            .hexpand(true)
+            .build();
+        spin.connect_value_notify(clone!(
+            #[strong]
+            settings,
+            move |sb| {
+                if let Err(e) = settings.set_int(key, sb.value_as_int()) {
+                    eprintln!("[CLIENT] Error saving {key} setting: {e:?}");
+                }
+            }
+        ));
+        let label = Label::builder().label(label).halign(Align::End).build();
+        grid.attach(&label, 0, row, 1, 1);
+        grid.attach(&spin, 1, row, 1, 1);
+    }
+
+    let adaptive = Switch::builder()
+        .active(settings.boolean("bulk-adaptive"))
+        .halign(Align::Start)
+        .build();
+    adaptive.connect_active_notify(clone!(
+        #[strong]
+        settings,
+        move |switch| {
+            if let Err(e) = settings.set_boolean("bulk-adaptive", switch.is_active()) {
+                eprintln!("[CLIENT] Error saving bulk-adaptive setting: {e:?}");
+            }
+        }
+    ));
+    let adaptive_label = Label::builder()
+        .label(tr("Fewer at once while Steam times out"))
+        .halign(Align::End)
+        .build();
+    grid.attach(&adaptive_label, 0, 3, 1, 1);
+    grid.attach(&adaptive, 1, 3, 1, 1);
+    content.append(&grid);
+
+    let close = gtk::Button::with_label(tr("Close").as_str());
+    close.add_css_class("suggested-action");
+    close.set_halign(Align::End);
+    close.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| dialog.close()
+    ));
+    content.append(&close);
+
+    dialog.set_child(Some(&content));
+    dialog.present();
+}
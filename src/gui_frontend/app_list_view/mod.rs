@@ -16,12 +16,18 @@
 mod achievement_loader;
 mod app_index;
 mod bulk_actions;
+mod bulk_settings_dialog;
 mod progress_actions;
+mod pushed_events;
 mod refresh_actions;
+mod report_dialog;
+mod resume_jobs;
+mod schedule_dialog;
 mod settings_bindings;
 mod sidebar;
 
 use crate::backend::app_lister::{AppModel, AppModelType};
+use crate::backend::library_query::AppFilter;
 use crate::backend::local_stats::LocalIndex;
 use crate::backend::user_unlock_times::account_id;
 use crate::gui_frontend::MainApplication;
@@ -30,13 +36,15 @@ use crate::gui_frontend::app_view::create_app_view;
 use crate::gui_frontend::application_actions::{
     set_app_action_enabled, set_timed_unlock_actions_enabled, setup_app_actions,
 };
+use crate::gui_frontend::dbus_service::{self, Progress};
 use crate::gui_frontend::dialogs::choose_steam_install_then;
+use crate::gui_frontend::gobjects::achievement::GAchievementObject;
 use crate::gui_frontend::gobjects::steam_app::GSteamAppObject;
 use crate::gui_frontend::gsettings::get_settings;
 use crate::gui_frontend::i18n::tr;
 use crate::gui_frontend::profile_view::build_profile_view;
 use crate::gui_frontend::profile_view::identity::{Identity, SharedIdentity, load_identity};
-use crate::gui_frontend::request::{AppProgress, LaunchApp, Request, StopApp};
+use crate::gui_frontend::request::{AppProgress, LaunchApp, OpenApp, Request, StopApp};
 use crate::gui_frontend::ui_components::{
     create_context_menu_button, set_context_popover_to_app_list_context,
     set_context_popover_to_profile_context,
@@ -46,8 +54,9 @@ use crate::utils::action_journal;
 use crate::utils::app_paths::get_executable_path;
 use crate::utils::arguments::parse_gui_arguments;
 use achievement_loader::AchievementLoader;
-use bulk_actions::create_bulk_actions;
-use gtk::gio::{ApplicationCommandLine, ListStore, spawn_blocking};
+use bulk_actions::{BulkCancel, create_bulk_actions};
+use bulk_settings_dialog::create_bulk_settings_action;
+use gtk::gio::{ApplicationCommandLine, ListStore, SimpleAction, spawn_blocking};
 use gtk::glib::ExitCode;
 use gtk::glib::{MainContext, clone};
 use gtk::prelude::*;
@@ -59,10 +68,14 @@ use gtk::{
 };
 use gtk::{IconSize, glib};
 use progress_actions::create_progress_actions;
+use pushed_events::follow_events;
 use refresh_actions::{
     create_clear_all_action, create_refresh_achievements_action, create_refresh_app_list_action,
     create_rescan_counts_action,
 };
+use report_dialog::{LastReport, create_report_action};
+use resume_jobs::{create_resume_action, offer_interrupted_job};
+use schedule_dialog::{AppPage, create_schedule_action};
 use settings_bindings::setup_settings_bindings;
 use sidebar::{build_sidebar, sort_needs_counts};
 use std::cell::{Cell, RefCell};
@@ -101,10 +114,19 @@ impl FilterState {
             .set(settings.boolean("filter-hide-without-achievements"));
     }
 
+    /// The library half of the filters, shared with the CLI's `list-apps`.
+    fn app_filter(&self) -> AppFilter {
+        AppFilter {
+            junk: self.junk.get(),
+            hide_fully_unlocked: self.hide_fully_unlocked.get(),
+            hide_never_launched: self.hide_never_launched.get(),
+            hide_no_unlocked: self.hide_no_unlocked.get(),
+            hide_without_achievements: self.hide_without_achievements.get(),
+        }
+    }
+
     fn depends_on_counts(&self) -> bool {
-        self.hide_fully_unlocked.get()
-            || self.hide_no_unlocked.get()
-            || self.hide_without_achievements.get()
+        self.app_filter().depends_on_counts()
     }
 }
 
@@ -261,6 +283,28 @@ fn counts_complete(list_store: &ListStore) -> bool {
     any
 }
 
+/// The library's entry for `app_id`, or a stand-in for an app it does not list.
+fn app_object_for(list_store: &ListStore, app_id: u32) -> GSteamAppObject {
+    list_store
+        .iter::<GSteamAppObject>()
+        .flatten()
+        .find(|app| app.app_id() == app_id)
+        .unwrap_or_else(|| {
+            GSteamAppObject::new(AppModel {
+                app_id,
+                app_name: format!("App {app_id}"),
+                app_type: AppModelType::App,
+                developer: "Unknown".to_string(),
+                image_url: None,
+                metacritic_score: None,
+                playtime_minutes: None,
+                last_played: None,
+                achievement_count: None,
+                unlocked_achievement_count: None,
+            })
+        })
+}
+
 pub fn create_main_ui(
     application: &MainApplication,
     cmd_line: &ApplicationCommandLine,
@@ -320,6 +364,7 @@ pub fn create_main_ui(
         app_pane,
         cancel_timed_unlock,
         app_achievements_stack,
+        app_timed_achievements_model,
     ) = create_app_view(
         app_id.clone(),
         app_unlocked_achievements_count.clone(),
@@ -455,28 +500,18 @@ pub fn create_main_ui(
                 return true;
             }
 
-            if filter_state.junk.get() && app.is_junk() {
+            let filter = filter_state.app_filter();
+            if filter.hides_by_kind(app.is_junk(), app.last_played()) {
                 return false;
             }
             if filter_state.only_idling.get() && !app.is_idling() {
                 return false;
             }
-            if filter_state.hide_never_launched.get() && app.last_played() == 0 {
-                return false;
-            }
 
-            if counts_ready.get() {
-                let total = app.achievement_count();
-                let unlocked = app.unlocked_achievement_count();
-                if filter_state.hide_without_achievements.get() && total == 0 {
-                    return false;
-                }
-                if filter_state.hide_fully_unlocked.get() && total > 0 && unlocked >= total {
-                    return false;
-                }
-                if filter_state.hide_no_unlocked.get() && unlocked == 0 {
-                    return false;
-                }
+            if counts_ready.get()
+                && filter.hides_by_counts(app.achievement_count(), app.unlocked_achievement_count())
+            {
+                return false;
             }
 
             let search_text = search_text_lower.borrow();
@@ -658,7 +693,10 @@ pub fn create_main_ui(
             ));
         }
     ));
+    let job_offered = Cell::new(false);
     let on_library_loaded: Rc<dyn Fn()> = Rc::new(clone!(
+        #[weak]
+        application,
         #[strong]
         counts_prefilled,
         #[strong]
@@ -666,6 +704,9 @@ pub fn create_main_ui(
         move || {
             counts_prefilled.set(false);
             prefill_counts();
+            if !job_offered.replace(true) {
+                offer_interrupted_job(&application);
+            }
         }
     ));
     let on_open_app: Rc<dyn Fn(&GSteamAppObject)> = Rc::new(clone!(
@@ -793,7 +834,7 @@ pub fn create_main_ui(
     ));
     let profile = Rc::new(build_profile_view(
         identity.clone(),
-        on_open_app,
+        on_open_app.clone(),
         on_measure_all,
         on_measure_apps,
         on_rescan_all,
@@ -1034,6 +1075,63 @@ pub fn create_main_ui(
         }
     ));
 
+    // A card's idle toggle and the `start_idling`/`stop_idling` actions. The
+    // backend call runs in the background and takes the toggle back if it fails.
+    let set_idling: Rc<dyn Fn(&GSteamAppObject, bool)> = Rc::new(clone!(
+        #[weak]
+        list_store,
+        #[strong]
+        idle_count,
+        #[weak]
+        list_custom_filter,
+        #[strong]
+        filter_state,
+        #[strong]
+        sync_empty_state,
+        move |app: &GSteamAppObject, active: bool| {
+            let app = app.clone();
+            let app_id = app.app_id();
+            app.set_is_idling(active);
+            apply_idle_cap_delta(&list_store, &idle_count, if active { 1 } else { -1 });
+            if filter_state.only_idling.get() {
+                list_custom_filter.changed(gtk::FilterChange::Different);
+                sync_empty_state();
+            }
+
+            let handle = spawn_blocking(move || {
+                if active {
+                    LaunchApp { app_id }.request().map(|_| ())
+                } else {
+                    StopApp { app_id }.request().map(|_| ())
+                }
+            });
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                list_store,
+                #[strong]
+                idle_count,
+                #[weak]
+                list_custom_filter,
+                #[strong]
+                filter_state,
+                async move {
+                    if let Ok(Err(e)) = handle.await {
+                        eprintln!(
+                            "[CLIENT] {} app {app_id} failed: {e:?}",
+                            if active { "Launching" } else { "Stopping" }
+                        );
+                        app.set_is_idling(!active);
+                        apply_idle_cap_delta(&list_store, &idle_count, if active { -1 } else { 1 });
+                        if filter_state.only_idling.get() {
+                            list_custom_filter.changed(gtk::FilterChange::Different);
+                        }
+                    }
+                }
+            ));
+        }
+    ));
+
     // Install everything once per allocated card. Handlers read the *current*
     // bound app via `card.app_object()` (kept in sync by the property-expression
     // binding below), and the selection handler captures the ListItem weakly.
@@ -1043,20 +1141,12 @@ pub fn create_main_ui(
         #[strong]
         app_id,
         #[strong]
-        idle_count,
+        set_idling,
         #[strong]
         prefetched_progress,
-        #[strong]
-        filter_state,
-        #[strong]
-        sync_empty_state,
         #[weak]
         application,
         #[weak]
-        list_custom_filter,
-        #[weak]
-        list_store,
-        #[weak]
         list_selection_model,
         #[weak]
         menu_model,
@@ -1173,35 +1263,40 @@ pub fn create_main_ui(
                         return;
                     };
                     let app_id_to_bind = app.app_id();
-                    match Command::new(get_executable_path())
-                        .arg(format!("--auto-open={app_id_to_bind}"))
-                        .spawn()
-                    {
-                        // Without the wait every window opened this way stays a
-                        // zombie until this process exits.
-                        Ok(mut child) => {
-                            std::thread::spawn(move || {
-                                let _ = child.wait();
-                            });
+                    MainContext::default().spawn_local(async move {
+                        // Another window on the daemon takes it, if there is one.
+                        let handed = spawn_blocking(move || {
+                            OpenApp {
+                                app_id: app_id_to_bind,
+                            }
+                            .request()
+                        })
+                        .await;
+                        if matches!(handed, Ok(Ok(true))) {
+                            return;
                         }
-                        Err(e) => eprintln!("[CLIENT] Could not open {app_id_to_bind}: {e}"),
-                    }
+                        match Command::new(get_executable_path())
+                            .arg(format!("--auto-open={app_id_to_bind}"))
+                            .spawn()
+                        {
+                            // Without the wait every window opened this way stays a
+                            // zombie until this process exits.
+                            Ok(mut child) => {
+                                std::thread::spawn(move || {
+                                    let _ = child.wait();
+                                });
+                            }
+                            Err(e) => eprintln!("[CLIENT] Could not open {app_id_to_bind}: {e}"),
+                        }
+                    });
                 }
             ));
 
             card.idle_button().connect_toggled(clone!(
                 #[weak]
                 card,
-                #[weak]
-                list_store,
                 #[strong]
-                idle_count,
-                #[weak]
-                list_custom_filter,
-                #[strong]
-                filter_state,
-                #[strong]
-                sync_empty_state,
+                set_idling,
                 move |button| {
                     let Some(app) = card.app_object() else {
                         return;
@@ -1212,50 +1307,7 @@ pub fn create_main_ui(
                     if active == app.is_idling() {
                         return;
                     }
-
-                    let app_id = app.app_id();
-                    app.set_is_idling(active);
-                    apply_idle_cap_delta(&list_store, &idle_count, if active { 1 } else { -1 });
-                    if filter_state.only_idling.get() {
-                        list_custom_filter.changed(gtk::FilterChange::Different);
-                        sync_empty_state();
-                    }
-
-                    let handle = spawn_blocking(move || {
-                        if active {
-                            LaunchApp { app_id }.request().map(|_| ())
-                        } else {
-                            StopApp { app_id }.request().map(|_| ())
-                        }
-                    });
-
-                    MainContext::default().spawn_local(clone!(
-                        #[weak]
-                        list_store,
-                        #[strong]
-                        idle_count,
-                        #[weak]
-                        list_custom_filter,
-                        #[strong]
-                        filter_state,
-                        async move {
-                            if let Ok(Err(e)) = handle.await {
-                                eprintln!(
-                                    "[CLIENT] {} app {app_id} failed: {e:?}",
-                                    if active { "Launching" } else { "Stopping" }
-                                );
-                                app.set_is_idling(!active);
-                                apply_idle_cap_delta(
-                                    &list_store,
-                                    &idle_count,
-                                    if active { -1 } else { 1 },
-                                );
-                                if filter_state.only_idling.get() {
-                                    list_custom_filter.changed(gtk::FilterChange::Different);
-                                }
-                            }
-                        }
-                    ));
+                    set_idling(&app, active);
                 }
             ));
 
@@ -1445,11 +1497,14 @@ pub fn create_main_ui(
         }
     ));
 
+    let bulk_cancel = BulkCancel::default();
+    let last_report = LastReport::default();
     let (
         action_select_all_apps,
         action_unselect_all_apps,
         action_unlock_all_selected,
         action_lock_all_selected,
+        action_cancel_bulk_operation,
     ) = create_bulk_actions(
         application,
         &grid_view,
@@ -1459,6 +1514,8 @@ pub fn create_main_ui(
         &context_menu_button_loading,
         &context_menu_button_loading_progress_label,
         &context_menu_button_info_label,
+        &bulk_cancel,
+        &last_report,
     );
 
     let (action_export_selected, action_import_progress) = create_progress_actions(
@@ -1470,7 +1527,34 @@ pub fn create_main_ui(
         &context_menu_button_loading,
         &context_menu_button_loading_progress_label,
         &context_menu_button_info_label,
+        &bulk_cancel,
+        &last_report,
+    );
+
+    let action_resume_bulk_job = create_resume_action(
+        application,
+        &grid_view,
+        &list_store,
+        achievement_loader.clone(),
+        &context_menu_button,
+        &context_menu_button_loading,
+        &context_menu_button_loading_progress_label,
+        &context_menu_button_info_label,
+        &bulk_cancel,
+        &last_report,
     );
+    application.add_action(&action_resume_bulk_job);
+    application.add_action(&create_report_action(application, &last_report));
+    application.add_action(&create_bulk_settings_action(application));
+    application.add_action(&create_schedule_action(
+        application,
+        AppPage {
+            app_id: app_id.clone(),
+            list_store: list_store.clone(),
+            achievements: app_achievements_model.clone(),
+            stats: app_stat_model.clone(),
+        },
+    ));
 
     let action_refresh_app_list = create_refresh_app_list_action(
         application,
@@ -1510,6 +1594,156 @@ pub fn create_main_ui(
         &app_stack,
     );
 
+    // Reached from the session bus (see `dbus_service`) as much as from inside.
+    let auto_open = gui_args.auto_open.clone();
+    let action_open_app = SimpleAction::new("open_app", Some(&u32::static_variant_type()));
+    action_open_app.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_stack,
+        #[weak]
+        list_store,
+        #[weak]
+        back_button,
+        #[strong]
+        app_id,
+        #[strong]
+        auto_open,
+        #[strong]
+        on_open_app,
+        move |_, parameter| {
+            let Some(target) = parameter.and_then(|p| p.get::<u32>()) else {
+                return;
+            };
+            window.present();
+            match list_stack.visible_child_name().as_deref() {
+                Some("list") => on_open_app(&app_object_for(&list_store, target)),
+                Some("app") if app_id.get() == Some(target) => {}
+                // Back to the list, which opens whatever `--auto-open` is waiting on.
+                Some("app") | Some("profile") => {
+                    auto_open.set(target);
+                    back_button.emit_clicked();
+                }
+                _ => auto_open.set(target),
+            }
+        }
+    ));
+    application.add_action(&action_open_app);
+
+    for (name, active) in [("start_idling", true), ("stop_idling", false)] {
+        let action = SimpleAction::new(name, Some(&u32::static_variant_type()));
+        action.connect_activate(clone!(
+            #[weak]
+            list_store,
+            #[strong]
+            set_idling,
+            move |_, parameter| {
+                let Some(target) = parameter.and_then(|p| p.get::<u32>()) else {
+                    return;
+                };
+                // Only what the library lists has a card to show it idling.
+                let Some(app) = list_store
+                    .iter::<GSteamAppObject>()
+                    .flatten()
+                    .find(|app| app.app_id() == target)
+                else {
+                    return;
+                };
+                if app.is_idling() == active || (active && !app.can_start_idling()) {
+                    return;
+                }
+                set_idling(&app, active);
+            }
+        ));
+        application.add_action(&action);
+    }
+
+    dbus_service::start(
+        application,
+        clone!(
+            #[weak]
+            list_stack,
+            #[weak]
+            list_store,
+            #[weak]
+            app_achievements_model,
+            #[weak]
+            app_timed_achievements_model,
+            #[strong]
+            app_id,
+            #[strong]
+            app_unlocked_achievements_count,
+            #[upgrade_or_default]
+            move || {
+                let achieved = |model: &ListStore| {
+                    model
+                        .iter::<GAchievementObject>()
+                        .flatten()
+                        .filter(|achievement| achievement.is_achieved())
+                        .count() as u32
+                };
+                let page = list_stack
+                    .visible_child_name()
+                    .map(|page| page.to_string())
+                    .unwrap_or_default();
+                let on_app_page = page == "app";
+                Progress {
+                    app_id: app_id.get().filter(|_| on_app_page).unwrap_or(0),
+                    achievements_unlocked: if on_app_page {
+                        app_unlocked_achievements_count.get() as u32
+                    } else {
+                        0
+                    },
+                    achievements_total: if on_app_page {
+                        app_achievements_model.n_items()
+                    } else {
+                        0
+                    },
+                    timed_unlock_done: achieved(&app_timed_achievements_model),
+                    timed_unlock_total: app_timed_achievements_model.n_items(),
+                    idling: list_store
+                        .iter::<GSteamAppObject>()
+                        .flatten()
+                        .filter(|app| app.is_idling())
+                        .map(|app| app.app_id())
+                        .collect(),
+                    page,
+                }
+            }
+        ),
+    );
+
+    // Another frontend stopping an idle this window started would leave its
+    // card saying it idles.
+    let on_idle_stopped: Rc<dyn Fn(u32)> = Rc::new(clone!(
+        #[weak]
+        list_store,
+        #[strong]
+        idle_count,
+        #[weak]
+        list_custom_filter,
+        #[strong]
+        filter_state,
+        #[strong]
+        sync_empty_state,
+        move |app_id| {
+            let Some(app) = list_store
+                .iter::<GSteamAppObject>()
+                .flatten()
+                .find(|app| app.app_id() == app_id && app.is_idling())
+            else {
+                return;
+            };
+            app.set_is_idling(false);
+            apply_idle_cap_delta(&list_store, &idle_count, -1);
+            if filter_state.only_idling.get() {
+                list_custom_filter.changed(gtk::FilterChange::Different);
+                sync_empty_state();
+            }
+        }
+    ));
+
     list_stack.connect_visible_child_notify(clone!(
         #[weak]
         back_button,
@@ -1557,26 +1791,7 @@ pub fn create_main_ui(
                 if auto_launch_app > 0 && page == Some("list") {
                     gui_args.auto_open.set(0);
 
-                    let target_app = list_store
-                        .snapshot()
-                        .into_iter()
-                        .filter_map(|obj| obj.downcast::<GSteamAppObject>().ok())
-                        .find(|g_app| g_app.app_id() == auto_launch_app);
-
-                    let app_to_open = target_app.unwrap_or_else(|| {
-                        GSteamAppObject::new(AppModel {
-                            app_id: auto_launch_app,
-                            app_name: format!("App {auto_launch_app}"),
-                            app_type: AppModelType::App,
-                            developer: "Unknown".to_string(),
-                            image_url: None,
-                            metacritic_score: None,
-                            playtime_minutes: None,
-                            last_played: None,
-                            achievement_count: None,
-                            unlocked_achievement_count: None,
-                        })
-                    });
+                    let app_to_open = app_object_for(&list_store, auto_launch_app);
 
                     switch_from_app_list_to_app(
                         &app_to_open,
@@ -1613,6 +1828,7 @@ pub fn create_main_ui(
         &action_lock_all_selected,
         &action_export_selected,
         &action_import_progress,
+        &action_cancel_bulk_operation,
     );
 
     let key_controller = gtk::EventControllerKey::new();
@@ -1645,15 +1861,27 @@ pub fn create_main_ui(
             #[strong]
             action_refresh_app_list,
             #[strong]
+            action_open_app,
+            #[strong]
             window,
+            #[strong]
+            on_idle_stopped,
             move |chosen| {
                 #[cfg(unix)]
                 if let Some(root) = chosen.as_ref() {
                     crate::utils::snap::pin_install_root(root);
                 }
-                if let Err(e) = crate::backend::orchestrator_client::spawn_orchestrator(chosen) {
+                if let Err(e) =
+                    crate::backend::orchestrator_client::spawn_orchestrator(chosen.clone())
+                {
                     eprintln!("[CLIENT] Failed to start orchestrator: {e}");
                 }
+                follow_events(
+                    &action_refresh_app_list,
+                    &action_open_app,
+                    chosen,
+                    on_idle_stopped.clone(),
+                );
                 app_stack.set_visible_child_name("loading");
                 list_stack.set_visible_child_name("loading");
                 action_refresh_app_list.activate(None);
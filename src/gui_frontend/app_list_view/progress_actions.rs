@@ -14,14 +14,18 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::achievement_loader::AchievementLoader;
+use super::bulk_actions::{BulkCancel, skipped_names};
+use super::report_dialog::{LastReport, show_list_with_report};
 use crate::gui_frontend::MainApplication;
 use crate::gui_frontend::application_actions::set_bulk_actions_enabled;
-use crate::gui_frontend::dialogs::show_list_dialog;
 use crate::gui_frontend::gobjects::steam_app::GSteamAppObject;
+use crate::gui_frontend::gsettings::fan_out_policy;
 use crate::gui_frontend::i18n::{tr, tr_noop};
 use crate::gui_frontend::request::{ExportApps, ImportApps, Request};
 use crate::utils::action_journal::{Batch, Change, Op};
-use crate::utils::export_file::{ExportFile, FORMAT_VERSION, iso8601_utc_now};
+use crate::utils::bulk_jobs::{Item, Kind, Running};
+use crate::utils::bulk_report::Report;
+use crate::utils::export_file::{self, ExportFile, FORMAT_VERSION, ReadError, iso8601_utc_now};
 use crate::utils::ipc_types::AppExport;
 use gtk::gio::{ListStore, SimpleAction, spawn_blocking};
 use gtk::glib::{MainContext, clone};
@@ -29,6 +33,7 @@ use gtk::prelude::*;
 use gtk::{GridView, Label, MenuButton, glib};
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 
 fn has_protected_fields(export: &AppExport) -> bool {
     export.achievements.iter().any(|a| a.permission != 0)
@@ -55,6 +60,8 @@ pub fn create_progress_actions(
     context_menu_button_loading: &MenuButton,
     context_menu_button_loading_progress_label: &Label,
     context_menu_button_info_label: &Label,
+    bulk_cancel: &BulkCancel,
+    last_report: &LastReport,
 ) -> (SimpleAction, SimpleAction) {
     let action_export_selected = SimpleAction::new("export_selected_progress", None);
     action_export_selected.set_enabled(false);
@@ -71,6 +78,10 @@ pub fn create_progress_actions(
         context_menu_button_loading_progress_label,
         #[weak]
         context_menu_button_info_label,
+        #[strong]
+        bulk_cancel,
+        #[strong]
+        last_report,
         move |_, _| {
             let Some(selection_model) = grid_view.model() else {
                 return;
@@ -127,6 +138,8 @@ pub fn create_progress_actions(
             let weak_info =
                 glib::object::SendWeakRef::from(context_menu_button_info_label.downgrade());
 
+            let bulk_cancel = bulk_cancel.clone();
+            let last_report = last_report.clone();
             MainContext::default().spawn_local(async move {
                 let file = match dialog.save_future(parent_window.as_ref()).await {
                     Ok(f) => f,
@@ -151,6 +164,8 @@ pub fn create_progress_actions(
 
                 let total = apps.len();
                 let path_for_task = path.clone();
+                let canceller = bulk_cancel.start();
+                let cancelled = canceller.clone();
                 let weak_progress_for_thread = weak_progress.clone();
                 MainContext::default().invoke(move || {
                     if let Some(label) = weak_progress.upgrade() {
@@ -164,33 +179,36 @@ pub fn create_progress_actions(
                         label.set_text("");
                     }
                 });
+                let policy = fan_out_policy();
                 let handle = spawn_blocking(move || {
                     let names: HashMap<u32, String> = apps.iter().cloned().collect();
                     let app_ids: Vec<u32> = apps.into_iter().map(|(id, _)| id).collect();
                     let mut last_done = 0usize;
-                    let results =
-                        match (ExportApps { app_ids }).request_with_progress(|done, total| {
-                            if done == last_done {
-                                return;
+                    let request = ExportApps { app_ids, policy };
+                    let response = request.request_cancellable(&canceller, |done, total| {
+                        if done == last_done {
+                            return;
+                        }
+                        last_done = done;
+                        let label = weak_progress_for_thread.clone();
+                        MainContext::default().invoke(move || {
+                            if let Some(l) = label.upgrade() {
+                                l.set_text(
+                                    &tr("Exporting {done} / {total} app(s)…")
+                                        .replace("{done}", &done.to_string())
+                                        .replace("{total}", &total.to_string()),
+                                );
                             }
-                            last_done = done;
-                            let label = weak_progress_for_thread.clone();
-                            MainContext::default().invoke(move || {
-                                if let Some(l) = label.upgrade() {
-                                    l.set_text(
-                                        &tr("Exporting {done} / {total} app(s)…")
-                                            .replace("{done}", &done.to_string())
-                                            .replace("{total}", &total.to_string()),
-                                    );
-                                }
-                            });
-                        }) {
-                            Ok(results) => results,
-                            Err(e) => return Err(format!("Export failed: {e}")),
-                        };
+                        });
+                    });
+                    let report = Report::from_export(&names, &response, canceller.is_cancelled());
+                    let results = match response {
+                        Ok(results) => results,
+                        Err(e) => return (Err(format!("Export failed: {e}")), report),
+                    };
 
                     let mut exports: Vec<AppExport> = Vec::new();
-                    let mut failed: Vec<String> = Vec::new();
+                    let mut failed = skipped_names(&names, &results);
                     for (app_id, res) in results {
                         let name = names
                             .get(&app_id)
@@ -214,19 +232,21 @@ pub fn create_progress_actions(
                         apps: exports,
                     };
 
-                    match serde_json::to_string_pretty(&file_struct) {
+                    let written = match serde_json::to_string_pretty(&file_struct) {
                         Ok(content) => match std::fs::write(&path_for_task, content) {
                             Ok(_) => Ok(failed),
                             Err(e) => Err(format!("Failed to write file: {e}")),
                         },
                         Err(e) => Err(format!("Failed to serialize: {e}")),
-                    }
+                    };
+                    (written, report)
                 });
 
-                let result = handle.await.expect("[CLIENT] Failed to wait for export");
+                let (result, report) = handle.await.expect("[CLIENT] Failed to wait for export");
 
                 if let Some(app) = weak_app.upgrade() {
                     set_bulk_actions_enabled(&app, true);
+                    last_report.set(&app, report);
                 }
                 if let Some(grid) = weak_grid.upgrade() {
                     grid.set_sensitive(true);
@@ -252,12 +272,23 @@ pub fn create_progress_actions(
                         .await;
                     }
                     Ok(failed) => {
-                        if let Some(parent) = parent {
-                            show_list_dialog(
+                        let (title, message) = if cancelled.is_cancelled() {
+                            (
+                                tr("Export cancelled"),
+                                tr("Wrote {path}\n\nThese apps were not exported:"),
+                            )
+                        } else {
+                            (
+                                tr("Export partially complete"),
+                                tr("Wrote {path}\n\nFailed to read data for these apps:"),
+                            )
+                        };
+                        if let (Some(app), Some(parent)) = (app.as_ref(), parent) {
+                            show_list_with_report(
+                                app,
                                 &parent,
-                                &tr("Export partially complete"),
-                                &tr("Wrote {path}\n\nFailed to read data for these apps:")
-                                    .replace("{path}", &path.display().to_string()),
+                                &title,
+                                &message.replace("{path}", &path.display().to_string()),
                                 &failed.join("\n"),
                             );
                         }
@@ -288,6 +319,10 @@ pub fn create_progress_actions(
         context_menu_button_loading_progress_label,
         #[weak]
         context_menu_button_info_label,
+        #[strong]
+        bulk_cancel,
+        #[strong]
+        last_report,
         move |_, _| {
             let json_filter = gtk::FileFilter::new();
             json_filter.add_pattern("*.json");
@@ -321,6 +356,8 @@ pub fn create_progress_actions(
             }
 
             let achievement_loader = achievement_loader.clone();
+            let bulk_cancel = bulk_cancel.clone();
+            let last_report = last_report.clone();
             MainContext::default().spawn_local(async move {
                 let file = match dialog.open_future(parent_window.as_ref()).await {
                     Ok(f) => f,
@@ -330,42 +367,13 @@ pub fn create_progress_actions(
                     return;
                 };
 
-                let contents = match std::fs::read_to_string(&path) {
-                    Ok(c) => c,
+                let parsed = match load_export(&path) {
+                    Ok(parsed) => parsed,
                     Err(e) => {
-                        show_alert(
-                            weak_app.upgrade().as_ref(),
-                            &tr("Import failed"),
-                            &tr("Could not read file: {error}").replace("{error}", &e.to_string()),
-                        )
-                        .await;
-                        return;
-                    }
-                };
-
-                let parsed: ExportFile = match serde_json::from_str(&contents) {
-                    Ok(p) => p,
-                    Err(e) => {
-                        show_alert(
-                            weak_app.upgrade().as_ref(),
-                            &tr("Import failed"),
-                            &tr("Could not parse file: {error}").replace("{error}", &e.to_string()),
-                        )
-                        .await;
+                        show_alert(weak_app.upgrade().as_ref(), &tr("Import failed"), &e).await;
                         return;
                     }
                 };
-                if parsed.format_version != FORMAT_VERSION {
-                    show_alert(
-                        weak_app.upgrade().as_ref(),
-                        &tr("Import failed"),
-                        &tr("Unsupported format version: {found} (this build expects {expected})")
-                            .replace("{found}", &parsed.format_version.to_string())
-                            .replace("{expected}", &FORMAT_VERSION.to_string()),
-                    )
-                    .await;
-                    return;
-                }
 
                 let mut missing: Vec<String> = Vec::new();
                 let mut present: Vec<AppExport> = Vec::new();
@@ -492,37 +500,51 @@ pub fn create_progress_actions(
                         (a.app_id, label)
                     })
                     .collect();
+                let canceller = bulk_cancel.start();
+                let policy = fan_out_policy();
                 let handle = spawn_blocking(move || {
                     let mut last_done = 0usize;
-                    let results =
-                        match (ImportApps { apps: present }).request_with_progress(|done, total| {
-                            if done == last_done {
-                                return;
-                            }
-                            last_done = done;
-                            let label = weak_progress_for_thread.clone();
-                            MainContext::default().invoke(move || {
-                                if let Some(l) = label.upgrade() {
-                                    l.set_text(
-                                        &tr("Importing {done} / {total} app(s)…")
-                                            .replace("{done}", &done.to_string())
-                                            .replace("{total}", &total.to_string()),
-                                    );
-                                }
-                            });
-                        }) {
-                            Ok(results) => results,
-                            Err(e) => {
-                                return (
-                                    0,
-                                    0,
-                                    0,
-                                    0,
-                                    vec![format!("Import failed: {e}")],
-                                    Vec::new(),
+                    let items = present.iter().cloned().map(Item::import).collect();
+                    let mut job = Running::start(Kind::Import, items);
+                    let request = ImportApps {
+                        apps: present,
+                        policy,
+                    };
+                    let response = request.request_stepped(&canceller, |step| {
+                        job.step(step.app_id, step.ok);
+                        let (done, total) = (step.done, step.total);
+                        if done == last_done {
+                            return;
+                        }
+                        last_done = done;
+                        let label = weak_progress_for_thread.clone();
+                        MainContext::default().invoke(move || {
+                            if let Some(l) = label.upgrade() {
+                                l.set_text(
+                                    &tr("Importing {done} / {total} app(s)…")
+                                        .replace("{done}", &done.to_string())
+                                        .replace("{total}", &total.to_string()),
                                 );
                             }
-                        };
+                        });
+                    });
+                    job.conclude(&response, canceller.is_cancelled());
+                    let report = Report::from_job(job.job(), response.as_ref().err());
+                    let results = match response {
+                        Ok(results) => results,
+                        Err(e) => {
+                            return (
+                                0,
+                                0,
+                                0,
+                                0,
+                                vec![format!("Import failed: {e}")],
+                                Vec::new(),
+                                Vec::new(),
+                                report,
+                            );
+                        }
+                    };
 
                     let mut total_ach: usize = 0;
                     let mut total_stat: usize = 0;
@@ -531,13 +553,15 @@ pub fn create_progress_actions(
                     let mut errors: Vec<String> = Vec::new();
                     let mut reset_candidates: Vec<String> = Vec::new();
                     let mut imported = Vec::new();
+                    let not_started = skipped_names(&names_by_id, &results);
                     for (app_id, res) in results {
                         let label = names_by_id
                             .get(&app_id)
                             .cloned()
                             .unwrap_or_else(|| format!("App {}", app_id));
                         match res {
-                            Ok(summary) => {
+                            Ok(kept) => {
+                                let summary = kept.result;
                                 // Only a clean, non-empty import is history: an
                                 // entry for a run Steam refused would read as
                                 // something that happened to the game.
@@ -547,13 +571,15 @@ pub fn create_progress_actions(
                                     imported.push((
                                         app_id,
                                         label.clone(),
-                                        Change::Opaque {
-                                            detail: tr_noop(
-                                                "achievements and stats written from a file",
-                                            )
-                                            .to_string(),
-                                        },
+                                        Change::bulk(
+                                            tr_noop("achievements and stats written from a file"),
+                                            kept.before,
+                                        ),
                                     ));
+                                    imported.extend(
+                                        Change::granted(summary.granted.clone())
+                                            .map(|change| (app_id, label.clone(), change)),
+                                    );
                                 }
                                 total_ach += summary.achievements_applied;
                                 total_stat += summary.stats_applied;
@@ -579,6 +605,8 @@ pub fn create_progress_actions(
                         total_skipped_unwriteable,
                         errors,
                         reset_candidates,
+                        not_started,
+                        report,
                     )
                 });
 
@@ -589,10 +617,13 @@ pub fn create_progress_actions(
                     skipped_unwriteable,
                     errors,
                     reset_candidates,
+                    not_started,
+                    report,
                 ) = handle.await.expect("[CLIENT] Failed to wait for import");
 
                 if let Some(app) = weak_app.upgrade() {
                     set_bulk_actions_enabled(&app, true);
+                    last_report.set(&app, report);
                 }
                 if let Some(grid) = weak_grid.upgrade() {
                     grid.set_sensitive(true);
@@ -633,6 +664,12 @@ pub fn create_progress_actions(
                             .replace("{list}", &missing.join("\n")),
                     );
                 }
+                if !not_started.is_empty() {
+                    sections.push(
+                        tr("Not imported (cancelled):\n{list}")
+                            .replace("{list}", &not_started.join("\n")),
+                    );
+                }
                 if !errors.is_empty() {
                     sections.push(tr("Errors:\n{list}").replace("{list}", &errors.join("\n")));
                 }
@@ -640,8 +677,16 @@ pub fn create_progress_actions(
                 let app = weak_app.upgrade();
                 if sections.is_empty() {
                     show_alert(app.as_ref(), &tr("Import complete"), &intro).await;
-                } else if let Some(parent) = app.as_ref().and_then(|a| a.active_window()) {
-                    show_list_dialog(&parent, &tr("Import complete"), &intro, &sections.join("\n\n"));
+                } else if let Some(app) = app.as_ref()
+                    && let Some(parent) = app.active_window()
+                {
+                    show_list_with_report(
+                        app,
+                        &parent,
+                        &tr("Import complete"),
+                        &intro,
+                        &sections.join("\n\n"),
+                    );
                 }
 
                 for id in affected_ids {
@@ -653,3 +698,19 @@ pub fn create_progress_actions(
 
     (action_export_selected, action_import_progress)
 }
+
+/// The progress in `path`, or why it cannot be imported, in the user's
+/// language.
+pub(super) fn load_export(path: &Path) -> Result<ExportFile, String> {
+    export_file::load(path).map_err(|e| match e {
+        ReadError::Read(e) => tr("Could not read file: {error}").replace("{error}", &e.to_string()),
+        ReadError::Parse(e) => {
+            tr("Could not parse file: {error}").replace("{error}", &e.to_string())
+        }
+        ReadError::Version(found) => {
+            tr("Unsupported format version: {found} (this build expects {expected})")
+                .replace("{found}", &found.to_string())
+                .replace("{expected}", &FORMAT_VERSION.to_string())
+        }
+    })
+}
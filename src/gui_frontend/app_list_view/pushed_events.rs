@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! What the orchestrator reports unasked, so that Steam quitting or an idle
+//! ended from another frontend shows without waiting for the next request.
+
+use crate::gui_frontend::request::{
+    FollowSchedule, Request, respawn_orchestrator, spawn_orchestrator, subscribe_events,
+};
+use crate::utils::ipc_types::OrchestratorEvent;
+use gtk::gio::{SimpleAction, spawn_blocking};
+use gtk::glib::MainContext;
+use gtk::prelude::*;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Reloads the library when Steam starts, quits or signs in someone else,
+/// hands `on_idle_stopped` each app whose last hold went away, and opens the
+/// apps another window sends through the daemon. Stored stats are left alone:
+/// the app page reloads them itself after its own changes.
+///
+/// A stranded orchestrator is replaced by one started like the first, with
+/// `chosen` as the install, and the library reloaded through it. Either one
+/// is asked to run the schedule while the window is open.
+pub fn follow_events(
+    refresh_app_list: &SimpleAction,
+    open_app: &SimpleAction,
+    chosen: Option<PathBuf>,
+    on_idle_stopped: Rc<dyn Fn(u32)>,
+) {
+    let refresh_app_list = refresh_app_list.downgrade();
+    let open_app = open_app.downgrade();
+    MainContext::default().spawn_local(async move {
+        follow_schedule();
+        let mut events = subscribe_events(true);
+        // The first events say how things stand, which the library loaded at
+        // startup already reflects.
+        let mut steam_running = None;
+        let mut user = None;
+        loop {
+            let Ok((receiver, event)) = spawn_blocking(move || {
+                let event = events.recv();
+                (events, event)
+            })
+            .await
+            else {
+                return;
+            };
+            events = receiver;
+            // The orchestrator is gone; the next request says so.
+            let Ok(event) = event else {
+                return;
+            };
+            let Some(refresh_app_list) = refresh_app_list.upgrade() else {
+                return;
+            };
+            let changed = match event {
+                OrchestratorEvent::SteamConnected => steam_running.replace(true) == Some(false),
+                OrchestratorEvent::SteamDisconnected => steam_running.replace(false) == Some(true),
+                OrchestratorEvent::UserChanged { steam_id64 } => user
+                    .replace(steam_id64)
+                    .is_some_and(|was| was != steam_id64),
+                OrchestratorEvent::IdleStopped { app_id } => {
+                    on_idle_stopped(app_id);
+                    false
+                }
+                OrchestratorEvent::OpenApp { app_id } => {
+                    if let Some(open_app) = open_app.upgrade() {
+                        open_app.activate(Some(&app_id.to_variant()));
+                    }
+                    false
+                }
+                OrchestratorEvent::Stranded => {
+                    let chosen = chosen.clone();
+                    let respawned =
+                        spawn_blocking(move || respawn_orchestrator(|| spawn_orchestrator(chosen)))
+                            .await;
+                    match respawned {
+                        Ok(Ok(())) => {
+                            // The old subscription ended with the old orchestrator.
+                            events = subscribe_events(true);
+                            follow_schedule();
+                            steam_running = None;
+                            user = None;
+                            true
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("[CLIENT] Failed to restart the orchestrator: {e}");
+                            false
+                        }
+                        Err(_) => false,
+                    }
+                }
+                _ => false,
+            };
+            // Disabled while the library loads or another page is up.
+            if changed && refresh_app_list.is_enabled() {
+                refresh_app_list.activate(None);
+            }
+        }
+    });
+}
+
+fn follow_schedule() {
+    spawn_blocking(|| {
+        if let Err(e) = FollowSchedule.request() {
+            eprintln!("[CLIENT] Failed to follow the schedule: {e}");
+        }
+    });
+}
@@ -164,7 +164,7 @@ pub fn create_refresh_app_list_action(
                                 ));
                             }
                         },
-                        Ok(Err(SamError::AppListRetrievalFailed)) => {
+                        Ok(Err(e)) if *e.kind() == SamError::AppListRetrievalFailed => {
                             search_entry.set_sensitive(true);
                             app_list_no_result_label.set_text(tr("Failed to load library. Check your internet connection. Search for App Id to get started.").as_str());
                             list_of_apps_or_no_result.set_visible_child_name("empty");
@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! The last bulk operation's report: every app it covered, what was applied,
+//! what was skipped and why, saved as JSON or CSV on request.
+
+use crate::gui_frontend::MainApplication;
+use crate::gui_frontend::application_actions::set_app_action_enabled;
+use crate::gui_frontend::dialogs::{show_list_dialog_with, show_message_dialog};
+use crate::gui_frontend::i18n::tr;
+use crate::utils::bulk_report::{AppReport, Operation, Outcome, Report};
+use gtk::gio::{ListStore, SimpleAction};
+use gtk::glib::{self, MainContext, clone};
+use gtk::prelude::*;
+use gtk::{Align, Label, Orientation};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Kept until the next bulk operation replaces it.
+#[derive(Clone, Default)]
+pub struct LastReport(Rc<RefCell<Option<Rc<Report>>>>);
+
+impl LastReport {
+    pub fn set(&self, application: &MainApplication, report: Report) {
+        *self.0.borrow_mut() = Some(Rc::new(report));
+        set_app_action_enabled(application, "show_bulk_report", true);
+    }
+}
+
+/// `app.show_bulk_report`, off until a bulk operation has run.
+pub fn create_report_action(
+    application: &MainApplication,
+    last_report: &LastReport,
+) -> SimpleAction {
+    let action = SimpleAction::new("show_bulk_report", None);
+    action.set_enabled(false);
+    action.connect_activate(clone!(
+        #[weak]
+        application,
+        #[strong]
+        last_report,
+        move |_, _| {
+            let Some(report) = last_report.0.borrow().clone() else {
+                return;
+            };
+            if let Some(parent) = application.active_window() {
+                show_report(&parent, report);
+            }
+        }
+    ));
+    action
+}
+
+/// `show_list_dialog` for the end of a bulk operation, with a way into its
+/// report.
+pub fn show_list_with_report(
+    application: &MainApplication,
+    parent: &gtk::Window,
+    title: &str,
+    intro: &str,
+    body: &str,
+) {
+    let weak_app = application.downgrade();
+    show_list_dialog_with(
+        parent,
+        title,
+        intro,
+        body,
+        &tr("Full report…"),
+        move || {
+            if let Some(application) = weak_app.upgrade() {
+                application.activate_action("show_bulk_report", None);
+            }
+        },
+    );
+}
+
+fn title(operation: Operation) -> String {
+    match operation {
+        Operation::UnlockAll => tr("Unlock report"),
+        Operation::Lock => tr("Reset report"),
+        Operation::Export => tr("Export report"),
+        Operation::Import => tr("Import report"),
+    }
+}
+
+fn outcome_label(outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Done => tr("Done"),
+        Outcome::NotStored => tr("Not stored by Steam"),
+        Outcome::Failed => tr("Failed"),
+        Outcome::NotStarted => tr("Not started"),
+    }
+}
+
+fn summary(report: &Report) -> String {
+    let mut text = tr("{done} of {total} app(s) done, finished {at}.")
+        .replace("{done}", &report.count(Outcome::Done).to_string())
+        .replace("{total}", &report.apps.len().to_string())
+        .replace("{at}", &report.finished_at);
+    if report.cancelled {
+        text.push('\n');
+        text.push_str(&tr("The operation was cancelled."));
+    }
+    if let Some(error) = &report.error {
+        text.push('\n');
+        text.push_str(&tr("The operation failed: {error}").replace("{error}", error));
+    }
+    text
+}
+
+fn cell(text: &str) -> Label {
+    Label::builder()
+        .label(text)
+        .xalign(0.0)
+        .yalign(0.0)
+        .wrap(true)
+        .max_width_chars(32)
+        .selectable(true)
+        .build()
+}
+
+/// Never empty, like the names in the bulk dialogs.
+fn app_label(app: &AppReport) -> String {
+    if app.app_name.is_empty() {
+        tr("App {id}").replace("{id}", &app.app_id.to_string())
+    } else {
+        app.app_name.clone()
+    }
+}
+
+/// The import's columns stay out of the other operations' reports, which
+/// would leave them empty.
+fn row(app: &AppReport, with_import: bool) -> Vec<String> {
+    let mut cells = vec![app_label(app), outcome_label(app.outcome)];
+    if with_import {
+        cells.push(
+            tr("{achievements} achievement(s), {stats} stat(s)")
+                .replace("{achievements}", &app.achievements_applied.to_string())
+                .replace("{stats}", &app.stats_applied.to_string()),
+        );
+        cells.push(app.skipped_protected.join("\n"));
+        cells.push(app.skipped_unwriteable.join("\n"));
+    }
+    cells.push(app.errors.join("\n"));
+    if with_import {
+        cells.push(if app.reset_would_help {
+            tr("Yes")
+        } else {
+            String::new()
+        });
+    }
+    cells
+}
+
+fn build_table(report: &Report) -> gtk::Grid {
+    let with_import = report.operation == Operation::Import;
+    let grid = gtk::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(16)
+        .margin_top(8)
+        .margin_bottom(8)
+        .margin_start(8)
+        .margin_end(8)
+        .build();
+
+    let mut headers = vec![tr("App"), tr("Result")];
+    if with_import {
+        headers.extend([
+            tr("Applied"),
+            tr("Skipped (protected)"),
+            tr("Skipped (unwriteable)"),
+        ]);
+    }
+    headers.push(tr("Errors"));
+    if with_import {
+        headers.push(tr("Reset first"));
+    }
+    for (column, header) in headers.iter().enumerate() {
+        let label = cell(header);
+        label.add_css_class("heading");
+        grid.attach(&label, column as i32, 0, 1, 1);
+    }
+
+    for (line, app) in report.apps.iter().enumerate() {
+        for (column, text) in row(app, with_import).iter().enumerate() {
+            let label = cell(text);
+            if column == 1 && app.has_problems() {
+                label.add_css_class("warning");
+            }
+            grid.attach(&label, column as i32, line as i32 + 1, 1, 1);
+        }
+    }
+    grid
+}
+
+fn show_report(parent: &gtk::Window, report: Rc<Report>) {
+    let dialog = gtk::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(title(report.operation))
+        .destroy_with_parent(true)
+        .default_width(760)
+        .default_height(480)
+        .build();
+
+    let content = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .spacing(12)
+        .build();
+
+    let intro = Label::builder()
+        .label(summary(&report))
+        .wrap(true)
+        .selectable(true)
+        .xalign(0.0)
+        .build();
+    content.append(&intro);
+
+    let scroller = gtk::ScrolledWindow::builder()
+        .vexpand(true)
+        .hexpand(true)
+        .min_content_height(200)
+        .has_frame(true)
+        .child(&build_table(&report))
+        .build();
+    content.append(&scroller);
+
+    let save_csv = gtk::Button::with_label(tr("Save as CSV…").as_str());
+    save_csv.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[strong]
+        report,
+        move |_| save(&dialog, report.clone(), true)
+    ));
+    let save_json = gtk::Button::with_label(tr("Save as JSON…").as_str());
+    save_json.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[strong]
+        report,
+        move |_| save(&dialog, report.clone(), false)
+    ));
+    let close = gtk::Button::with_label(tr("Close").as_str());
+    close.add_css_class("suggested-action");
+    close.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| dialog.close()
+    ));
+
+    let button_box = gtk::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .halign(Align::End)
+        .spacing(8)
+        .build();
+    button_box.append(&save_csv);
+    button_box.append(&save_json);
+    button_box.append(&close);
+    content.append(&button_box);
+
+    dialog.set_child(Some(&content));
+    dialog.present();
+}
+
+fn save(parent: &gtk::Window, report: Rc<Report>, csv: bool) {
+    let extension = if csv { "csv" } else { "json" };
+    let default_name = glib::DateTime::now_local()
+        .ok()
+        .and_then(|d| d.format("%Y%m%d-%H%M%S").ok())
+        .map(|s| format!("samrewritten_report_{s}.{extension}"))
+        .unwrap_or_else(|| format!("samrewritten_report.{extension}"));
+
+    let filter = gtk::FileFilter::new();
+    filter.add_pattern(&format!("*.{extension}"));
+    filter.set_name(Some(if csv { "CSV files" } else { "JSON files" }));
+    let filters = ListStore::new::<gtk::FileFilter>();
+    filters.append(&filter);
+
+    let dialog = gtk::FileDialog::builder()
+        .modal(true)
+        .initial_name(&default_name)
+        .filters(&filters)
+        .default_filter(&filter)
+        .title(tr("Save report").as_str())
+        .build();
+
+    let parent = parent.clone();
+    MainContext::default().spawn_local(async move {
+        let Ok(file) = dialog.save_future(Some(&parent)).await else {
+            return;
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+        let content = if csv {
+            Ok(report.to_csv())
+        } else {
+            report.to_json().map_err(|e| e.to_string())
+        };
+        if let Err(e) = content.and_then(|c| std::fs::write(&path, c).map_err(|e| e.to_string())) {
+            show_message_dialog(Some(&parent), &tr("Could not save the report"), &e);
+        }
+    });
+}
@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Picking up a bulk unlock, lock or import that a crash, or a lost backend,
+//! cut short: the apps it had done are skipped, the rest run again.
+
+use super::achievement_loader::AchievementLoader;
+use super::bulk_actions::BulkCancel;
+use super::report_dialog::{LastReport, show_list_with_report};
+use crate::gui_frontend::MainApplication;
+use crate::gui_frontend::application_actions::set_bulk_actions_enabled;
+use crate::gui_frontend::dialogs::show_message_dialog;
+use crate::gui_frontend::gsettings::fan_out_policy;
+use crate::gui_frontend::i18n::{tr, tr_noop};
+use crate::gui_frontend::request::{
+    Canceller, ImportApps, Request, ResetApps, Step, UnlockAllApps,
+};
+use crate::utils::action_journal::{Batch, Change, Op};
+use crate::utils::bulk_jobs::{self, Job, Kind, Running, Status};
+use crate::utils::bulk_report::Report;
+use crate::utils::ipc_types::{FanOutPolicy, ImportSummary, SamError, Snapshotted};
+use gtk::gio::{ListStore, SimpleAction, spawn_blocking};
+use gtk::glib::object::SendWeakRef;
+use gtk::glib::{MainContext, VariantTy, clone};
+use gtk::prelude::*;
+use gtk::{GridView, Label, MenuButton};
+use serde::Serialize;
+use serde_json::Value;
+
+/// What a resumed job did: the apps to refresh, those still not done, and
+/// the report on the whole job.
+struct Resumed {
+    affected: Vec<u32>,
+    left: Vec<String>,
+    report: Report,
+}
+
+fn verb(kind: Kind) -> &'static str {
+    match kind {
+        Kind::UnlockAll => tr_noop("Unlock all achievements"),
+        Kind::Lock => tr_noop("Reset all achievements and stats"),
+        Kind::Import => tr_noop("Import progress"),
+    }
+}
+
+fn journal_op(kind: Kind) -> Op {
+    match kind {
+        Kind::UnlockAll => Op::BulkUnlock,
+        Kind::Lock => Op::BulkLock,
+        Kind::Import => Op::Import,
+    }
+}
+
+/// The journal entries the first run would have written for a finished app.
+fn recorded(kind: Kind, item: &bulk_jobs::Item) -> Option<Vec<Change>> {
+    let answer: Snapshotted<Value> = serde_json::from_value(item.result.clone()?).ok()?;
+    let (detail, granted) = match kind {
+        Kind::UnlockAll => (tr_noop("every achievement unlocked"), Vec::new()),
+        Kind::Lock => (tr_noop("every achievement and stat reset"), Vec::new()),
+        Kind::Import => {
+            let summary: ImportSummary = serde_json::from_value(answer.result).ok()?;
+            if summary.achievements_applied + summary.stats_applied == 0 {
+                return None;
+            }
+            (
+                tr_noop("achievements and stats written from a file"),
+                summary.granted,
+            )
+        }
+    };
+    let mut changes = vec![Change::bulk(detail, answer.before)];
+    changes.extend(Change::granted(granted));
+    Some(changes)
+}
+
+fn label(item: &bulk_jobs::Item) -> String {
+    if item.app_name.is_empty() {
+        format!("App {}", item.app_id)
+    } else {
+        item.app_name.clone()
+    }
+}
+
+/// The error when the request failed as a whole.
+fn run<T, R>(
+    request: R,
+    job: &mut Running,
+    canceller: &Canceller,
+    progress_label: &SendWeakRef<Label>,
+) -> Option<SamError>
+where
+    T: Serialize,
+    R: Request<Response = Vec<(u32, Result<T, SamError>)>>,
+{
+    let response = request.request_stepped(canceller, |step: Step| {
+        job.step(step.app_id, step.ok);
+        let label = progress_label.clone();
+        MainContext::default().invoke(move || {
+            if let Some(l) = label.upgrade() {
+                l.set_text(
+                    &tr("Resuming {done} / {total} app(s)…")
+                        .replace("{done}", &step.done.to_string())
+                        .replace("{total}", &step.total.to_string()),
+                );
+            }
+        });
+    });
+    job.conclude(&response, canceller.is_cancelled());
+    response.err()
+}
+
+/// Runs on a worker thread.
+fn resume(
+    id: u64,
+    policy: FanOutPolicy,
+    canceller: &Canceller,
+    progress_label: &SendWeakRef<Label>,
+) -> Result<Resumed, String> {
+    let mut job = Running::resume(id).map_err(|e| e.to_string())?;
+    let kind = job.job().kind;
+    let items = job.job().remaining();
+    let app_ids: Vec<u32> = items.iter().map(|item| item.app_id).collect();
+    let failure = match kind {
+        Kind::UnlockAll => {
+            let request = UnlockAllApps {
+                app_ids: app_ids.clone(),
+                policy,
+            };
+            run(request, &mut job, canceller, progress_label)
+        }
+        Kind::Lock => {
+            let request = ResetApps {
+                app_ids: app_ids.clone(),
+                achievements_too: true,
+                policy,
+            };
+            run(request, &mut job, canceller, progress_label)
+        }
+        Kind::Import => {
+            let apps = items.into_iter().filter_map(|item| item.export).collect();
+            let request = ImportApps { apps, policy };
+            run(request, &mut job, canceller, progress_label)
+        }
+    };
+
+    let mut journal = Vec::new();
+    let mut left = Vec::new();
+    for item in &job.job().items {
+        if item.status != Status::Done {
+            left.push(label(item));
+        } else if app_ids.contains(&item.app_id)
+            && let Some(changes) = recorded(kind, item)
+        {
+            journal.extend(
+                changes
+                    .into_iter()
+                    .map(|change| (item.app_id, label(item), change)),
+            );
+        }
+    }
+    Batch::across(journal_op(kind)).record_per_app(journal);
+    left.sort();
+    Ok(Resumed {
+        affected: app_ids,
+        left,
+        report: Report::from_job(job.job(), failure.as_ref()),
+    })
+}
+
+/// `app.resume_bulk_job(id)`, run like the bulk actions: the loading popover
+/// shows how far it got, and its Cancel button stops it.
+pub fn create_resume_action(
+    application: &MainApplication,
+    grid_view: &GridView,
+    list_store: &ListStore,
+    achievement_loader: AchievementLoader,
+    context_menu_button: &MenuButton,
+    context_menu_button_loading: &MenuButton,
+    context_menu_button_loading_progress_label: &Label,
+    context_menu_button_info_label: &Label,
+    bulk_cancel: &BulkCancel,
+    last_report: &LastReport,
+) -> SimpleAction {
+    let action = SimpleAction::new("resume_bulk_job", Some(VariantTy::UINT64));
+    action.connect_activate(clone!(
+        #[weak]
+        grid_view,
+        #[weak]
+        application,
+        #[weak]
+        list_store,
+        #[strong]
+        achievement_loader,
+        #[weak]
+        context_menu_button,
+        #[weak]
+        context_menu_button_loading,
+        #[weak]
+        context_menu_button_loading_progress_label,
+        #[weak]
+        context_menu_button_info_label,
+        #[strong]
+        bulk_cancel,
+        #[strong]
+        last_report,
+        move |_, parameter| {
+            let Some(id) = parameter.and_then(|p| p.get::<u64>()) else {
+                return;
+            };
+            set_bulk_actions_enabled(&application, false);
+            context_menu_button_loading.set_visible(true);
+            context_menu_button.set_visible(false);
+            grid_view.set_sensitive(false);
+            context_menu_button_loading_progress_label.set_text(&tr("Resuming…"));
+            context_menu_button_info_label.set_text("");
+
+            let progress_label =
+                SendWeakRef::from(context_menu_button_loading_progress_label.downgrade());
+            let canceller = bulk_cancel.start();
+            let cancelled = canceller.clone();
+            let policy = fan_out_policy();
+            let handle = spawn_blocking(move || resume(id, policy, &canceller, &progress_label));
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                grid_view,
+                #[weak]
+                application,
+                #[weak]
+                list_store,
+                #[strong]
+                achievement_loader,
+                #[weak]
+                context_menu_button_loading,
+                #[weak]
+                context_menu_button,
+                #[strong]
+                last_report,
+                async move {
+                    let outcome = handle
+                        .await
+                        .expect("[CLIENT] Failed to wait for the resumed job");
+
+                    set_bulk_actions_enabled(&application, true);
+                    context_menu_button_loading.set_visible(false);
+                    context_menu_button.set_visible(true);
+                    grid_view.set_sensitive(true);
+
+                    let parent = application.active_window();
+                    match outcome {
+                        Ok(resumed) => {
+                            last_report.set(&application, resumed.report);
+                            if !resumed.left.is_empty()
+                                && let Some(parent) = parent
+                            {
+                                let title = if cancelled.is_cancelled() {
+                                    tr("Resume cancelled")
+                                } else {
+                                    tr("Resume incomplete")
+                                };
+                                show_list_with_report(
+                                    &application,
+                                    &parent,
+                                    &title,
+                                    tr("The following apps were left as they were:").as_str(),
+                                    &resumed.left.join("\n"),
+                                );
+                            }
+                            for id in resumed.affected {
+                                achievement_loader.refresh_app(id, &list_store);
+                            }
+                        }
+                        Err(message) => {
+                            show_message_dialog(parent.as_ref(), &tr("Could not resume"), &message);
+                        }
+                    }
+                }
+            ));
+        }
+    ));
+    action
+}
+
+fn describe(job: &Job) -> String {
+    tr("{operation}: {left} of {total} app(s) left.")
+        .replace("{operation}", &tr(verb(job.kind)))
+        .replace("{left}", &job.remaining().len().to_string())
+        .replace("{total}", &job.items.len().to_string())
+}
+
+/// Once the library is in: ask about the newest job a crash cut short. The
+/// others wait for the next start, or for `samrewritten jobs`.
+pub fn offer_interrupted_job(application: &MainApplication) {
+    let weak_app = application.downgrade();
+    MainContext::default().spawn_local(async move {
+        let Ok(Some(job)) = spawn_blocking(|| bulk_jobs::interrupted().into_iter().next()).await
+        else {
+            return;
+        };
+        let Some(application) = weak_app.upgrade() else {
+            return;
+        };
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(tr("A bulk action did not finish").as_str())
+            .detail(describe(&job))
+            .buttons([
+                tr("Later").as_str(),
+                tr("Discard").as_str(),
+                tr("Resume").as_str(),
+            ])
+            .cancel_button(0)
+            .default_button(2)
+            .build();
+        let parent = application.active_window();
+        match dialog.choose_future(parent.as_ref()).await {
+            Ok(1) => {
+                let id = job.id;
+                let _ = spawn_blocking(move || bulk_jobs::discard(id)).await;
+            }
+            Ok(2) => application.activate_action("resume_bulk_job", Some(&job.id.to_variant())),
+            _ => {}
+        }
+    });
+}
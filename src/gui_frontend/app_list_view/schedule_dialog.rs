@@ -0,0 +1,787 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! The schedule as a page: what is queued and how what ran went, a way to
+//! cancel what has not run yet, and a form to queue more. The GUI's own
+//! orchestrator runs the entries while a window is open, the daemon otherwise.
+
+use super::app_object_for;
+use super::progress_actions::load_export;
+use crate::gui_frontend::MainApplication;
+use crate::gui_frontend::dialogs::show_message_dialog;
+use crate::gui_frontend::gobjects::achievement::GAchievementObject;
+use crate::gui_frontend::gobjects::stat::GStatObject;
+use crate::gui_frontend::i18n::tr;
+use crate::utils::action_journal;
+use crate::utils::ipc_types::{AppExport, AppStatValue};
+use crate::utils::schedule::{self, Entry, Operation, ScheduledStat, Status};
+use gtk::gio::{ListStore, SimpleAction};
+use gtk::glib::{self, MainContext, clone};
+use gtk::prelude::*;
+use gtk::{Align, CheckButton, Label, Orientation, StringList};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+const REFRESH_SECS: u32 = 5;
+
+/// The app page the form queues for, when one is open.
+#[derive(Clone)]
+pub struct AppPage {
+    pub app_id: Rc<Cell<Option<u32>>>,
+    pub list_store: ListStore,
+    pub achievements: ListStore,
+    pub stats: ListStore,
+}
+
+/// `app.show_schedule`.
+pub fn create_schedule_action(application: &MainApplication, page: AppPage) -> SimpleAction {
+    let action = SimpleAction::new("show_schedule", None);
+    action.connect_activate(clone!(
+        #[weak]
+        application,
+        move |_, _| {
+            if let Some(parent) = application.active_window() {
+                show_schedule(&parent, page.clone());
+            }
+        }
+    ));
+    action
+}
+
+/// The same strings as `Operation::detail`, which the journal shows through
+/// `tr` when it lists a run.
+fn operation_label(operation: &Operation) -> String {
+    match operation {
+        Operation::Achievements { unlocked: true, .. } => {
+            tr("achievements unlocked at a scheduled time")
+        }
+        Operation::Achievements {
+            unlocked: false, ..
+        } => tr("achievements locked at a scheduled time"),
+        Operation::Stats { .. } => tr("stats set at a scheduled time"),
+        Operation::Import { .. } => tr("progress written from a file at a scheduled time"),
+        Operation::StartIdle { .. } => tr("idling started at a scheduled time"),
+        Operation::StopIdle { .. } => tr("idling stopped at a scheduled time"),
+    }
+}
+
+fn status_label(status: Status) -> String {
+    match status {
+        Status::Pending => tr("Pending"),
+        Status::Running => tr("Running"),
+        Status::Interrupted => tr("Interrupted"),
+        Status::Done => tr("Done"),
+        Status::Failed => tr("Failed"),
+        Status::Cancelled => tr("Cancelled"),
+    }
+}
+
+fn local_time(secs: u64) -> String {
+    glib::DateTime::from_unix_local(secs as i64)
+        .ok()
+        .and_then(|d| d.format("%x %H:%M").ok())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+fn app_label(entry: &Entry) -> String {
+    let app_ids = entry.operation.app_ids();
+    if !entry.app_name.is_empty() {
+        return entry.app_name.clone();
+    }
+    match app_ids.as_slice() {
+        [app_id] => tr("App {id}").replace("{id}", &app_id.to_string()),
+        _ => tr("{count} apps").replace("{count}", &app_ids.len().to_string()),
+    }
+}
+
+fn entry_row(entry: &Entry, list: &gtk::ListBox, shown: &Rc<RefCell<Vec<Entry>>>) -> gtk::Box {
+    let text = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .hexpand(true)
+        .build();
+    let title = Label::builder()
+        .label(format!(
+            "{} — {}",
+            app_label(entry),
+            operation_label(&entry.operation)
+        ))
+        .xalign(0.0)
+        .wrap(true)
+        .build();
+    title.add_css_class("heading");
+    text.append(&title);
+
+    let mut when = tr("Due {at}").replace("{at}", &local_time(entry.at));
+    if let Some(ran_at) = entry.ran_at {
+        when.push_str(" · ");
+        when.push_str(&tr("ran {at}").replace("{at}", &local_time(ran_at)));
+    }
+    when.push_str(" · ");
+    when.push_str(&status_label(entry.status));
+    let status = Label::builder().label(when).xalign(0.0).wrap(true).build();
+    status.add_css_class("dim-label");
+    text.append(&status);
+
+    if let Some(error) = &entry.error {
+        let error = Label::builder()
+            .label(error.as_str())
+            .xalign(0.0)
+            .wrap(true)
+            .selectable(true)
+            .build();
+        error.add_css_class("warning");
+        text.append(&error);
+    }
+
+    let row = gtk::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(8)
+        .margin_end(8)
+        .build();
+    row.append(&text);
+
+    if entry.status == Status::Pending {
+        let cancel = gtk::Button::with_label(tr("Cancel").as_str());
+        cancel.set_valign(Align::Center);
+        let id = entry.id;
+        cancel.connect_clicked(clone!(
+            #[weak]
+            list,
+            #[strong]
+            shown,
+            move |_| {
+                if let Err(e) = schedule::cancel(id) {
+                    let parent = list.root().and_downcast::<gtk::Window>();
+                    show_message_dialog(
+                        parent.as_ref(),
+                        &tr("Could not cancel the operation"),
+                        &e.to_string(),
+                    );
+                }
+                fill(&list, &shown);
+            }
+        ));
+        row.append(&cancel);
+    }
+    row
+}
+
+/// Rebuilt only when the schedule changed, so that a refresh keeps the place.
+fn fill(list: &gtk::ListBox, shown: &Rc<RefCell<Vec<Entry>>>) {
+    let entries = schedule::load();
+    if *shown.borrow() == entries {
+        return;
+    }
+    list.remove_all();
+    // The latest first: what is about to run, and what just did.
+    for entry in entries.iter().rev() {
+        list.append(&entry_row(entry, list, shown));
+    }
+    *shown.borrow_mut() = entries;
+}
+
+fn show_schedule(parent: &gtk::Window, page: AppPage) {
+    let dialog = gtk::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(tr("Scheduled operations"))
+        .destroy_with_parent(true)
+        .default_width(640)
+        .default_height(480)
+        .build();
+
+    let content = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .spacing(12)
+        .build();
+
+    let intro = Label::builder()
+        .label(tr(
+            "Operations queued for a later time. They run while SamRewritten is open or its daemon is running, and late if neither is when they are due.",
+        ))
+        .wrap(true)
+        .xalign(0.0)
+        .build();
+    content.append(&intro);
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    list.set_placeholder(Some(
+        &Label::builder()
+            .label(tr("Nothing is scheduled."))
+            .margin_top(24)
+            .margin_bottom(24)
+            .build(),
+    ));
+    let shown = Rc::new(RefCell::new(Vec::new()));
+    fill(&list, &shown);
+
+    let scroller = gtk::ScrolledWindow::builder()
+        .vexpand(true)
+        .hexpand(true)
+        .min_content_height(200)
+        .has_frame(true)
+        .child(&list)
+        .build();
+    content.append(&scroller);
+
+    glib::timeout_add_seconds_local(
+        REFRESH_SECS,
+        clone!(
+            #[weak]
+            list,
+            #[strong]
+            shown,
+            #[upgrade_or]
+            glib::ControlFlow::Break,
+            move || {
+                fill(&list, &shown);
+                glib::ControlFlow::Continue
+            }
+        ),
+    );
+
+    let add = gtk::Button::with_label(tr("Add…").as_str());
+    add.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        list,
+        #[strong]
+        shown,
+        #[strong]
+        page,
+        move |_| {
+            let on_added = Rc::new(clone!(
+                #[weak]
+                list,
+                #[strong]
+                shown,
+                move || fill(&list, &shown)
+            ));
+            show_add_form(&dialog, &page, on_added);
+        }
+    ));
+    let close = gtk::Button::with_label(tr("Close").as_str());
+    close.add_css_class("suggested-action");
+    close.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| dialog.close()
+    ));
+
+    let button_box = gtk::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .halign(Align::End)
+        .spacing(8)
+        .build();
+    button_box.append(&add);
+    button_box.append(&close);
+    content.append(&button_box);
+
+    dialog.set_child(Some(&content));
+    dialog.present();
+}
+
+/// What the form can queue, in the order its dropdown lists them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Unlock,
+    Lock,
+    SetStats,
+    StartIdle,
+    StopIdle,
+    Import,
+}
+
+impl Choice {
+    fn label(self) -> String {
+        match self {
+            Choice::Unlock => tr("Unlock achievements"),
+            Choice::Lock => tr("Lock achievements"),
+            Choice::SetStats => tr("Set stats"),
+            Choice::StartIdle => tr("Start idling"),
+            Choice::StopIdle => tr("Stop idling"),
+            Choice::Import => tr("Import a file"),
+        }
+    }
+}
+
+/// The form's inputs for the operation picked last.
+#[derive(Default)]
+struct Picks {
+    achievements: Vec<(String, CheckButton)>,
+    stats: Vec<(GStatObject, gtk::Entry)>,
+    file: Option<Vec<AppExport>>,
+}
+
+/// "YYYY-MM-DD HH:MM" in local time, as Unix seconds.
+fn parse_local_time(text: &str) -> Option<u64> {
+    let (date, time) = text.trim().split_once(' ')?;
+    let date: Vec<i32> = date
+        .split('-')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let [year, month, day] = date[..] else {
+        return None;
+    };
+    let (hour, minute) = time.trim().split_once(':')?;
+    let at = glib::DateTime::from_local(
+        year,
+        month,
+        day,
+        hour.parse().ok()?,
+        minute.parse().ok()?,
+        0.0,
+    )
+    .ok()?;
+    u64::try_from(at.to_unix()).ok()
+}
+
+/// `N`, or `+=N` and `-=N` for a change to whatever the stat holds when the
+/// entry runs.
+fn scheduled_stat(stat: &GStatObject, text: &str) -> Result<ScheduledStat, String> {
+    let invalid = || {
+        tr("\"{value}\" is not a valid value for {stat}")
+            .replace("{value}", text)
+            .replace("{stat}", &stat.display_name())
+    };
+    let (number, relative, subtract) = if let Some(number) = text.strip_prefix("+=") {
+        (number.trim(), true, false)
+    } else if let Some(number) = text.strip_prefix("-=") {
+        (number.trim(), true, true)
+    } else {
+        (text, false, false)
+    };
+    let value = if stat.is_integer() {
+        let n: i32 = number.parse().map_err(|_| invalid())?;
+        let n = if subtract { n.checked_neg() } else { Some(n) };
+        AppStatValue::Int(n.ok_or_else(invalid)?)
+    } else {
+        let n: f32 = number.parse().map_err(|_| invalid())?;
+        AppStatValue::Float(if subtract { -n } else { n })
+    };
+    Ok(ScheduledStat {
+        id: stat.id(),
+        value,
+        relative,
+    })
+}
+
+/// The operation the form describes, or what is missing from it.
+fn operation(choice: Choice, open: Option<u32>, picks: &Picks) -> Result<Operation, String> {
+    let app_id = || open.ok_or_else(|| tr("Open a game first."));
+    match choice {
+        Choice::Unlock | Choice::Lock => {
+            let ids: Vec<String> = picks
+                .achievements
+                .iter()
+                .filter(|(_, check)| check.is_active())
+                .map(|(id, _)| id.clone())
+                .collect();
+            if ids.is_empty() {
+                return Err(tr("Pick at least one achievement."));
+            }
+            Ok(Operation::Achievements {
+                app_id: app_id()?,
+                ids,
+                unlocked: choice == Choice::Unlock,
+            })
+        }
+        Choice::SetStats => {
+            let mut stats = Vec::new();
+            for (stat, entry) in &picks.stats {
+                let text = entry.text();
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                stats.push(scheduled_stat(stat, text)?);
+            }
+            if stats.is_empty() {
+                return Err(tr("Give at least one stat a value."));
+            }
+            Ok(Operation::Stats {
+                app_id: app_id()?,
+                stats,
+            })
+        }
+        Choice::StartIdle => Ok(Operation::StartIdle { app_id: app_id()? }),
+        Choice::StopIdle => Ok(Operation::StopIdle { app_id: app_id()? }),
+        Choice::Import => match &picks.file {
+            Some(apps) => Ok(Operation::Import { apps: apps.clone() }),
+            None => Err(tr("Choose a file to import.")),
+        },
+    }
+}
+
+fn read_export(path: &std::path::Path) -> Result<Vec<AppExport>, String> {
+    let parsed = load_export(path)?;
+    if parsed.apps.is_empty() {
+        return Err(tr("The file holds no apps."));
+    }
+    Ok(parsed.apps)
+}
+
+fn scroller(child: &impl IsA<gtk::Widget>) -> gtk::ScrolledWindow {
+    gtk::ScrolledWindow::builder()
+        .vexpand(true)
+        .min_content_height(160)
+        .has_frame(true)
+        .child(child)
+        .build()
+}
+
+fn note(text: &str) -> Label {
+    Label::builder()
+        .label(text)
+        .wrap(true)
+        .margin_top(12)
+        .margin_bottom(12)
+        .build()
+}
+
+/// Swaps the inputs under the dropdown for those `choice` needs.
+fn show_inputs(
+    holder: &gtk::Box,
+    choice: Choice,
+    page: &AppPage,
+    picks: &Rc<RefCell<Picks>>,
+    form: &gtk::Window,
+) {
+    while let Some(child) = holder.first_child() {
+        holder.remove(&child);
+    }
+    *picks.borrow_mut() = Picks::default();
+
+    match choice {
+        Choice::Unlock | Choice::Lock => {
+            let unlock = choice == Choice::Unlock;
+            let column = gtk::Box::builder()
+                .orientation(Orientation::Vertical)
+                .spacing(4)
+                .margin_top(8)
+                .margin_bottom(8)
+                .margin_start(8)
+                .margin_end(8)
+                .build();
+            // Protected ones cannot be written, now or later.
+            for achievement in page
+                .achievements
+                .iter::<GAchievementObject>()
+                .flatten()
+                .filter(|a| a.permission() == 0 && a.is_achieved() != unlock)
+            {
+                let check = CheckButton::with_label(&achievement.name());
+                column.append(&check);
+                picks
+                    .borrow_mut()
+                    .achievements
+                    .push((achievement.id(), check));
+            }
+            if picks.borrow().achievements.is_empty() {
+                holder.append(&scroller(&note(&if unlock {
+                    tr("Every achievement that can be unlocked already is.")
+                } else {
+                    tr("No achievement that can be locked is unlocked.")
+                })));
+                return;
+            }
+            holder.append(&scroller(&column));
+        }
+        Choice::SetStats => {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .margin_top(8)
+                .margin_bottom(8)
+                .margin_start(8)
+                .margin_end(8)
+                .build();
+            for stat in page
+                .stats
+                .iter::<GStatObject>()
+                .flatten()
+                .filter(|s| (s.permission() & 2) == 0)
+            {
+                let line = picks.borrow().stats.len() as i32;
+                let name = Label::builder()
+                    .label(stat.display_name())
+                    .xalign(0.0)
+                    .hexpand(true)
+                    .wrap(true)
+                    .build();
+                let value = gtk::Entry::builder()
+                    .placeholder_text(stat.current_value().to_string())
+                    .tooltip_text(tr(
+                        "A value, or +=N or -=N to change what the stat holds when this runs",
+                    ))
+                    .build();
+                grid.attach(&name, 0, line, 1, 1);
+                grid.attach(&value, 1, line, 1, 1);
+                picks.borrow_mut().stats.push((stat, value));
+            }
+            if picks.borrow().stats.is_empty() {
+                holder.append(&scroller(&note(&tr(
+                    "This game has no stat that can be set.",
+                ))));
+                return;
+            }
+            holder.append(&scroller(&grid));
+        }
+        Choice::StartIdle | Choice::StopIdle => {}
+        Choice::Import => {
+            let chosen = Label::builder()
+                .label(tr("No file chosen."))
+                .xalign(0.0)
+                .hexpand(true)
+                .wrap(true)
+                .build();
+            let choose = gtk::Button::with_label(tr("Choose a file…").as_str());
+            choose.connect_clicked(clone!(
+                #[weak]
+                form,
+                #[weak]
+                chosen,
+                #[strong]
+                picks,
+                move |_| choose_file(&form, &chosen, &picks)
+            ));
+            let line = gtk::Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(8)
+                .build();
+            line.append(&chosen);
+            line.append(&choose);
+            holder.append(&line);
+        }
+    }
+}
+
+fn choose_file(form: &gtk::Window, chosen: &Label, picks: &Rc<RefCell<Picks>>) {
+    let json_filter = gtk::FileFilter::new();
+    json_filter.add_pattern("*.json");
+    json_filter.set_name(Some("JSON files"));
+    let filters = ListStore::new::<gtk::FileFilter>();
+    filters.append(&json_filter);
+    let dialog = gtk::FileDialog::builder()
+        .modal(true)
+        .filters(&filters)
+        .default_filter(&json_filter)
+        .title(tr("Import progress").as_str())
+        .build();
+
+    let form = form.clone();
+    let chosen = chosen.clone();
+    let picks = picks.clone();
+    MainContext::default().spawn_local(async move {
+        let Ok(file) = dialog.open_future(Some(&form)).await else {
+            return;
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+        match read_export(&path) {
+            Ok(apps) => {
+                chosen.set_label(
+                    &tr("{file}: {count} app(s)")
+                        .replace("{file}", &path.display().to_string())
+                        .replace("{count}", &apps.len().to_string()),
+                );
+                picks.borrow_mut().file = Some(apps);
+            }
+            Err(e) => show_message_dialog(Some(&form), &tr("Import failed"), &e),
+        }
+    });
+}
+
+fn show_add_form(parent: &gtk::Window, page: &AppPage, on_added: Rc<dyn Fn()>) {
+    let app_id = page.app_id.get();
+    let choices: Vec<Choice> = if app_id.is_some() {
+        vec![
+            Choice::Unlock,
+            Choice::Lock,
+            Choice::SetStats,
+            Choice::StartIdle,
+            Choice::StopIdle,
+            Choice::Import,
+        ]
+    } else {
+        vec![Choice::Import]
+    };
+
+    let form = gtk::Window::builder()
+        .transient_for(parent)
+        .modal(true)
+        .title(tr("Schedule an operation"))
+        .destroy_with_parent(true)
+        .default_width(520)
+        .default_height(480)
+        .build();
+
+    let content = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .spacing(12)
+        .build();
+
+    let app_name = app_id.map(|app_id| app_object_for(&page.list_store, app_id).app_name());
+    let intro = Label::builder()
+        .label(match &app_name {
+            Some(name) => tr("For {app}, or for the apps in a file.").replace("{app}", name),
+            None => {
+                tr("Open a game to schedule changes to it; from here, only a file can be imported.")
+            }
+        })
+        .wrap(true)
+        .xalign(0.0)
+        .build();
+    content.append(&intro);
+
+    let labels: Vec<String> = choices.iter().map(|c| c.label()).collect();
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let dropdown = gtk::DropDown::builder()
+        .model(&StringList::new(&labels))
+        .build();
+    content.append(&dropdown);
+
+    let holder = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .vexpand(true)
+        .build();
+    content.append(&holder);
+    let picks = Rc::new(RefCell::new(Picks::default()));
+    show_inputs(&holder, choices[0], page, &picks, &form);
+    dropdown.connect_selected_notify(clone!(
+        #[weak]
+        holder,
+        #[weak]
+        form,
+        #[strong]
+        picks,
+        #[strong]
+        page,
+        #[strong]
+        choices,
+        move |dropdown| {
+            if let Some(&choice) = choices.get(dropdown.selected() as usize) {
+                show_inputs(&holder, choice, &page, &picks, &form);
+            }
+        }
+    ));
+
+    let when_label = Label::builder()
+        .label(tr("When (YYYY-MM-DD HH:MM, local time)"))
+        .xalign(0.0)
+        .build();
+    let when = gtk::Entry::builder()
+        .text(
+            glib::DateTime::now_local()
+                .and_then(|now| now.add_hours(1))
+                .and_then(|at| at.format("%Y-%m-%d %H:%M"))
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+        )
+        .build();
+    content.append(&when_label);
+    content.append(&when);
+
+    let problem = Label::builder()
+        .xalign(0.0)
+        .wrap(true)
+        .visible(false)
+        .build();
+    problem.add_css_class("warning");
+    content.append(&problem);
+
+    let cancel = gtk::Button::with_label(tr("Cancel").as_str());
+    cancel.connect_clicked(clone!(
+        #[weak]
+        form,
+        move |_| form.close()
+    ));
+    let schedule_button = gtk::Button::with_label(tr("Schedule").as_str());
+    schedule_button.add_css_class("suggested-action");
+    schedule_button.connect_clicked(clone!(
+        #[weak]
+        form,
+        #[weak]
+        dropdown,
+        #[weak]
+        when,
+        #[weak]
+        problem,
+        #[strong]
+        picks,
+        #[strong]
+        choices,
+        move |_| {
+            let Some(&choice) = choices.get(dropdown.selected() as usize) else {
+                return;
+            };
+            let result = parse_local_time(&when.text())
+                .ok_or_else(|| tr("Write the time as YYYY-MM-DD HH:MM."))
+                .and_then(|at| Ok((at, operation(choice, app_id, &picks.borrow())?)));
+            let (at, operation) = match result {
+                Ok(ok) => ok,
+                Err(e) => {
+                    problem.set_label(&e);
+                    problem.set_visible(true);
+                    return;
+                }
+            };
+            let name = match &operation {
+                Operation::Import { apps } => match apps.as_slice() {
+                    [app] => app.app_name.clone(),
+                    _ => String::new(),
+                },
+                _ => app_name.clone().unwrap_or_default(),
+            };
+            schedule::add(
+                at,
+                &name,
+                operation,
+                action_journal::is_enabled(),
+                action_journal::account(),
+            );
+            on_added();
+            form.close();
+        }
+    ));
+
+    let button_box = gtk::Box::builder()
+        .orientation(Orientation::Horizontal)
+        .halign(Align::End)
+        .spacing(8)
+        .build();
+    button_box.append(&cancel);
+    button_box.append(&schedule_button);
+    content.append(&button_box);
+
+    form.set_child(Some(&content));
+    form.present();
+}
@@ -15,6 +15,7 @@
 
 use crate::dev_println;
 use crate::gui_frontend::MainApplication;
+use crate::gui_frontend::diagnostics_dialog::show_diagnostics_dialog;
 use crate::gui_frontend::ui_components::show_about_dialog;
 use gtk::gio::SimpleAction;
 use gtk::glib;
@@ -32,6 +33,7 @@ pub fn setup_app_actions(
     lock_all_apps_action: &SimpleAction,
     export_selected_progress_action: &SimpleAction,
     import_progress_action: &SimpleAction,
+    cancel_bulk_operation_action: &SimpleAction,
 ) {
     let action_show_about_dialog = SimpleAction::new("about", None);
     action_show_about_dialog.connect_activate(clone!(
@@ -42,6 +44,17 @@ pub fn setup_app_actions(
         }
     ));
 
+    let action_show_diagnostics = SimpleAction::new("diagnostics", None);
+    action_show_diagnostics.connect_activate(clone!(
+        #[weak]
+        application,
+        move |_, _| {
+            if let Some(parent) = application.active_window() {
+                show_diagnostics_dialog(parent);
+            }
+        }
+    ));
+
     let action_quit = SimpleAction::new("quit", None);
     action_quit.connect_activate(clone!(
         #[weak]
@@ -57,10 +70,12 @@ pub fn setup_app_actions(
     application.add_action(lock_all_apps_action);
     application.add_action(export_selected_progress_action);
     application.add_action(import_progress_action);
+    application.add_action(cancel_bulk_operation_action);
     application.add_action(refresh_app_list_action);
     application.add_action(refresh_achievements_list_action);
     application.add_action(reset_all_stats_and_achievements_action);
     application.add_action(&action_show_about_dialog);
+    application.add_action(&action_show_diagnostics);
     application.add_action(&action_quit);
 
     // Snap-only: re-exec after forgetting the grant — resets the locator's
@@ -104,16 +119,21 @@ pub fn set_app_action_enabled(application: &MainApplication, action_name: &str,
 }
 
 /// A refresh cancels a running timed unlock, and a language change goes through a
-/// refresh, so both come off the menu while the timed view is up.
+/// refresh, so both come off the menu while the timed view is up. So does
+/// starting another one.
 pub fn set_timed_unlock_actions_enabled(application: &MainApplication, enabled: bool) {
     set_app_action_enabled(application, "refresh_achievements_list", enabled);
+    set_app_action_enabled(application, "start_timed_unlock", enabled);
     set_app_action_enabled(application, "clear_all_stats_and_achievements", enabled);
     set_app_action_enabled(application, "achievement-language", enabled);
 }
 
+/// They are disabled while one of them runs, which is when it can be cancelled.
 pub fn set_bulk_actions_enabled(application: &MainApplication, enabled: bool) {
     set_app_action_enabled(application, "unlock_all_apps", enabled);
     set_app_action_enabled(application, "lock_all_apps", enabled);
     set_app_action_enabled(application, "export_selected_progress", enabled);
     set_app_action_enabled(application, "import_progress", enabled);
+    set_app_action_enabled(application, "resume_bulk_job", enabled);
+    set_app_action_enabled(application, "cancel_bulk_operation", !enabled);
 }
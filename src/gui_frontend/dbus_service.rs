@@ -0,0 +1,305 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A control interface on the session bus, for launchers and scripts to drive a
+//! window that is already open. The application is `NON_UNIQUE`, so GTK never
+//! claims its id as a bus name; this does. The first window gets it, and later
+//! ones queue behind it and take over when it closes.
+//!
+//! Calls become the application's own actions, the ones its buttons and menus
+//! use, so a call does what the equivalent click would and is refused when that
+//! click would be impossible.
+
+use crate::APP_ID;
+use crate::dev_println;
+use crate::gui_frontend::MainApplication;
+use gtk::gio::{self, BusNameOwnerFlags, DBusConnection, DBusMethodInvocation, DBusNodeInfo};
+use gtk::glib::{self, Variant, VariantDict};
+use gtk::prelude::*;
+
+pub const OBJECT_PATH: &str = "/org/samrewritten/SamRewritten/Control";
+pub const INTERFACE: &str = "org.samrewritten.SamRewritten.Control";
+const ERROR_UNAVAILABLE: &str = "org.samrewritten.SamRewritten.Error.Unavailable";
+
+const INTROSPECTION: &str = r#"<node>
+  <interface name="org.samrewritten.SamRewritten.Control">
+    <method name="OpenApp">
+      <arg name="app_id" type="u" direction="in"/>
+    </method>
+    <method name="Refresh"/>
+    <method name="StartIdling">
+      <arg name="app_id" type="u" direction="in"/>
+    </method>
+    <method name="StopIdling">
+      <arg name="app_id" type="u" direction="in"/>
+    </method>
+    <method name="StartTimedUnlock">
+      <arg name="minutes" type="u" direction="in"/>
+      <arg name="count" type="u" direction="in"/>
+    </method>
+    <method name="GetProgress">
+      <arg name="progress" type="a{sv}" direction="out"/>
+    </method>
+  </interface>
+</node>"#;
+
+/// What `GetProgress` reports. App fields are zero outside an app's page.
+#[derive(Debug, Default)]
+pub struct Progress {
+    /// `loading`, `list`, `disconnected`, `app` or `profile`.
+    pub page: String,
+    pub app_id: u32,
+    pub achievements_unlocked: u32,
+    pub achievements_total: u32,
+    /// Of the running or last timed unlock; zero when none was started.
+    pub timed_unlock_done: u32,
+    pub timed_unlock_total: u32,
+    pub idling: Vec<u32>,
+}
+
+impl Progress {
+    fn to_dict(&self) -> Variant {
+        let dict = VariantDict::new(None);
+        dict.insert("page", self.page.as_str());
+        dict.insert("app-id", self.app_id);
+        dict.insert("achievements-unlocked", self.achievements_unlocked);
+        dict.insert("achievements-total", self.achievements_total);
+        dict.insert("timed-unlock-done", self.timed_unlock_done);
+        dict.insert("timed-unlock-total", self.timed_unlock_total);
+        dict.insert("idling", self.idling.to_variant());
+        dict.end()
+    }
+}
+
+/// Export the interface on the application's bus connection and ask for the
+/// application id as a name. Without a session bus there is nothing to do.
+pub fn start(application: &MainApplication, progress: impl Fn() -> Progress + 'static) {
+    let Some(connection) = application.dbus_connection() else {
+        dev_println!(
+            "DBUS",
+            "No session bus, not exporting the control interface"
+        );
+        return;
+    };
+    if let Err(e) = export(&connection, application, progress) {
+        eprintln!("[DBUS] Failed to export the control interface: {e}");
+        return;
+    }
+    // Dropping the id keeps the name: it is only given up with the connection.
+    let _ = gio::bus_own_name_on_connection(
+        &connection,
+        APP_ID,
+        BusNameOwnerFlags::NONE,
+        |_, name| dev_println!("DBUS", "Acquired {name}"),
+        |_, name| dev_println!("DBUS", "Waiting for {name}"),
+    );
+}
+
+/// Register the control object, turning calls into actions of `actions`.
+pub fn export(
+    connection: &DBusConnection,
+    actions: &impl IsA<gio::ActionGroup>,
+    progress: impl Fn() -> Progress + 'static,
+) -> Result<gio::RegistrationId, glib::Error> {
+    let node = DBusNodeInfo::for_xml(INTROSPECTION)?;
+    let interface = node
+        .lookup_interface(INTERFACE)
+        .expect("the interface is declared above");
+    let actions = actions.as_ref().clone();
+    connection
+        .register_object(OBJECT_PATH, &interface)
+        .method_call(move |_, sender, _, _, method, parameters, invocation| {
+            dev_println!("DBUS", "{method}{parameters} from {sender}");
+            dispatch(&actions, method, &parameters, invocation, &progress);
+        })
+        .build()
+}
+
+fn dispatch(
+    actions: &gio::ActionGroup,
+    method: &str,
+    parameters: &Variant,
+    invocation: DBusMethodInvocation,
+    progress: &dyn Fn() -> Progress,
+) {
+    let progress = progress();
+    if method == "GetProgress" {
+        invocation.return_value(Some(&Variant::tuple_from_iter([progress.to_dict()])));
+        return;
+    }
+
+    let on_app_page = progress.page == "app";
+    let (action, parameter) = match method {
+        "OpenApp" => ("open_app", Some(parameters.child_value(0))),
+        "Refresh" if on_app_page => ("refresh_achievements_list", None),
+        "Refresh" => ("refresh_app_list", None),
+        "StartIdling" => ("start_idling", Some(parameters.child_value(0))),
+        "StopIdling" => ("stop_idling", Some(parameters.child_value(0))),
+        "StartTimedUnlock" => ("start_timed_unlock", Some(parameters.clone())),
+        _ => {
+            // The bus checks calls against the introspection data first.
+            invocation.return_dbus_error(
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                &format!("No method {method}"),
+            );
+            return;
+        }
+    };
+
+    // Timed unlocks act on the open app, and its page is the only place they
+    // are offered.
+    let possible = actions.has_action(action)
+        && actions.is_action_enabled(action)
+        && (action != "start_timed_unlock" || on_app_page);
+    if !possible {
+        invocation.return_dbus_error(
+            ERROR_UNAVAILABLE,
+            &format!(
+                "{method} is not available on the {} page right now",
+                progress.page
+            ),
+        );
+        return;
+    }
+
+    actions.activate_action(action, parameter.as_ref());
+    invocation.return_value(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gtk::gio::{DBusCallFlags, DBusConnectionFlags, SimpleAction, SimpleActionGroup};
+    use std::cell::RefCell;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::rc::Rc;
+
+    /// A bus of the test's own, gone when the test is.
+    struct PrivateBus(Child);
+
+    impl PrivateBus {
+        fn start() -> Option<(Self, String)> {
+            let mut child = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            BufReader::new(child.stdout.take()?)
+                .read_line(&mut address)
+                .ok()?;
+            Some((Self(child), address.trim().to_owned()))
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    #[test]
+    fn calls_become_actions_on_a_private_bus() {
+        let Some((_bus, address)) = PrivateBus::start() else {
+            eprintln!("dbus-daemon is not installed, skipping");
+            return;
+        };
+        let context = glib::MainContext::new();
+        context
+            .with_thread_default(|| {
+                let connect = || {
+                    DBusConnection::for_address_sync(
+                        &address,
+                        DBusConnectionFlags::AUTHENTICATION_CLIENT
+                            | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+                        None,
+                        gio::Cancellable::NONE,
+                    )
+                    .unwrap()
+                };
+                let service = connect();
+                let client = connect();
+
+                let activated = Rc::new(RefCell::new(Vec::new()));
+                let actions = SimpleActionGroup::new();
+                for (name, parameter) in [
+                    ("open_app", Some(glib::VariantTy::UINT32)),
+                    ("refresh_app_list", None),
+                    (
+                        "start_timed_unlock",
+                        Some(glib::VariantTy::new("(uu)").unwrap()),
+                    ),
+                ] {
+                    let action = SimpleAction::new(name, parameter);
+                    let activated = activated.clone();
+                    action.connect_activate(move |action, parameter| {
+                        activated
+                            .borrow_mut()
+                            .push((action.name().to_string(), parameter.map(|p| p.to_string())));
+                    });
+                    actions.add_action(&action);
+                }
+
+                export(&service, &actions, || Progress {
+                    page: "list".to_owned(),
+                    idling: vec![480],
+                    ..Default::default()
+                })
+                .unwrap();
+
+                let call = |method: &str, parameters: Option<Variant>| {
+                    context.block_on(client.call_future(
+                        service.unique_name().as_deref(),
+                        OBJECT_PATH,
+                        INTERFACE,
+                        method,
+                        parameters.as_ref(),
+                        None,
+                        DBusCallFlags::NONE,
+                        5_000,
+                    ))
+                };
+
+                call("OpenApp", Some((480u32,).to_variant())).unwrap();
+                call("Refresh", None).unwrap();
+                assert_eq!(
+                    *activated.borrow(),
+                    [
+                        ("open_app".to_owned(), Some("uint32 480".to_owned())),
+                        ("refresh_app_list".to_owned(), None),
+                    ]
+                );
+
+                // Not on an app's page, and not an action this group has at all.
+                assert!(call("StartTimedUnlock", Some((30u32, 5u32).to_variant())).is_err());
+                assert!(call("StartIdling", Some((480u32,).to_variant())).is_err());
+                assert_eq!(activated.borrow().len(), 2);
+
+                let reply = call("GetProgress", None).unwrap();
+                let progress = VariantDict::new(Some(&reply.child_value(0)));
+                assert_eq!(
+                    progress.lookup::<String>("page").unwrap().as_deref(),
+                    Some("list")
+                );
+                assert_eq!(
+                    progress.lookup::<Vec<u32>>("idling").unwrap(),
+                    Some(vec![480])
+                );
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The CLI's `doctor` report as a page, for when the app list will not load.
+//! The body stays the raw report, since its main use is pasting into an issue.
+
+use crate::backend::diagnostics;
+use crate::gui_frontend::dialogs::show_list_dialog;
+use crate::gui_frontend::i18n::tr;
+use gtk::gio::spawn_blocking;
+use gtk::glib::MainContext;
+
+pub fn show_diagnostics_dialog(parent: gtk::Window) {
+    MainContext::default().spawn_local(async move {
+        // The handshake can wait out the orchestrator's connection timeout.
+        let Ok(report) =
+            spawn_blocking(|| diagnostics::collect().with_handshake(diagnostics::handshake()))
+                .await
+        else {
+            return;
+        };
+
+        let intro = if report.problems.is_empty() {
+            tr("Nothing looks wrong: Steam was found and answered the test connection.").to_string()
+        } else {
+            let mut intro = tr("Possible problems, most likely cause first:").to_string();
+            for problem in &report.problems {
+                intro.push_str("\n• ");
+                intro.push_str(problem);
+            }
+            intro
+        };
+        let body = serde_json::to_string_pretty(&report).unwrap_or_default();
+
+        show_list_dialog(&parent, tr("Diagnostics").as_str(), &intro, &body);
+    });
+}
@@ -35,6 +35,30 @@ pub fn show_list_dialog(
     title: &str,
     intro: &str,
     body: &str,
+) {
+    list_dialog(parent, title, intro, body, None);
+}
+
+/// `show_list_dialog` with one more button beside OK, which closes the dialog
+/// and then runs `on_extra`.
+pub fn show_list_dialog_with(
+    parent: &impl gtk::glib::object::IsA<gtk::Window>,
+    title: &str,
+    intro: &str,
+    body: &str,
+    extra_label: &str,
+    on_extra: impl Fn() + 'static,
+) {
+    let on_extra: Box<dyn Fn()> = Box::new(on_extra);
+    list_dialog(parent, title, intro, body, Some((extra_label, on_extra)));
+}
+
+fn list_dialog(
+    parent: &impl gtk::glib::object::IsA<gtk::Window>,
+    title: &str,
+    intro: &str,
+    body: &str,
+    extra: Option<(&str, Box<dyn Fn()>)>,
 ) {
     let dialog = gtk::Window::builder()
         .transient_for(parent)
@@ -97,7 +121,20 @@ pub fn show_list_dialog(
     let button_box = gtk::Box::builder()
         .orientation(Orientation::Horizontal)
         .halign(Align::End)
+        .spacing(8)
         .build();
+    if let Some((label, on_extra)) = extra {
+        let extra_button = gtk::Button::with_label(label);
+        extra_button.connect_clicked(clone!(
+            #[weak]
+            dialog,
+            move |_| {
+                dialog.close();
+                on_extra();
+            }
+        ));
+        button_box.append(&extra_button);
+    }
     button_box.append(&ok_button);
     content.append(&button_box);
 
@@ -283,7 +283,7 @@ pub fn open_friend_picker<Fut>(
                 count_busy.set(false);
                 let state = match result {
                     Ok(Ok((achieved, total))) => CountState::Ready(achieved, total),
-                    Ok(Err(SamError::ProfilePrivate)) => CountState::Private,
+                    Ok(Err(e)) if *e.kind() == SamError::ProfilePrivate => CountState::Private,
                     _ => CountState::Failed,
                 };
                 count_cache.borrow_mut().insert(sid, state.clone());
@@ -493,7 +493,7 @@ pub fn open_friend_picker<Fut>(
                     match result {
                         Ok(()) => window.close(),
                         Err(e) => {
-                            let msg = if e == SamError::ProfilePrivate {
+                            let msg = if *e.kind() == SamError::ProfilePrivate {
                                 tr("This profile is private")
                             } else {
                                 tr("Couldn't load this user")
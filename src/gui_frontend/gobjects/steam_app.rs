@@ -14,6 +14,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::backend::app_lister::{AppModel, AppModelType};
+use crate::backend::library_query::{completion, remaining};
 use crate::utils::steam_locator::SteamLocator;
 use glib::Object;
 use gtk::glib;
@@ -172,17 +173,8 @@ impl GSteamAppObject {
         self.set_achievements_loaded(loaded);
 
         let imp = self.imp();
-        imp.completion.set(if loaded && total > 0 {
-            f64::from(unlocked) / f64::from(total)
-        } else {
-            -1.0
-        });
-        let remaining = total.saturating_sub(unlocked);
-        imp.remaining.set(if loaded && remaining > 0 {
-            remaining
-        } else {
-            u32::MAX
-        });
+        imp.completion.set(completion(total, unlocked, loaded));
+        imp.remaining.set(remaining(total, unlocked, loaded));
     }
 
     pub fn completion(&self) -> f64 {
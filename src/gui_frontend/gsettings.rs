@@ -1,5 +1,7 @@
+use crate::utils::ipc_types::FanOutPolicy;
 use crate::{APP_ID, dev_println};
 use gtk::gio::Settings;
+use std::ops::RangeInclusive;
 
 pub const LAST_SCAN_KEY: &str = "last-counts-scan";
 
@@ -40,3 +42,23 @@ pub fn get_settings() -> Settings {
     // Arch, Windows
     Settings::new(APP_ID)
 }
+
+/// The ranges the schema gives the `bulk-*` keys, for the settings window and
+/// for keys written around it.
+pub const BULK_CONCURRENCY: RangeInclusive<i32> = 1..=16;
+pub const BULK_RETRIES: RangeInclusive<i32> = 0..=10;
+pub const BULK_BACKOFF_MS: RangeInclusive<i32> = 0..=60_000;
+
+/// How bulk actions pace their apps, from the `bulk-*` keys.
+pub fn fan_out_policy() -> FanOutPolicy {
+    let settings = get_settings();
+    let int = |key: &str, range: RangeInclusive<i32>| {
+        settings.int(key).clamp(*range.start(), *range.end())
+    };
+    FanOutPolicy {
+        concurrency: int("bulk-concurrency", BULK_CONCURRENCY) as usize,
+        retries: int("bulk-retries", BULK_RETRIES) as u32,
+        backoff_ms: int("bulk-backoff-ms", BULK_BACKOFF_MS) as u64,
+        adaptive: settings.boolean("bulk-adaptive"),
+    }
+}
@@ -21,9 +21,11 @@ mod app_list_view_callbacks;
 mod app_view;
 mod application_actions;
 mod custom_progress_bar_widget;
+mod dbus_service;
 /// Scripting aid for driving the UI from outside; never built into a release.
 #[cfg(debug_assertions)]
 mod dev_widgets;
+mod diagnostics_dialog;
 mod dialogs;
 mod friend_picker;
 mod gobjects;
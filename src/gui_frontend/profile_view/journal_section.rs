@@ -18,12 +18,13 @@
 //! An undo does not go through `SetAchievement`: that is only forwarded to a
 //! child already holding the app open, and nothing is open from here. It goes
 //! through `ImportApps`, carrying the recorded before-values as a partial
-//! export.
+//! export. A library-wide action is undone the same way, one export per game
+//! built from the snapshot each of its children took before writing.
 
 use super::{boxed_list, caption, clear_list, section_heading};
 use crate::gui_frontend::dialogs::{confirm_dialog, show_list_dialog};
-use crate::gui_frontend::gsettings::get_settings;
-use crate::gui_frontend::i18n::tr;
+use crate::gui_frontend::gsettings::{fan_out_policy, get_settings};
+use crate::gui_frontend::i18n::{tr, tr_noop};
 use crate::gui_frontend::request::{ImportApps, Request};
 use crate::utils::action_journal::{self, Batch, Change, Op, Operation, RecordedChange, Reverses};
 use crate::utils::ipc_types::{AppAchievementExport, AppExport, AppStatExport, AppStatValue};
@@ -428,12 +429,19 @@ impl JournalSection {
         Some(button)
     }
 
-    fn title_for(&self, operation: &Operation) -> String {
-        let where_ = if operation.apps.len() > 1 {
+    /// The game, or how many, an operation touched.
+    fn where_(&self, operation: &Operation) -> String {
+        if operation.apps.len() > 1 {
             tr("{count} games").replace("{count}", &operation.apps.len().to_string())
+        } else if let Some((app_id, app_name)) = operation.apps.first() {
+            self.app_label(*app_id, app_name)
         } else {
             self.app_label(operation.app_id, &operation.app_name)
-        };
+        }
+    }
+
+    fn title_for(&self, operation: &Operation) -> String {
+        let where_ = self.where_(operation);
 
         let achievements: Vec<&RecordedChange> = operation
             .changes
@@ -464,10 +472,17 @@ impl JournalSection {
             Op::ResetApp | Op::BulkLock => tr("Reset {app}"),
             Op::BulkUnlock => tr("Unlocked everything in {app}"),
             Op::Import => tr("Wrote a file's progress into {app}"),
+            Op::Scheduled => tr("Ran a scheduled operation in {app}"),
             _ => tr("Changed {count} thing(s) in {app}"),
         };
+        // Steam's own unlocks ride along; they are not what was done.
+        let done = operation
+            .changes
+            .iter()
+            .filter(|c| !matches!(c.change, Change::Granted { .. }))
+            .count();
         template
-            .replace("{count}", &operation.changes.len().to_string())
+            .replace("{count}", &done.to_string())
             .replace("{app}", &where_)
     }
 
@@ -518,10 +533,13 @@ impl JournalSection {
                 .replace("{name}", label)
                 .replace("{before}", &before.to_string())
                 .replace("{after}", &after.to_string()),
-            Change::Opaque { detail } => {
+            Change::Opaque { detail } | Change::Snapshot { detail, .. } => {
                 let app = self.app_label(recorded.app_id, &recorded.app_name);
                 format!("{app} — {}", tr(detail))
             }
+            Change::Granted { .. } => {
+                tr("{name} — unlocked by Steam itself").replace("{name}", label)
+            }
         }
     }
 
@@ -582,14 +600,13 @@ impl JournalSection {
             .widget
             .root()
             .and_then(|root| root.downcast::<gtk::Window>().ok());
-        let app_name = self.app_label(operation.app_id, &operation.app_name);
 
         let went_ahead = confirm_dialog(
             window.as_ref(),
             tr("Undo this change?").as_str(),
             &tr("{count} change(s) in {app} will be put back the way they were.")
                 .replace("{count}", &picked.len().to_string())
-                .replace("{app}", &app_name),
+                .replace("{app}", &self.where_(&operation)),
             tr("Undo").as_str(),
             false,
         )
@@ -598,14 +615,27 @@ impl JournalSection {
             return;
         }
 
-        let mut export = AppExport {
-            app_id: operation.app_id,
-            app_name: app_name.clone(),
-            achievements: Vec::new(),
-            stats: Vec::new(),
-        };
-        let mut inverse = Vec::new();
+        // One export per app: a bulk operation's changes span many.
+        let mut exports: Vec<AppExport> = Vec::new();
+        let mut inverse: Vec<(u32, Change)> = Vec::new();
+        // Apps whose snapshot goes back. Theirs is filed once the undo answers
+        // with what it found.
+        let mut snapshots: Vec<u32> = Vec::new();
         for (_, recorded) in &picked {
+            let app_id = recorded.app_id;
+            let position = match exports.iter().position(|e| e.app_id == app_id) {
+                Some(position) => position,
+                None => {
+                    exports.push(AppExport {
+                        app_id,
+                        app_name: self.app_label(app_id, &recorded.app_name),
+                        achievements: Vec::new(),
+                        stats: Vec::new(),
+                    });
+                    exports.len() - 1
+                }
+            };
+            let export = &mut exports[position];
             match &recorded.change {
                 Change::Achievement {
                     id,
@@ -618,12 +648,15 @@ impl JournalSection {
                         is_achieved: *before,
                         permission: 0,
                     });
-                    inverse.push(Change::Achievement {
-                        id: id.clone(),
-                        name: name.clone(),
-                        before: *after,
-                        after: *before,
-                    });
+                    inverse.push((
+                        app_id,
+                        Change::Achievement {
+                            id: id.clone(),
+                            name: name.clone(),
+                            before: *after,
+                            after: *before,
+                        },
+                    ));
                 }
                 Change::IntStat {
                     id,
@@ -636,12 +669,15 @@ impl JournalSection {
                         value: AppStatValue::Int(*before),
                         permission: 0,
                     });
-                    inverse.push(Change::IntStat {
-                        id: id.clone(),
-                        name: name.clone(),
-                        before: *after,
-                        after: *before,
-                    });
+                    inverse.push((
+                        app_id,
+                        Change::IntStat {
+                            id: id.clone(),
+                            name: name.clone(),
+                            before: *after,
+                            after: *before,
+                        },
+                    ));
                 }
                 Change::FloatStat {
                     id,
@@ -654,93 +690,134 @@ impl JournalSection {
                         value: AppStatValue::Float(*before),
                         permission: 0,
                     });
-                    inverse.push(Change::FloatStat {
-                        id: id.clone(),
-                        name: name.clone(),
-                        before: *after,
-                        after: *before,
-                    });
+                    inverse.push((
+                        app_id,
+                        Change::FloatStat {
+                            id: id.clone(),
+                            name: name.clone(),
+                            before: *after,
+                            after: *before,
+                        },
+                    ));
+                }
+                Change::Snapshot { before, .. } => {
+                    export
+                        .achievements
+                        .extend(before.achievements.iter().cloned());
+                    export.stats.extend(before.stats.iter().cloned());
+                    snapshots.push(app_id);
                 }
-                Change::Opaque { .. } => {}
+                Change::Opaque { .. } | Change::Granted { .. } => {}
             }
         }
+        let several = exports.len() > 1;
+        let labels: HashMap<u32, String> = exports
+            .iter()
+            .map(|e| (e.app_id, e.app_name.clone()))
+            .collect();
 
-        let app_id = operation.app_id;
+        let policy = fan_out_policy();
         let Ok(result) = spawn_blocking(move || {
-            (ImportApps { apps: vec![export] }).request_with_progress(|_, _| {})
+            let request = ImportApps {
+                apps: exports,
+                policy,
+            };
+            request.request_with_progress(|_, _| {})
         })
         .await
         else {
             return;
         };
 
+        // Problems name their game only when there is more than one.
+        let in_app = |app_id: u32, problem: &str| match labels.get(&app_id) {
+            Some(label) if several => format!("{label}: {problem}"),
+            _ => problem.to_string(),
+        };
         let mut problems: Vec<String> = Vec::new();
-        let mut applied = 0usize;
-        // A refusal Steam names is one this can work around: everything it did
-        // not name came back. A failure with no summary names nothing, so none
-        // of it may be filed as put back.
-        let mut unaccounted = false;
+        let mut applied: Vec<u32> = Vec::new();
+        // By app, what Steam refused and what the undo found there. A refusal
+        // Steam names is one this can work around: everything it did not name
+        // came back. An app missing here failed with no summary, which names
+        // nothing, so none of its changes may be filed as put back.
+        let mut answered: HashMap<u32, (Vec<String>, Option<AppExport>)> = HashMap::new();
         match result {
             Ok(results) => {
-                if results.is_empty() {
-                    unaccounted = true;
-                }
-                for (_, res) in results {
+                for (app_id, res) in results {
                     match res {
-                        Ok(summary) => {
-                            applied += summary.achievements_applied + summary.stats_applied;
+                        Ok(kept) => {
+                            let summary = kept.result;
+                            if summary.achievements_applied + summary.stats_applied > 0 {
+                                applied.push(app_id);
+                            }
                             // Steam holds every set until the store commits,
                             // so a failed store took the whole app with it.
-                            if summary.errors.iter().any(|e| e.starts_with("store failed")) {
-                                unaccounted = true;
+                            let store_failed =
+                                summary.errors.iter().any(|e| e.starts_with("store failed"));
+                            let mut refusals = summary.errors;
+                            refusals.extend(summary.skipped_protected);
+                            refusals.extend(summary.skipped_unwriteable);
+                            problems.extend(refusals.iter().map(|p| in_app(app_id, p)));
+                            if !store_failed {
+                                answered.insert(app_id, (refusals, kept.before));
                             }
-                            problems.extend(summary.errors);
-                            problems.extend(summary.skipped_protected);
-                            problems.extend(summary.skipped_unwriteable);
-                        }
-                        Err(e) => {
-                            problems.push(e.to_string());
-                            unaccounted = true;
                         }
+                        Err(e) => problems.push(in_app(app_id, &e.to_string())),
                     }
                 }
             }
-            Err(e) => {
-                problems.push(e.to_string());
-                unaccounted = true;
-            }
+            Err(e) => problems.push(e.to_string()),
         }
 
         // Lifted out of the cell first: a handler reaching back into
         // `connect_undone` would panic on the borrow.
         let on_undone = self.on_undone.borrow().clone();
-        if applied > 0
+        if !applied.is_empty()
             && let Some(on_undone) = on_undone
         {
-            on_undone(&[app_id]);
+            on_undone(&applied);
         }
 
         // Filed change by change: recording a half-refused batch whole claims
         // values still at zero were restored, and recording none of it leaves a
         // row whose every change is back still asking to be undone.
-        let put_back: Vec<Change> = if unaccounted {
-            Vec::new()
-        } else {
-            inverse
-                .into_iter()
-                .filter(|change| !refused(&problems, change))
-                .collect()
-        };
+        let mut put_back: Vec<(u32, String, Change)> = inverse
+            .into_iter()
+            .filter(|(app_id, change)| {
+                answered
+                    .get(app_id)
+                    .is_some_and(|(refusals, _)| !refused(refusals, change))
+            })
+            .map(|(app_id, change)| (app_id, labels[&app_id].clone(), change))
+            .collect();
+        // A snapshot goes back whole or not at all. Filed even when the undo
+        // could not read the app first, with nothing to write back: left out,
+        // the row it undoes would go on offering to be undone.
+        for app_id in snapshots {
+            if let Some((_, found)) = answered.get_mut(&app_id) {
+                let before = found.take().unwrap_or_else(|| AppExport {
+                    app_id,
+                    app_name: String::new(),
+                    achievements: Vec::new(),
+                    stats: Vec::new(),
+                });
+                let change = Change::Snapshot {
+                    detail: tr_noop("progress put back").to_string(),
+                    before,
+                };
+                put_back.push((app_id, labels[&app_id].clone(), change));
+            }
+        }
         let put_back_count = put_back.len();
         if !put_back.is_empty() {
             Batch::reversing(
-                app_id,
-                app_name,
+                operation.app_id,
+                operation.app_name.clone(),
                 Reverses {
                     batch: operation.batch,
                 },
             )
-            .record(put_back);
+            .record_per_app(put_back);
         }
 
         // The clicked button goes away with its row. Left holding the focus,
@@ -785,7 +862,9 @@ fn refused(problems: &[String], change: &Change) -> bool {
     let head = match change {
         Change::Achievement { id, .. } => format!("ach:{id}"),
         Change::IntStat { id, .. } | Change::FloatStat { id, .. } => format!("stat:{id}"),
-        Change::Opaque { .. } => return true,
+        Change::Opaque { .. } | Change::Snapshot { .. } | Change::Granted { .. } => {
+            return true;
+        }
     };
     let with_reason = format!("{head} ");
     problems
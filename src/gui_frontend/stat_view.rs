@@ -283,6 +283,7 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                             app_id,
                             stat_id,
                             value: value as i32,
+                            store: true,
                         }
                         .request()
                     } else {
@@ -290,24 +291,23 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                             app_id,
                             stat_id,
                             value: value as f32,
+                            store: true,
                         }
                         .request()
                     };
 
                     match res {
-                        Ok(success) if success => (true, value),
-                        _ => (false, value),
+                        Ok(stored) if stored.result => (Some(stored.granted), value),
+                        _ => (None, value),
                     }
                 });
 
-                let (success, debounced_value) =
+                let (stored, debounced_value) =
                     join_handle.await.expect("spawn_blocking task panicked");
 
-                if success {
+                if let Some(granted) = stored {
                     let before = stat_object_clone.original_value();
-                    Batch::new(Op::StatEdit, app_id, "").record(vec![if stat_object_clone
-                        .is_integer()
-                    {
+                    let edit = if stat_object_clone.is_integer() {
                         Change::IntStat {
                             id: stat_object_clone.id(),
                             name: stat_object_clone.display_name(),
@@ -321,7 +321,14 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                             before: before as f32,
                             after: debounced_value as f32,
                         }
-                    }]);
+                    };
+                    // Raising a stat is the usual way Steam comes to unlock
+                    // something on its own.
+                    Batch::new(Op::StatEdit, app_id, "").record(
+                        std::iter::once(edit)
+                            .chain(Change::granted(granted))
+                            .collect(),
+                    );
                     stat_object_clone.set_original_value(debounced_value);
                 } else {
                     stat_object_clone.set_current_value(stat_object_clone.original_value());
@@ -161,8 +161,13 @@ pub fn create_context_menu_button() -> (
         .max_width_chars(20)
         .ellipsize(gtk::pango::EllipsizeMode::Middle)
         .build();
+    let popover_loading_cancel = gtk::Button::builder()
+        .label(tr("Cancel").as_str())
+        .action_name("app.cancel_bulk_operation")
+        .build();
     popover_loading_box.append(&popover_loading_progress_label);
     popover_loading_box.append(&popover_loading_info_label);
+    popover_loading_box.append(&popover_loading_cancel);
     let popover_loading = Popover::builder().child(&popover_loading_box).build();
 
     menu_button.set_popover(Some(&popover));
@@ -210,6 +215,18 @@ fn setup_app_list_popover_menu(menu_model: &gtk::gio::Menu, with_bulk: bool) {
         Some(tr("Import progress...").as_str()),
         Some("app.import_progress"),
     );
+    bulk_process_section.append(
+        Some(tr("Last bulk report…").as_str()),
+        Some("app.show_bulk_report"),
+    );
+    bulk_process_section.append(
+        Some(tr("Scheduled operations…").as_str()),
+        Some("app.show_schedule"),
+    );
+    bulk_process_section.append(
+        Some(tr("Bulk action settings…").as_str()),
+        Some("app.show_bulk_settings"),
+    );
 
     menu_model.append(
         Some(tr("Refresh app list").as_str()),
@@ -219,6 +236,7 @@ fn setup_app_list_popover_menu(menu_model: &gtk::gio::Menu, with_bulk: bool) {
         Some(tr("Refresh achievement counts").as_str()),
         Some("app.rescan_achievement_counts"),
     );
+    menu_model.append(Some(tr("Diagnostics").as_str()), Some("app.diagnostics"));
     menu_model.append(Some(tr("About").as_str()), Some("app.about"));
     #[cfg(unix)]
     if crate::utils::snap::is_snap() {
@@ -403,6 +421,10 @@ pub fn set_context_popover_to_app_details_context(
         Some(tr("Reset everything").as_str()),
         Some("app.clear_all_stats_and_achievements"),
     );
+    menu_model.append(
+        Some(tr("Scheduled operations…").as_str()),
+        Some("app.show_schedule"),
+    );
     menu_model.append(Some(tr("About").as_str()), Some("app.about"));
 
     ACHIEVEMENT_LANGUAGES_FROM_FETCH.with(|f| f.set(false));
@@ -149,19 +149,24 @@ pub async fn run_timed_unlock(
             })
             .await;
 
-            // Only `Ok(Ok(true))` is Steam saying it stored the thing.
+            // Only a stored `true` is Steam saying it stored the thing.
             match result {
-                Ok(Ok(true)) => {
-                    batch.record(vec![Change::Achievement {
+                Ok(Ok(stored)) if stored.result => {
+                    let unlock = Change::Achievement {
                         id: achievement.id(),
                         name: achievement.name(),
                         before: false,
                         after: true,
-                    }]);
+                    };
+                    batch.record(
+                        std::iter::once(unlock)
+                            .chain(Change::granted(stored.granted))
+                            .collect(),
+                    );
                 }
                 // Switched on before the request went out, so a refusal has to
                 // switch it back.
-                Ok(Ok(false)) => {
+                Ok(Ok(_)) => {
                     eprintln!("[CLIENT] Steam did not store {}", achievement.id());
                     achievement.set_is_achieved(false);
                 }
@@ -209,21 +214,21 @@ pub fn unlock_all_immediately(app_id: u32, achievements: &[GAchievementObject])
         }
         .request();
         match res {
-            Ok(true) => unlocked.push(Change::Achievement {
+            Ok(set) if set.result => unlocked.push(Change::Achievement {
                 id: ach.id(),
                 name: ach.name(),
                 before: false,
                 after: true,
             }),
-            Ok(false) => eprintln!("[CLIENT] Steam refused achievement {}", ach.id()),
+            Ok(_) => eprintln!("[CLIENT] Steam refused achievement {}", ach.id()),
             Err(e) => eprintln!("[CLIENT] Failed to set achievement: {e:?}"),
         }
     }
 
     // Nothing above was committed until this lands.
     match (StoreStatsAndAchievements { app_id }).request() {
-        Ok(true) => {}
-        Ok(false) => {
+        Ok(stored) if stored.result => unlocked.extend(Change::granted(stored.granted)),
+        Ok(_) => {
             eprintln!("[CLIENT] Steam did not store the unlocks");
             return;
         }
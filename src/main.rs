@@ -23,8 +23,13 @@ compile_error!(
     "features `cli` and `gui` are mutually exclusive; build the CLI with `--no-default-features --features cli`"
 );
 
-#[cfg(not(any(feature = "cli", feature = "gui")))]
-compile_error!("either the `cli` or `gui` feature must be enabled");
+#[cfg(all(feature = "tui", any(feature = "cli", feature = "gui")))]
+compile_error!(
+    "feature `tui` excludes `cli` and `gui`; build the terminal UI with `--no-default-features --features tui`"
+);
+
+#[cfg(not(any(feature = "cli", feature = "gui", feature = "tui")))]
+compile_error!("one of the `cli`, `gui` or `tui` features must be enabled");
 
 mod backend;
 #[cfg(feature = "cli")]
@@ -32,12 +37,14 @@ mod cli_frontend;
 #[cfg(feature = "gui")]
 mod gui_frontend;
 mod steam_client;
+#[cfg(feature = "tui")]
+mod tui_frontend;
 mod utils;
 
 #[cfg(feature = "gui")]
 const APP_ID: &str = "org.samrewritten.SamRewritten";
 
-#[cfg(feature = "cli")]
+#[cfg(any(feature = "cli", feature = "tui"))]
 fn main() -> std::process::ExitCode {
     use crate::backend::app::app;
     use crate::backend::orchestrator::orchestrator;
@@ -45,7 +52,7 @@ fn main() -> std::process::ExitCode {
 
     let arguments = parse_cli_arguments();
 
-    // Orchestrator mode: the single Steam-owning process the CLI frontend spawns
+    // Orchestrator mode: the single Steam-owning process the frontend spawns
     // and drives over IPC. It also owns every app-server child (so they share
     // its namespace).
     if arguments.is_orchestrator {
@@ -59,6 +66,12 @@ fn main() -> std::process::ExitCode {
         return std::process::ExitCode::from(exit_code);
     }
 
+    // Daemon mode: an orchestrator that outlives its frontends, on a socket.
+    #[cfg(target_os = "linux")]
+    if arguments.is_daemon {
+        return std::process::ExitCode::from(crate::backend::daemon::daemon());
+    }
+
     // App-server mode: a per-app child spawned by the orchestrator.
     if arguments.is_app > 0 {
         let mut tx = arguments.tx.unwrap();
@@ -67,7 +80,11 @@ fn main() -> std::process::ExitCode {
         return std::process::ExitCode::from(exit_code);
     }
 
-    cli_frontend::main()
+    #[cfg(feature = "cli")]
+    let frontend = cli_frontend::main;
+    #[cfg(feature = "tui")]
+    let frontend = tui_frontend::main;
+    frontend()
 }
 
 #[cfg(feature = "gui")]
@@ -89,6 +106,11 @@ fn main() -> gtk::glib::ExitCode {
         return gtk::glib::ExitCode::from(exit_code);
     }
 
+    #[cfg(target_os = "linux")]
+    if arguments.is_daemon {
+        return gtk::glib::ExitCode::from(crate::backend::daemon::daemon());
+    }
+
     if arguments.is_app > 0 {
         let mut tx = arguments.tx.unwrap();
         let mut rx = arguments.rx.unwrap();
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! One game's achievements and stats. Edits are staged here and only reach
+//! Steam on `s`, as a single store, the way the GUI's manual mode works.
+
+use crate::backend::app_manager::StatState;
+use crate::backend::orchestrator_client::AppProgress;
+use crate::backend::progress_io::{WriteDecision, classify_stat};
+use crate::backend::stat_definitions::{AchievementInfo, StatInfo};
+use ratatui::widgets::TableState;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pane {
+    Achievements,
+    Stats,
+}
+
+/// Everything staged, ready to send.
+pub struct Changes {
+    pub achievements: Vec<(String, bool)>,
+    pub stats: Vec<StatInfo>,
+}
+
+pub struct Game {
+    pub app_id: u32,
+    pub name: String,
+    /// `None` until the app server answers.
+    pub progress: Option<AppProgress>,
+    /// Achievement id to the state it will be stored as. Stats are staged in
+    /// place, as their `original_value` already remembers what Steam has.
+    pub staged: BTreeMap<String, bool>,
+    pub pane: Pane,
+    pub achievements: TableState,
+    pub stats: TableState,
+}
+
+impl Game {
+    pub fn new(app_id: u32, name: String) -> Self {
+        Self {
+            app_id,
+            name,
+            progress: None,
+            staged: BTreeMap::new(),
+            pane: Pane::Achievements,
+            achievements: TableState::default(),
+            stats: TableState::default(),
+        }
+    }
+
+    /// A fresh fetch replaces everything, staged edits included.
+    pub fn set_progress(&mut self, progress: AppProgress) {
+        let first = |len: usize| (len > 0).then_some(0);
+        let clamp =
+            |state: &TableState, len: usize| state.selected().map(|i| i.min(len.saturating_sub(1)));
+        let achievements = progress.achievements.len();
+        let stats = progress.stats.len();
+        self.achievements
+            .select(clamp(&self.achievements, achievements).or(first(achievements)));
+        self.stats
+            .select(clamp(&self.stats, stats).or(first(stats)));
+        self.staged.clear();
+        self.progress = Some(progress);
+    }
+
+    pub fn is_unlocked(&self, achievement: &AchievementInfo) -> bool {
+        self.staged
+            .get(&achievement.id)
+            .copied()
+            .unwrap_or(achievement.is_achieved)
+    }
+
+    /// `(unlocked, total)` as they will be once stored.
+    pub fn counts(&self) -> (u32, u32) {
+        let Some(progress) = &self.progress else {
+            return (0, 0);
+        };
+        let unlocked = progress
+            .achievements
+            .iter()
+            .filter(|a| self.is_unlocked(a))
+            .count();
+        (unlocked as u32, progress.achievements.len() as u32)
+    }
+
+    pub fn pending(&self) -> usize {
+        let stats = self.progress.as_ref().map_or(0, |progress| {
+            progress.stats.iter().filter(|s| s.is_modified()).count()
+        });
+        self.staged.len() + stats
+    }
+
+    pub fn selected_achievement(&self) -> Option<&AchievementInfo> {
+        let progress = self.progress.as_ref()?;
+        progress.achievements.get(self.achievements.selected()?)
+    }
+
+    pub fn selected_stat(&self) -> Option<&StatInfo> {
+        let progress = self.progress.as_ref()?;
+        progress.stats.get(self.stats.selected()?)
+    }
+
+    /// Flip the selected achievement; flipping it back unstages it.
+    pub fn toggle(&mut self) -> Result<(), String> {
+        let Some(achievement) = self.selected_achievement() else {
+            return Ok(());
+        };
+        if achievement.permission != 0 {
+            return Err(format!(
+                "{} is protected by Steam and cannot be changed",
+                achievement.name
+            ));
+        }
+        let (id, achieved) = (achievement.id.clone(), achievement.is_achieved);
+        if self.staged.remove(&id).is_none() {
+            self.staged.insert(id, !achieved);
+        }
+        Ok(())
+    }
+
+    /// Stage every unprotected achievement towards `unlocked`, returning how
+    /// many that changed.
+    pub fn stage_all(&mut self, unlocked: bool) -> usize {
+        let Some(progress) = &self.progress else {
+            return 0;
+        };
+        let mut changed = 0;
+        for achievement in &progress.achievements {
+            if achievement.permission != 0 || self.is_unlocked(achievement) == unlocked {
+                continue;
+            }
+            if achievement.is_achieved == unlocked {
+                self.staged.remove(&achievement.id);
+            } else {
+                self.staged.insert(achievement.id.clone(), unlocked);
+            }
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Stage `text` as the selected stat's new value, checked against its
+    /// schema as an import would be.
+    pub fn set_stat(&mut self, text: &str) -> Result<(), String> {
+        let Some(stat) = self
+            .stats
+            .selected()
+            .and_then(|i| self.progress.as_mut()?.stats.get_mut(i))
+        else {
+            return Ok(());
+        };
+        if (stat.permission() & 2) != 0 {
+            return Err(format!(
+                "{} is protected by Steam and cannot be changed",
+                stat.display_name()
+            ));
+        }
+        let staged = match stat {
+            StatInfo::Integer(i) => {
+                let value = checked(
+                    text,
+                    StatState {
+                        min: i.min_value,
+                        max: i.max_value,
+                        increment_only: i.is_increment_only,
+                        default: i.default_value,
+                        current: Some(i.original_value),
+                    },
+                )?;
+                i.set_value(value)
+            }
+            StatInfo::Float(f) => {
+                let value = checked(
+                    text,
+                    StatState {
+                        min: f.min_value,
+                        max: f.max_value,
+                        increment_only: f.is_increment_only,
+                        default: f.default_value,
+                        current: Some(f.original_value),
+                    },
+                )?;
+                f.set_value(value)
+            }
+        };
+        staged.map_err(|e| e.to_string())
+    }
+
+    pub fn discard(&mut self) {
+        self.staged.clear();
+        if let Some(progress) = &mut self.progress {
+            for stat in &mut progress.stats {
+                // Always allowed: a protected stat can only hold its original.
+                let _ = match stat {
+                    StatInfo::Integer(i) => i.set_value(i.original_value),
+                    StatInfo::Float(f) => f.set_value(f.original_value),
+                };
+            }
+        }
+    }
+
+    pub fn changes(&self) -> Changes {
+        let stats = self.progress.as_ref().map_or_else(Vec::new, |progress| {
+            progress
+                .stats
+                .iter()
+                .filter(|s| s.is_modified())
+                .cloned()
+                .collect()
+        });
+        Changes {
+            achievements: self
+                .staged
+                .iter()
+                .map(|(id, unlocked)| (id.clone(), *unlocked))
+                .collect(),
+            stats,
+        }
+    }
+}
+
+fn checked<T: Copy + PartialOrd + FromStr + Display>(
+    text: &str,
+    state: StatState<T>,
+) -> Result<T, String> {
+    let value = text
+        .trim()
+        .parse::<T>()
+        .map_err(|_| format!("'{}' is not a valid value for this stat", text.trim()))?;
+    match classify_stat(value, &state) {
+        WriteDecision::Write => Ok(value),
+        WriteDecision::OutOfRangeHigh { max } => Err(format!("The maximum is {max}")),
+        WriteDecision::OutOfRangeLow { min } => Err(format!("The minimum is {min}")),
+        WriteDecision::IncrementOnlyResetFixable { current } => Err(format!(
+            "This stat only increases and is {current}; reset the game's stats to lower it"
+        )),
+        WriteDecision::IncrementOnlyHard { default } => Err(format!(
+            "This stat only increases and cannot go below its default of {default}"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::stat_definitions::IntStatInfo;
+
+    fn achievement(id: &str, is_achieved: bool, permission: i32) -> AchievementInfo {
+        AchievementInfo {
+            id: id.to_owned(),
+            is_achieved,
+            unlock_time: None,
+            permission,
+            icon_normal: String::new(),
+            icon_locked: String::new(),
+            name: id.to_owned(),
+            description: String::new(),
+            global_achieved_percent: None,
+            is_hidden: false,
+        }
+    }
+
+    fn game() -> Game {
+        let mut game = Game::new(480, "Spacewar".to_owned());
+        game.set_progress(AppProgress {
+            achievements: vec![
+                achievement("WIN", false, 0),
+                achievement("LOSE", true, 0),
+                achievement("SERVER", false, 1),
+            ],
+            stats: vec![StatInfo::Integer(IntStatInfo {
+                id: "Kills".to_owned(),
+                app_id: 480,
+                display_name: "Kills".to_owned(),
+                is_increment_only: true,
+                permission: 0,
+                original_value: 3,
+                int_value: 3,
+                min_value: 0,
+                max_value: 100,
+                default_value: 0,
+            })],
+            languages: vec![],
+        });
+        game
+    }
+
+    #[test]
+    fn staging_skips_protected_achievements_and_undoes_itself() {
+        let mut game = game();
+
+        assert!(game.toggle().is_ok());
+        assert_eq!(game.counts(), (2, 3));
+        assert!(game.toggle().is_ok());
+        assert_eq!(game.pending(), 0);
+
+        assert_eq!(game.stage_all(true), 1);
+        assert_eq!(game.changes().achievements, [("WIN".to_owned(), true)]);
+        assert_eq!(game.stage_all(false), 2);
+        assert_eq!(game.changes().achievements, [("LOSE".to_owned(), false)]);
+
+        game.achievements.select(Some(2));
+        assert!(game.toggle().is_err());
+
+        assert!(game.set_stat("2").is_err());
+        assert!(game.set_stat("200").is_err());
+        assert!(game.set_stat("7").is_ok());
+        assert_eq!(game.pending(), 2);
+        game.discard();
+        assert_eq!(game.pending(), 0);
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The library screen: every owned app, narrowed by the same filters and sort
+//! orders as the GUI sidebar.
+
+use crate::backend::app_lister::AppModel;
+use crate::backend::library_query::{AppFilter, AppSort, query_apps};
+use ratatui::widgets::TableState;
+
+/// In the order `o` cycles through them.
+pub const SORTS: [(AppSort, &str); 6] = [
+    (AppSort::AppId, "app id"),
+    (AppSort::Alphabetical, "name"),
+    (AppSort::LastPlayed, "last played"),
+    (AppSort::Playtime, "playtime"),
+    (AppSort::Completion, "completion"),
+    (AppSort::Remaining, "remaining"),
+];
+
+pub struct Library {
+    apps: Vec<AppModel>,
+    pub shown: Vec<AppModel>,
+    pub filter: AppFilter,
+    pub sort: AppSort,
+    pub search: String,
+    pub table: TableState,
+    /// Counts arrive in a second listing, after the names.
+    pub counted: bool,
+    pub loading: bool,
+}
+
+impl Library {
+    pub fn new() -> Self {
+        Self {
+            apps: Vec::new(),
+            shown: Vec::new(),
+            // As the GUI's defaults: junk hidden, nothing else.
+            filter: AppFilter {
+                junk: true,
+                ..AppFilter::default()
+            },
+            sort: AppSort::AppId,
+            search: String::new(),
+            table: TableState::default(),
+            counted: false,
+            loading: true,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.apps.len()
+    }
+
+    pub fn set_apps(&mut self, apps: Vec<AppModel>, counted: bool) {
+        self.apps = apps;
+        self.counted = counted;
+        self.loading = !counted;
+        self.refresh();
+    }
+
+    /// Re-run the query, keeping the selection on the same app when it is
+    /// still listed.
+    pub fn refresh(&mut self) {
+        let selected = self.selected().map(|app| app.app_id);
+        self.shown = query_apps(self.apps.clone(), &self.filter, &self.search, self.sort);
+        let index = selected
+            .and_then(|id| self.shown.iter().position(|app| app.app_id == id))
+            .unwrap_or(0);
+        self.table.select((!self.shown.is_empty()).then_some(index));
+    }
+
+    pub fn selected(&self) -> Option<&AppModel> {
+        self.table.selected().and_then(|i| self.shown.get(i))
+    }
+
+    /// Keys `1`-`5`, in the sidebar's order.
+    pub fn toggle_filter(&mut self, key: char) {
+        let filter = &mut self.filter;
+        let flag = match key {
+            '1' => &mut filter.junk,
+            '2' => &mut filter.hide_fully_unlocked,
+            '3' => &mut filter.hide_never_launched,
+            '4' => &mut filter.hide_no_unlocked,
+            '5' => &mut filter.hide_without_achievements,
+            _ => return,
+        };
+        *flag = !*flag;
+        self.refresh();
+    }
+
+    pub fn next_sort(&mut self) {
+        let index = SORTS.iter().position(|(sort, _)| *sort == self.sort);
+        self.sort = SORTS[index.map_or(0, |i| (i + 1) % SORTS.len())].0;
+        self.refresh();
+    }
+
+    pub fn sort_label(&self) -> &'static str {
+        SORTS
+            .iter()
+            .find(|(sort, _)| *sort == self.sort)
+            .map_or("", |(_, label)| label)
+    }
+
+    /// A filter that needs counts hides nothing honest until they are in.
+    pub fn waiting_on_counts(&self) -> bool {
+        !self.counted && (self.filter.depends_on_counts() || self.sort.needs_counts())
+    }
+
+    /// After a store, so the row matches the game screen without a relisting.
+    pub fn update_counts(&mut self, app_id: u32, total: u32, unlocked: u32) {
+        for app in self.apps.iter_mut().chain(self.shown.iter_mut()) {
+            if app.app_id == app_id {
+                app.achievement_count = Some(total);
+                app.unlocked_achievement_count = Some(unlocked);
+            }
+        }
+    }
+}
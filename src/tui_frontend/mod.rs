@@ -0,0 +1,544 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Full-screen terminal frontend. A thin IPC client like the CLI: it spawns
+//! one orchestrator and drives it through the `Request` trait, from a worker
+//! thread so the screen keeps answering while Steam does.
+
+mod game;
+mod library;
+mod ui;
+
+use crate::backend::app_lister::AppModel;
+use crate::backend::orchestrator_client::{
+    AppProgress, GetAchievementsAndStats, GetSubscribedAppList, Request, SetAchievement,
+    SetFloatStat, SetIntStat, StopApp, StoreStatsAndAchievements, attach_to_daemon,
+    respawn_orchestrator, set_orchestrator, shutdown_and_wait, subscribe_events,
+};
+use crate::backend::stat_definitions::StatInfo;
+use crate::utils::app_paths::{get_app_cache_dir, get_executable_path};
+use crate::utils::bidir_child::BidirChild;
+use crate::utils::dev_println;
+use crate::utils::ipc_client::IpcClient;
+use crate::utils::ipc_types::{GrantedAchievement, OrchestratorEvent, SamError};
+use game::{Changes, Game, Pane};
+use library::Library;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::TableState;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::time::Duration;
+
+/// Work for the worker thread, run in order: the orchestrator takes one
+/// request at a time anyway, and a `Stop` must not overtake the `Open` it ends.
+enum Job {
+    Library,
+    Open(u32),
+    Reload(u32),
+    Store(u32, Changes),
+    Stop(u32),
+    /// The orchestrator cannot reach Steam anymore; start another.
+    Respawn,
+}
+
+enum Loaded {
+    Apps(Result<Vec<AppModel>, SamError>, bool),
+    Progress(u32, Result<AppProgress, SamError>),
+    /// What Steam refused, by id, and what it unlocked by itself; or why
+    /// nothing was stored at all.
+    Stored(Result<(Vec<String>, Vec<GrantedAchievement>), SamError>),
+    Respawned(Result<(), SamError>),
+}
+
+/// A line being typed at the bottom of the screen.
+enum Prompt {
+    Search,
+    StatValue,
+    /// Leaving a game, or quitting, would drop its staged changes.
+    Discard {
+        quit: bool,
+    },
+}
+
+struct App {
+    library: Library,
+    game: Option<Game>,
+    prompt: Option<Prompt>,
+    input: String,
+    status: String,
+    /// What the open game is waiting on.
+    busy: Option<&'static str>,
+    jobs: Sender<Job>,
+    loaded: Receiver<Loaded>,
+    log: PathBuf,
+    quit: bool,
+}
+
+/// The backend's diagnostics would scribble over the screen, so the
+/// orchestrator and its children write them to `tui.log` instead. A daemon
+/// keeps its own output.
+fn spawn_orchestrator(log: &PathBuf) -> Result<(), SamError> {
+    if attach_to_daemon() {
+        return Ok(());
+    }
+    let file = File::create(log).map_err(|e| {
+        eprintln!("Failed to create {}: {e}", log.display());
+        SamError::UnknownError
+    })?;
+    let stderr = file.try_clone().map_err(|_| SamError::UnknownError)?;
+
+    let mut command = Command::new(get_executable_path());
+    command
+        .arg("--orchestrator")
+        .stdout(Stdio::from(file))
+        .stderr(Stdio::from(stderr));
+    let child = BidirChild::new(&mut command)?;
+    set_orchestrator(IpcClient::new(child));
+    Ok(())
+}
+
+pub fn main() -> ExitCode {
+    let log = get_app_cache_dir().join("tui.log");
+    if let Err(e) = spawn_orchestrator(&log) {
+        eprintln!("Failed to start the backend process: {e}");
+        return ExitCode::FAILURE;
+    }
+    dev_println::mute();
+
+    let result = ratatui::run(|terminal| App::new(log).run(terminal));
+    shutdown_and_wait();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Terminal error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Queue a `Respawn` should the orchestrator be stranded by a Flatpak Steam
+/// started after it. Ends with the orchestrator it follows.
+fn watch_for_stranding(jobs: Sender<Job>) {
+    thread::spawn(move || {
+        for event in subscribe_events(false) {
+            if event == OrchestratorEvent::Stranded && jobs.send(Job::Respawn).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+fn work(jobs: Receiver<Job>, loaded: Sender<Loaded>, log: PathBuf) {
+    let progress = |app_id, launch| {
+        GetAchievementsAndStats {
+            app_id,
+            launch,
+            // The game's own language, as the GUI's default.
+            language: String::new(),
+        }
+        .request()
+    };
+
+    for job in jobs {
+        let message = match job {
+            Job::Library => {
+                // Names first, so there is something to browse while the counts load.
+                for with_achievement_counts in [false, true] {
+                    let apps = GetSubscribedAppList {
+                        include_playtime: true,
+                        with_achievement_counts,
+                    }
+                    .request();
+                    let failed = apps.is_err();
+                    let _ = loaded.send(Loaded::Apps(apps, with_achievement_counts));
+                    if failed {
+                        break;
+                    }
+                }
+                continue;
+            }
+            Job::Open(app_id) => Loaded::Progress(app_id, progress(app_id, true)),
+            Job::Reload(app_id) => Loaded::Progress(app_id, progress(app_id, false)),
+            Job::Store(app_id, changes) => {
+                let stored = store(app_id, changes);
+                let _ = loaded.send(Loaded::Stored(stored));
+                Loaded::Progress(app_id, progress(app_id, false))
+            }
+            Job::Stop(app_id) => {
+                let _ = StopApp { app_id }.request();
+                continue;
+            }
+            Job::Respawn => Loaded::Respawned(respawn_orchestrator(|| spawn_orchestrator(&log))),
+        };
+        if loaded.send(message).is_err() {
+            break;
+        }
+    }
+}
+
+/// Every change is sent unstored, then saved by one store, so Steam shows a
+/// single sync for the lot.
+fn store(
+    app_id: u32,
+    changes: Changes,
+) -> Result<(Vec<String>, Vec<GrantedAchievement>), SamError> {
+    let mut refused = Vec::new();
+    for (achievement_id, unlocked) in changes.achievements {
+        let set = SetAchievement {
+            app_id,
+            achievement_id: achievement_id.clone(),
+            unlocked,
+            store: false,
+        }
+        .request();
+        if !set.is_ok_and(|set| set.result) {
+            refused.push(achievement_id);
+        }
+    }
+    for stat in changes.stats {
+        let set = match &stat {
+            StatInfo::Integer(i) => SetIntStat {
+                app_id,
+                stat_id: i.id.clone(),
+                value: i.int_value,
+                store: false,
+            }
+            .request(),
+            StatInfo::Float(f) => SetFloatStat {
+                app_id,
+                stat_id: f.id.clone(),
+                value: f.float_value,
+                store: false,
+            }
+            .request(),
+        };
+        if !set.is_ok_and(|set| set.result) {
+            refused.push(stat.id().to_owned());
+        }
+    }
+    let stored = (StoreStatsAndAchievements { app_id }).request()?;
+    match stored.result {
+        true => Ok((refused, stored.granted)),
+        false => Err(SamError::UnknownError),
+    }
+}
+
+/// Move a table selection by `delta` rows, clamped to the table.
+fn step(table: &mut TableState, len: usize, delta: isize) {
+    if len == 0 {
+        table.select(None);
+        return;
+    }
+    let current = table.selected().unwrap_or(0) as isize;
+    table.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+}
+
+impl App {
+    fn new(log: PathBuf) -> Self {
+        let (jobs, queue) = channel();
+        let (results, loaded) = channel();
+        let worker_log = log.clone();
+        thread::spawn(move || work(queue, results, worker_log));
+        let _ = jobs.send(Job::Library);
+        watch_for_stranding(jobs.clone());
+
+        Self {
+            library: Library::new(),
+            game: None,
+            prompt: None,
+            input: String::new(),
+            status: String::new(),
+            busy: None,
+            jobs,
+            loaded,
+            log,
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| ui::draw(frame, &mut self))?;
+            while let Ok(loaded) = self.loaded.try_recv() {
+                self.on_loaded(loaded);
+            }
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if !key.is_press() {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                match key.code {
+                    KeyCode::Char('c') => self.quit = true,
+                    // Stray output from a dying backend can still reach the screen.
+                    KeyCode::Char('l') => terminal.clear()?,
+                    _ => {}
+                }
+                continue;
+            }
+            self.on_key(key);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, job: Job, busy: &'static str) {
+        self.busy = Some(busy);
+        let _ = self.jobs.send(job);
+    }
+
+    fn failed(&mut self, what: &str, e: SamError) {
+        self.status = format!("{what} ({e}; details in {})", self.log.display());
+    }
+
+    fn on_loaded(&mut self, loaded: Loaded) {
+        match loaded {
+            Loaded::Apps(Ok(apps), counted) => self.library.set_apps(apps, counted),
+            Loaded::Apps(Err(e), _) => {
+                self.library.loading = false;
+                self.failed("Failed to list your apps. Is Steam running?", e);
+            }
+            Loaded::Respawned(Ok(())) => {
+                self.status = "Restarted the backend to reach Flatpak Steam.".to_owned();
+                self.library.loading = true;
+                let _ = self.jobs.send(Job::Library);
+                watch_for_stranding(self.jobs.clone());
+            }
+            Loaded::Respawned(Err(e)) => self.failed(
+                "Flatpak Steam started after the backend, which failed to restart",
+                e,
+            ),
+            Loaded::Progress(app_id, progress) => {
+                // A game left before it finished loading.
+                let Some(game) = self.game.as_mut().filter(|g| g.app_id == app_id) else {
+                    return;
+                };
+                self.busy = None;
+                match progress {
+                    Ok(progress) => {
+                        game.set_progress(progress);
+                        let (unlocked, total) = game.counts();
+                        self.library.update_counts(app_id, total, unlocked);
+                    }
+                    Err(e) => self.failed("Failed to load the game", e),
+                }
+            }
+            Loaded::Stored(Ok((refused, granted))) => {
+                self.status = if refused.is_empty() {
+                    "Stored.".to_owned()
+                } else {
+                    format!("Stored, but Steam refused: {}", refused.join(", "))
+                };
+                if !granted.is_empty() {
+                    let names: Vec<&str> = granted.iter().map(|a| a.name.as_str()).collect();
+                    self.status += &format!(" Steam also unlocked: {}", names.join(", "));
+                }
+            }
+            Loaded::Stored(Err(e)) => self.failed("Failed to store", e),
+        }
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        if let Some(prompt) = self.prompt.take() {
+            self.on_prompt_key(prompt, key);
+            return;
+        }
+        self.status.clear();
+        match self.game.is_some() {
+            true => self.on_game_key(key),
+            false => self.on_library_key(key),
+        }
+    }
+
+    fn on_prompt_key(&mut self, prompt: Prompt, key: KeyEvent) {
+        match prompt {
+            Prompt::Discard { quit } => {
+                if matches!(key.code, KeyCode::Char('y' | 'Y')) {
+                    self.leave_game();
+                    self.quit = quit;
+                }
+            }
+            Prompt::Search => match key.code {
+                KeyCode::Esc => {
+                    self.library.search.clear();
+                    self.library.refresh();
+                }
+                KeyCode::Enter => {}
+                KeyCode::Backspace => {
+                    self.library.search.pop();
+                    self.library.refresh();
+                    self.prompt = Some(prompt);
+                }
+                KeyCode::Char(c) => {
+                    self.library.search.push(c);
+                    self.library.refresh();
+                    self.prompt = Some(prompt);
+                }
+                // Arrows still move through the narrowed list.
+                _ => {
+                    self.on_library_key(key);
+                    self.prompt = Some(prompt);
+                }
+            },
+            Prompt::StatValue => match key.code {
+                KeyCode::Esc => self.input.clear(),
+                KeyCode::Enter => {
+                    let text = std::mem::take(&mut self.input);
+                    if let Some(game) = &mut self.game
+                        && let Err(e) = game.set_stat(&text)
+                    {
+                        self.status = e;
+                    }
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                    self.prompt = Some(prompt);
+                }
+                KeyCode::Char(c) => {
+                    self.input.push(c);
+                    self.prompt = Some(prompt);
+                }
+                _ => self.prompt = Some(prompt),
+            },
+        }
+    }
+
+    fn on_library_key(&mut self, key: KeyEvent) {
+        let len = self.library.shown.len();
+        let table = &mut self.library.table;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => step(table, len, 1),
+            KeyCode::Up | KeyCode::Char('k') => step(table, len, -1),
+            KeyCode::PageDown => step(table, len, 20),
+            KeyCode::PageUp => step(table, len, -20),
+            KeyCode::Home => step(table, len, -(len as isize)),
+            KeyCode::End => step(table, len, len as isize),
+            KeyCode::Char('/') => self.prompt = Some(Prompt::Search),
+            KeyCode::Char(c @ '1'..='5') => self.library.toggle_filter(c),
+            KeyCode::Char('o') => self.library.next_sort(),
+            KeyCode::Char('r') => {
+                self.library.loading = true;
+                let _ = self.jobs.send(Job::Library);
+            }
+            KeyCode::Enter => {
+                if let Some(app) = self.library.selected() {
+                    let game = Game::new(app.app_id, app.app_name.clone());
+                    let app_id = game.app_id;
+                    self.game = Some(game);
+                    self.send(Job::Open(app_id), "Launching the game…");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_game_key(&mut self, key: KeyEvent) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        let (table, len) = match (game.pane, &game.progress) {
+            (Pane::Achievements, progress) => (
+                &mut game.achievements,
+                progress.as_ref().map_or(0, |p| p.achievements.len()),
+            ),
+            (Pane::Stats, progress) => (
+                &mut game.stats,
+                progress.as_ref().map_or(0, |p| p.stats.len()),
+            ),
+        };
+        match key.code {
+            KeyCode::Char('q') => self.leave_or_ask(true),
+            KeyCode::Esc | KeyCode::Backspace => self.leave_or_ask(false),
+            KeyCode::Down | KeyCode::Char('j') => step(table, len, 1),
+            KeyCode::Up | KeyCode::Char('k') => step(table, len, -1),
+            KeyCode::PageDown => step(table, len, 20),
+            KeyCode::PageUp => step(table, len, -20),
+            KeyCode::Home => step(table, len, -(len as isize)),
+            KeyCode::End => step(table, len, len as isize),
+            KeyCode::Tab | KeyCode::BackTab => {
+                game.pane = match game.pane {
+                    Pane::Achievements => Pane::Stats,
+                    Pane::Stats => Pane::Achievements,
+                };
+            }
+            KeyCode::Char(' ') if game.pane == Pane::Achievements => {
+                if let Err(e) = game.toggle() {
+                    self.status = e;
+                }
+            }
+            KeyCode::Enter if game.pane == Pane::Achievements => {
+                if let Err(e) = game.toggle() {
+                    self.status = e;
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(stat) = game.selected_stat() {
+                    self.input = match stat {
+                        StatInfo::Integer(i) => i.int_value.to_string(),
+                        StatInfo::Float(f) => f.float_value.to_string(),
+                    };
+                    self.prompt = Some(Prompt::StatValue);
+                }
+            }
+            KeyCode::Char(c @ ('a' | 'l')) => {
+                let changed = game.stage_all(c == 'a');
+                self.status = format!("{changed} achievements staged");
+            }
+            KeyCode::Char('u') => game.discard(),
+            KeyCode::Char('s') => {
+                if game.pending() == 0 {
+                    self.status = "Nothing to store".to_owned();
+                } else {
+                    let job = Job::Store(game.app_id, game.changes());
+                    self.send(job, "Storing…");
+                }
+            }
+            KeyCode::Char('r') => {
+                if game.pending() > 0 {
+                    self.status = "Store (s) or undo (u) your changes first".to_owned();
+                } else {
+                    let job = Job::Reload(game.app_id);
+                    self.send(job, "Reloading…");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn leave_or_ask(&mut self, quit: bool) {
+        if self.game.as_ref().is_some_and(|game| game.pending() > 0) {
+            self.prompt = Some(Prompt::Discard { quit });
+        } else {
+            self.leave_game();
+            self.quit = quit;
+        }
+    }
+
+    fn leave_game(&mut self) {
+        if let Some(game) = self.game.take() {
+            let _ = self.jobs.send(Job::Stop(game.app_id));
+            self.busy = None;
+        }
+    }
+}
@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::game::{Game, Pane};
+use super::library::Library;
+use super::{App, Prompt};
+use crate::backend::stat_definitions::StatInfo;
+use crate::utils::format::{format_achievement_progress, format_playtime_minutes};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, Tabs};
+
+const LIBRARY_KEYS: &str =
+    "↑↓ move · Enter open · / search · 1-5 filters · o sort · r refresh · q quit";
+const ACHIEVEMENT_KEYS: &str =
+    "Space toggle · a unlock all · l lock all · u undo · s store · Tab stats · r reload · Esc back";
+const STAT_KEYS: &str = "Enter edit · u undo · s store · Tab achievements · r reload · Esc back";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [body, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+
+    let keys = match &mut app.game {
+        Some(game) => {
+            draw_game(frame, body, game, app.busy);
+            match game.pane {
+                Pane::Achievements => ACHIEVEMENT_KEYS,
+                Pane::Stats => STAT_KEYS,
+            }
+        }
+        None => {
+            draw_library(frame, body, &mut app.library);
+            LIBRARY_KEYS
+        }
+    };
+
+    let first = match &app.prompt {
+        Some(Prompt::Search) => Line::from(vec![
+            "Search: ".bold(),
+            Span::raw(app.library.search.as_str()),
+            "▏".into(),
+        ]),
+        Some(Prompt::StatValue) => Line::from(vec![
+            "New value: ".bold(),
+            Span::raw(app.input.as_str()),
+            "▏".into(),
+        ]),
+        Some(Prompt::Discard { .. }) => {
+            Line::from("Discard your unstored changes? (y/n)".bold().yellow())
+        }
+        None => Line::from(app.status.as_str().yellow()),
+    };
+    let hint = match app.prompt {
+        Some(Prompt::Search) => "Enter keep · Esc clear",
+        Some(Prompt::StatValue) => "Enter stage · Esc cancel",
+        _ => keys,
+    };
+    frame.render_widget(Paragraph::new(vec![first, Line::from(hint.dim())]), footer);
+}
+
+fn draw_library(frame: &mut Frame, area: Rect, library: &mut Library) {
+    let filter = &library.filter;
+    let flags = [
+        (filter.junk, "1 junk"),
+        (filter.hide_fully_unlocked, "2 complete"),
+        (filter.hide_never_launched, "3 unplayed"),
+        (filter.hide_no_unlocked, "4 no unlocks"),
+        (filter.hide_without_achievements, "5 no achievements"),
+    ];
+    let mut title = vec![
+        format!(" {} of {} apps ", library.shown.len(), library.total()).bold(),
+        format!("· sort: {} · hide:", library.sort_label()).into(),
+    ];
+    for (on, label) in flags {
+        title.push(match on {
+            true => format!(" {label}").cyan().bold(),
+            false => format!(" {label}").dim(),
+        });
+    }
+    title.push(" ".into());
+    if !library.search.is_empty() {
+        title.push(format!("· \"{}\" ", library.search).into());
+    }
+
+    let status = if library.loading && library.total() == 0 {
+        Some("Loading your library…")
+    } else if library.loading {
+        Some("Counting achievements…")
+    } else if library.waiting_on_counts() {
+        Some("Achievement counts are not loaded; press r")
+    } else {
+        None
+    };
+    let mut block = Block::bordered().title(Line::from(title));
+    if let Some(status) = status {
+        block = block.title_bottom(Line::from(format!(" {status} ").italic()));
+    }
+
+    let rows = library.shown.iter().map(|app| {
+        let achievements = match (app.unlocked_achievement_count, app.achievement_count) {
+            (Some(unlocked), Some(total)) if total > 0 => {
+                format_achievement_progress(unlocked as usize, total as usize)
+            }
+            (Some(_), Some(_)) => "none".to_owned(),
+            _ => String::new(),
+        };
+        Row::new([
+            Cell::from(app.app_id.to_string()),
+            Cell::from(app.app_name.as_str()),
+            Cell::from(achievements),
+            Cell::from(
+                app.playtime_minutes
+                    .map(format_playtime_minutes)
+                    .unwrap_or_default(),
+            ),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Length(18),
+            Constraint::Length(14),
+        ],
+    )
+    .header(Row::new(["App ID", "Name", "Achievements", "Playtime"]).bold())
+    .block(block)
+    .row_highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(table, area, &mut library.table);
+}
+
+fn draw_game(frame: &mut Frame, area: Rect, game: &mut Game, busy: Option<&str>) {
+    let (unlocked, total) = game.counts();
+    let mut title = vec![
+        format!(" {} ", game.name).bold(),
+        format!("({}) ", game.app_id).dim(),
+    ];
+    if game.progress.is_some() {
+        title.push(
+            format!(
+                "· {} ",
+                format_achievement_progress(unlocked as usize, total as usize)
+            )
+            .into(),
+        );
+    }
+    let pending = game.pending();
+    if pending > 0 {
+        title.push(format!("· {pending} unstored ").yellow().bold());
+    }
+    let mut block = Block::bordered().title(Line::from(title));
+    if let Some(busy) = busy {
+        block = block.title_bottom(Line::from(format!(" {busy} ").italic()));
+    }
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [tabs, list] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    let selected = match game.pane {
+        Pane::Achievements => 0,
+        Pane::Stats => 1,
+    };
+    let (achievement_count, stat_count) = game
+        .progress
+        .as_ref()
+        .map_or((0, 0), |p| (p.achievements.len(), p.stats.len()));
+    frame.render_widget(
+        Tabs::new([
+            format!("Achievements ({achievement_count})"),
+            format!("Stats ({stat_count})"),
+        ])
+        .select(selected)
+        .highlight_style(Style::new().bold().reversed()),
+        tabs,
+    );
+
+    match game.pane {
+        Pane::Achievements => draw_achievements(frame, list, game),
+        Pane::Stats => draw_stats(frame, list, game),
+    }
+}
+
+fn draw_achievements(frame: &mut Frame, area: Rect, game: &mut Game) {
+    let Some(progress) = &game.progress else {
+        return;
+    };
+    let rows = progress.achievements.iter().map(|achievement| {
+        let unlocked = game.is_unlocked(achievement);
+        let staged = game.staged.contains_key(&achievement.id);
+        let mark = match (unlocked, staged) {
+            (true, false) => "[x]",
+            (false, false) => "[ ]",
+            (true, true) => "[+]",
+            (false, true) => "[-]",
+        };
+        let rarity = achievement
+            .global_achieved_percent
+            .map(|p| format!("{p:.1}%"))
+            .unwrap_or_default();
+        let note = match (achievement.permission != 0, achievement.is_hidden) {
+            (true, _) => "protected",
+            (false, true) => "hidden",
+            _ => "",
+        };
+        let row = Row::new([
+            Cell::from(mark),
+            Cell::from(achievement.name.as_str()),
+            Cell::from(achievement.description.as_str()),
+            Cell::from(rarity),
+            Cell::from(note),
+        ]);
+        match (staged, achievement.permission != 0) {
+            (true, _) => row.yellow(),
+            (false, true) => row.dim(),
+            _ => row,
+        }
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Percentage(30),
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(9),
+        ],
+    )
+    .header(Row::new(["", "Name", "Description", "Rarity", ""]).bold())
+    .row_highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(table, area, &mut game.achievements);
+}
+
+fn draw_stats(frame: &mut Frame, area: Rect, game: &mut Game) {
+    let Some(progress) = &game.progress else {
+        return;
+    };
+    let rows = progress.stats.iter().map(|stat| {
+        let (value, original, range) = match stat {
+            StatInfo::Integer(i) => (
+                i.int_value.to_string(),
+                i.original_value.to_string(),
+                format!("{} – {}", i.min_value, i.max_value),
+            ),
+            StatInfo::Float(f) => (
+                f.float_value.to_string(),
+                f.original_value.to_string(),
+                format!("{} – {}", f.min_value, f.max_value),
+            ),
+        };
+        let value = match stat.is_modified() {
+            true => format!("{original} → {value}"),
+            false => value,
+        };
+        let note = match stat {
+            _ if (stat.permission() & 2) != 0 => "protected",
+            StatInfo::Integer(i) if i.is_increment_only => "increment only",
+            StatInfo::Float(f) if f.is_increment_only => "increment only",
+            _ => "",
+        };
+        let row = Row::new([
+            Cell::from(stat.display_name()),
+            Cell::from(value),
+            Cell::from(range),
+            Cell::from(note),
+        ]);
+        match (stat.is_modified(), (stat.permission() & 2) != 0) {
+            (true, _) => row.yellow(),
+            (false, true) => row.dim(),
+            _ => row,
+        }
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Length(24),
+            Constraint::Length(26),
+            Constraint::Length(14),
+        ],
+    )
+    .header(Row::new(["Name", "Value", "Range", ""]).bold())
+    .row_highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(table, area, &mut game.stats);
+}
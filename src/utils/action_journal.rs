@@ -18,17 +18,19 @@
 //! the user did, and the unit the undo works in.
 
 use crate::utils::app_paths::get_app_cache_dir;
+use crate::utils::ipc_types::{AppExport, GrantedAchievement};
+use crate::utils::json_records::{next_id, now_secs};
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
-use std::sync::{LazyLock, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 /// v2 names the change an undo put back by id, not by position in the batch,
-/// which a trim could shift. v1 lines still load.
-pub const FORMAT_VERSION: u32 = 2;
+/// which a trim could shift. v3 adds `snapshot` changes and v4 `granted` ones,
+/// which older builds skip as unreadable. v1 lines still load.
+pub const FORMAT_VERSION: u32 = 4;
 
 const FILE_NAME: &str = "action_journal.jsonl";
 
@@ -50,6 +52,8 @@ pub enum Op {
     BulkLock,
     Import,
     Revert,
+    /// An entry of the schedule, run by the orchestrator.
+    Scheduled,
 }
 
 impl Op {
@@ -85,6 +89,16 @@ pub enum Change {
     /// A library-wide operation: nothing here to put back. `detail` is an
     /// untranslated message id.
     Opaque { detail: String },
+    /// A library-wide operation on one app, with the app's progress as the
+    /// operation found it. The undo writes `before` back whole.
+    Snapshot { detail: String, before: AppExport },
+    /// An achievement Steam unlocked by itself when the operation stored. Not
+    /// undoable: it would come back while the stat behind it stands.
+    Granted {
+        id: String,
+        #[serde(default)]
+        name: String,
+    },
 }
 
 impl Change {
@@ -94,6 +108,28 @@ impl Change {
             Change::IntStat { id, .. } => format!("int:{id}"),
             Change::FloatStat { id, .. } => format!("float:{id}"),
             Change::Opaque { detail } => format!("opaque:{detail}"),
+            // One per app and batch, whichever way it goes: an undo's own
+            // snapshot has to name the one it put back.
+            Change::Snapshot { .. } => "snapshot".to_string(),
+            Change::Granted { id, .. } => format!("granted:{id}"),
+        }
+    }
+
+    /// One entry per achievement Steam unlocked by itself, to go with the
+    /// operation whose store set it off.
+    pub fn granted(granted: Vec<GrantedAchievement>) -> impl Iterator<Item = Self> {
+        granted
+            .into_iter()
+            .map(|GrantedAchievement { id, name }| Change::Granted { id, name })
+    }
+
+    /// A bulk operation's entry for one app, undoable when the app server
+    /// could read the app before changing it.
+    pub fn bulk(detail: &str, before: Option<AppExport>) -> Self {
+        let detail = detail.to_string();
+        match before {
+            Some(before) => Change::Snapshot { detail, before },
+            None => Change::Opaque { detail },
         }
     }
 
@@ -101,8 +137,9 @@ impl Change {
         let (id, name) = match self {
             Change::Achievement { id, name, .. }
             | Change::IntStat { id, name, .. }
-            | Change::FloatStat { id, name, .. } => (id, name),
-            Change::Opaque { detail } => return detail,
+            | Change::FloatStat { id, name, .. }
+            | Change::Granted { id, name } => (id, name),
+            Change::Opaque { detail } | Change::Snapshot { detail, .. } => return detail,
         };
         if name.is_empty() { id } else { name }
     }
@@ -133,9 +170,9 @@ pub struct Entry {
 impl Entry {
     pub fn undoable(&self) -> bool {
         match self.change {
-            Change::Achievement { .. } => true,
+            Change::Achievement { .. } | Change::Snapshot { .. } => true,
             Change::IntStat { .. } | Change::FloatStat { .. } => self.op.restores_stats(),
-            Change::Opaque { .. } => false,
+            Change::Opaque { .. } | Change::Granted { .. } => false,
         }
     }
 }
@@ -143,11 +180,6 @@ impl Entry {
 static ENABLED: AtomicBool = AtomicBool::new(false);
 static ACCOUNT: AtomicU32 = AtomicU32::new(0);
 
-/// Seeded from the clock, with the process id in the low bits: SamRewritten is
-/// `NON_UNIQUE`, and two windows sharing the file must not mint the same ids.
-static NEXT_BATCH: LazyLock<AtomicU64> =
-    LazyLock::new(|| AtomicU64::new((now_millis() << 16) | u64::from(std::process::id() as u16)));
-
 /// `(lines, bytes)` as this process last left the file, and the write lock. The
 /// byte count is what says the tally still holds.
 static LINES: Mutex<Option<(usize, u64)>> = Mutex::new(None);
@@ -172,20 +204,6 @@ pub fn path() -> PathBuf {
     get_app_cache_dir().join(FILE_NAME)
 }
 
-fn now_secs() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
-}
-
-fn now_millis() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-}
-
 #[derive(Debug, Clone)]
 pub struct Batch {
     id: u64,
@@ -193,16 +211,19 @@ pub struct Batch {
     app_id: u32,
     app_name: String,
     reverses: Option<Reverses>,
+    /// Filed under this account rather than the one this process signed in as.
+    account: Option<u32>,
 }
 
 impl Batch {
     pub fn new(op: Op, app_id: u32, app_name: impl Into<String>) -> Self {
         Self {
-            id: NEXT_BATCH.fetch_add(1, Ordering::Relaxed),
+            id: next_id(),
             op,
             app_id,
             app_name: app_name.into(),
             reverses: None,
+            account: None,
         }
     }
 
@@ -217,6 +238,15 @@ impl Batch {
         }
     }
 
+    /// A schedule entry's run, for the account that queued it. The
+    /// orchestrator runs it, which knows nothing of the frontend's settings.
+    pub fn scheduled(account: u32) -> Self {
+        Self {
+            account: Some(account),
+            ..Self::across(Op::Scheduled)
+        }
+    }
+
     pub fn record(&self, changes: Vec<Change>) {
         let app_id = self.app_id;
         let app_name = self.app_name.clone();
@@ -231,12 +261,13 @@ impl Batch {
     pub fn record_per_app(&self, changes: Vec<(u32, String, Change)>) {
         // An undo is written even with recording off: it is bookkeeping on a
         // history already on screen, and dropping it would leave the row it
-        // undoes forever offering to be undone again.
-        if changes.is_empty() || (!is_enabled() && self.op != Op::Revert) {
+        // undoes forever offering to be undone again. A scheduled run was
+        // asked to be recorded, or not, when it was queued.
+        if changes.is_empty() || (!is_enabled() && !matches!(self.op, Op::Revert | Op::Scheduled)) {
             return;
         }
         let at = now_secs();
-        let account = account();
+        let account = self.account.unwrap_or_else(account);
         let entries: Vec<Entry> = changes
             .into_iter()
             .map(|(app_id, app_name, change)| Entry {
@@ -596,6 +627,60 @@ mod tests {
         assert!(!entry(1, Op::BulkUnlock, change, None).undoable());
     }
 
+    #[test]
+    fn a_bulk_action_with_a_before_image_undoes_app_by_app() {
+        let before = AppExport {
+            app_id: 730,
+            app_name: String::new(),
+            achievements: Vec::new(),
+            stats: Vec::new(),
+        };
+        let change = Change::bulk("everything unlocked", Some(before.clone()));
+        let mut other = entry(1, Op::BulkUnlock, change.clone(), None);
+        other.app_id = 440;
+        let undo = entry(
+            2,
+            Op::Revert,
+            Change::bulk("everything unlocked", Some(before)),
+            Some(Reverses { batch: 1 }),
+        );
+        let entries = vec![entry(1, Op::BulkUnlock, change, None), other, undo];
+
+        let operations = group(entries);
+        let bulk = &operations[1];
+        assert!(bulk.changes.iter().all(|c| c.undoable));
+        assert!(bulk.changes[0].reverted);
+        assert!(!bulk.changes[1].reverted);
+        assert!(bulk.undoable());
+    }
+
+    #[test]
+    fn steams_own_unlocks_do_not_hold_an_undone_operation_open() {
+        let granted = Change::granted(vec![GrantedAchievement {
+            id: "B".to_string(),
+            name: "Bee".to_string(),
+        }]);
+        let mut entries = vec![entry(
+            1,
+            Op::ManualToggle,
+            achievement("A", false, true),
+            None,
+        )];
+        entries.extend(granted.map(|change| entry(1, Op::ManualToggle, change, None)));
+        entries.push(entry(
+            2,
+            Op::Revert,
+            achievement("A", true, false),
+            Some(Reverses { batch: 1 }),
+        ));
+
+        let operations = group(entries);
+        let toggle = &operations[1];
+        assert_eq!(toggle.changes[1].change.label(), "Bee");
+        assert!(!toggle.changes[1].undoable);
+        assert!(toggle.reverted);
+    }
+
     #[test]
     fn undoing_one_change_leaves_the_rest_of_the_operation_standing() {
         let operations = group(vec![
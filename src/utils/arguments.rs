@@ -28,6 +28,7 @@ use std::rc::Rc;
 #[derive(Debug)]
 pub struct CliArguments {
     pub is_orchestrator: bool,
+    pub is_daemon: bool,
     pub is_app: u32,
     pub rx: Option<Recver>,
     pub tx: Option<Sender>,
@@ -41,6 +42,7 @@ pub struct GuiArguments {
 pub fn parse_cli_arguments() -> CliArguments {
     let mut args = CliArguments {
         is_orchestrator: false,
+        is_daemon: false,
         is_app: 0,
         rx: None,
         tx: None,
@@ -57,6 +59,10 @@ pub fn parse_cli_arguments() -> CliArguments {
                 args.is_orchestrator = true;
                 continue;
             }
+            "--daemon" => {
+                args.is_daemon = true;
+                continue;
+            }
             _ => unsafe {
                 let split: Vec<&str> = arg.split("=").collect();
                 if split.len() != 2 {
@@ -16,7 +16,7 @@
 use crate::utils::inherit::keep_private;
 #[cfg(unix)]
 use crate::utils::inherit::set_inheritable;
-use crate::utils::ipc_types::SamError;
+use crate::utils::ipc_types::{SamError, offer_handshake};
 #[cfg(unix)]
 use interprocess::unnamed_pipe::pipe;
 use interprocess::unnamed_pipe::{Recver, Sender};
@@ -43,8 +43,22 @@ pub struct BidirChild {
 static SPAWN_LOCK: Mutex<()> = Mutex::new(());
 
 impl BidirChild {
-    #[cfg(unix)]
+    /// Spawn `command` with a pipe each way, and check it speaks our protocol
+    /// before handing it out. Outside `SPAWN_LOCK`: the child may take a while to
+    /// answer, and other spawns need not wait for it.
     pub fn new(command: &mut Command) -> Result<Self, SamError> {
+        let mut child = Self::spawn(command)?;
+        if let Err(e) = offer_handshake(&mut child.tx, &mut child.rx) {
+            eprintln!("Handshake with {:?} failed: {e}", command.get_program());
+            let _ = child.process.kill();
+            let _ = child.process.wait();
+            return Err(e);
+        }
+        Ok(child)
+    }
+
+    #[cfg(unix)]
+    fn spawn(command: &mut Command) -> Result<Self, SamError> {
         let _spawn_guard = SPAWN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
 
         let make_pipe = || {
@@ -93,7 +107,7 @@ impl BidirChild {
     }
 
     #[cfg(windows)]
-    pub fn new(command: &mut Command) -> Result<Self, SamError> {
+    fn spawn(command: &mut Command) -> Result<Self, SamError> {
         let _spawn_guard = SPAWN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
 
         let make_pipe = || {
@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Bulk operations kept on disk while they run, one JSON file per job in the
+//! cache folder: which apps, how far each got, and what each answered. A job
+//! that a crash cut short can be picked up again, skipping the apps done.
+//!
+//! Only what changes Steam is kept: an export that stopped is just run again.
+
+use crate::utils::ipc_types::{AppExport, SamError};
+use crate::utils::json_records::{Record, Records, next_id, now_secs};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+
+/// What a bulk request answers: one result per app it got to.
+pub type PerApp<T> = Vec<(u32, Result<T, SamError>)>;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Older finished jobs are deleted as new ones start.
+const KEEP_FINISHED: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    UnlockAll,
+    /// Every achievement and stat reset.
+    Lock,
+    Import,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Running,
+    /// The process running it died, or lost the orchestrator.
+    Interrupted,
+    Cancelled,
+    /// Every app was tried; some may have failed.
+    Finished,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Item {
+    pub app_id: u32,
+    #[serde(default)]
+    pub app_name: String,
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The app's answer, as the bulk request returned it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// What an import writes to the app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<AppExport>,
+}
+
+impl Item {
+    pub fn new(app_id: u32, app_name: impl Into<String>) -> Self {
+        Self {
+            app_id,
+            app_name: app_name.into(),
+            status: Status::Pending,
+            error: None,
+            result: None,
+            export: None,
+        }
+    }
+
+    pub fn import(export: AppExport) -> Self {
+        Self {
+            export: Some(export.clone()),
+            ..Self::new(export.app_id, export.app_name)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Job {
+    pub v: u32,
+    pub id: u64,
+    pub kind: Kind,
+    /// Unix seconds.
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub state: State,
+    pub items: Vec<Item>,
+}
+
+impl Job {
+    /// The apps a resume would run: those not done yet, failed ones included.
+    pub fn remaining(&self) -> Vec<Item> {
+        self.items
+            .iter()
+            .filter(|item| item.status != Status::Done)
+            .cloned()
+            .collect()
+    }
+
+    pub fn count(&self, status: Status) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.status == status)
+            .count()
+    }
+}
+
+impl Record for Job {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn running(&self) -> bool {
+        self.state == State::Running
+    }
+
+    fn interrupt(&mut self) {
+        self.state = State::Interrupted;
+    }
+
+    fn finished(&self) -> bool {
+        self.state == State::Finished
+    }
+}
+
+/// A job's lock is held while a process runs it.
+static JOBS: Records<Job> = Records::new("jobs", "[CLIENT]", "job", KEEP_FINISHED);
+
+fn newest_first(a: &Job, b: &Job) -> std::cmp::Ordering {
+    b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id))
+}
+
+/// Every job on disk, newest first.
+pub fn load() -> Vec<Job> {
+    let mut jobs = JOBS.load();
+    jobs.sort_by(newest_first);
+    jobs
+}
+
+pub fn get(id: u64) -> Option<Job> {
+    JOBS.get(id)
+}
+
+/// The jobs a crash or a lost orchestrator cut short, newest first.
+#[cfg(feature = "gui")]
+pub fn interrupted() -> Vec<Job> {
+    load()
+        .into_iter()
+        .filter(|job| job.state == State::Interrupted && !job.remaining().is_empty())
+        .collect()
+}
+
+/// False if a process is running it.
+pub fn discard(id: u64) -> bool {
+    JOBS.discard(id)
+}
+
+/// Why a job cannot be resumed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResumeError {
+    NotFound,
+    /// Another process is running it.
+    Busy,
+    NothingLeft,
+}
+
+impl std::fmt::Display for ResumeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResumeError::NotFound => write!(f, "No such job"),
+            ResumeError::Busy => write!(f, "The job is running in another window or command"),
+            ResumeError::NothingLeft => write!(f, "Every app in the job is done already"),
+        }
+    }
+}
+
+/// A job this process runs. Every change is written through, so whatever
+/// happens to the process, the file says how far it got.
+pub struct Running {
+    job: Job,
+    _lock: Option<File>,
+}
+
+impl Running {
+    pub fn start(kind: Kind, items: Vec<Item>) -> Self {
+        JOBS.prune(newest_first);
+        let id = next_id();
+        let now = now_secs();
+        let mut running = Self {
+            job: Job {
+                v: FORMAT_VERSION,
+                id,
+                kind,
+                created_at: now,
+                updated_at: now,
+                state: State::Running,
+                items,
+            },
+            _lock: JOBS.claim(id),
+        };
+        running.save();
+        running
+    }
+
+    pub fn resume(id: u64) -> Result<Self, ResumeError> {
+        let lock = JOBS.claim(id).ok_or(ResumeError::Busy)?;
+        let mut job = JOBS.read(id).ok_or(ResumeError::NotFound)?;
+        if job.remaining().is_empty() {
+            return Err(ResumeError::NothingLeft);
+        }
+        job.state = State::Running;
+        for item in &mut job.items {
+            if item.status == Status::Failed {
+                item.status = Status::Pending;
+                item.error = None;
+            }
+        }
+        let mut running = Self {
+            job,
+            _lock: Some(lock),
+        };
+        running.save();
+        Ok(running)
+    }
+
+    pub fn job(&self) -> &Job {
+        &self.job
+    }
+
+    /// An app finished, as a `Step` reports it.
+    pub fn step(&mut self, app_id: u32, ok: bool) {
+        if let Some(item) = self.item(app_id) {
+            item.status = if ok { Status::Done } else { Status::Failed };
+        }
+        self.save();
+    }
+
+    /// The bulk request's results are in. Apps it never got to stay pending.
+    pub fn finish<T: Serialize>(
+        &mut self,
+        results: &[(u32, Result<T, SamError>)],
+        cancelled: bool,
+    ) {
+        for (app_id, result) in results {
+            let Some(item) = self.item(*app_id) else {
+                continue;
+            };
+            match result {
+                Ok(answer) => item.result = serde_json::to_value(answer).ok(),
+                Err(e) => {
+                    item.status = Status::Failed;
+                    item.error = Some(e.to_string());
+                }
+            }
+        }
+        self.job.state = if cancelled && self.job.count(Status::Pending) > 0 {
+            State::Cancelled
+        } else {
+            State::Finished
+        };
+        self.save();
+    }
+
+    /// The bulk request failed as a whole, most likely with the orchestrator.
+    pub fn interrupt(&mut self, error: &SamError) {
+        eprintln!("[CLIENT] Job {} interrupted: {error}", self.job.id);
+        self.job.state = State::Interrupted;
+        self.save();
+    }
+
+    /// `finish` or `interrupt`, whichever the bulk request's answer calls for.
+    pub fn conclude<T: Serialize>(
+        &mut self,
+        response: &Result<PerApp<T>, SamError>,
+        cancelled: bool,
+    ) {
+        match response {
+            Ok(results) => self.finish(results, cancelled),
+            Err(e) => self.interrupt(e),
+        }
+    }
+
+    fn item(&mut self, app_id: u32) -> Option<&mut Item> {
+        self.job.items.iter_mut().find(|item| item.app_id == app_id)
+    }
+
+    fn save(&mut self) {
+        self.job.updated_at = now_secs();
+        JOBS.save(&self.job);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_resume_runs_the_failed_and_pending_apps_only() {
+        let mut items: Vec<Item> = [10, 20, 30].map(|id| Item::new(id, "")).to_vec();
+        items[0].status = Status::Done;
+        items[1].status = Status::Failed;
+        let job = Job {
+            v: FORMAT_VERSION,
+            id: 1,
+            kind: Kind::UnlockAll,
+            created_at: 0,
+            updated_at: 0,
+            state: State::Interrupted,
+            items,
+        };
+        let line = serde_json::to_string(&job).unwrap();
+        let job: Job = serde_json::from_str(&line).unwrap();
+        let left: Vec<u32> = job.remaining().iter().map(|item| item.app_id).collect();
+        assert_eq!(left, [20, 30]);
+    }
+}
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! What a bulk operation did to each app, for the report the GUI shows after
+//! one and saves as JSON or CSV. Unlock, lock and import read it back from
+//! their job; export, which keeps no job, from its results.
+
+use crate::utils::bulk_jobs::{Job, Kind, PerApp, State, Status};
+use crate::utils::export_file::iso8601_utc_now;
+use crate::utils::ipc_types::{AppExport, ImportSummary, SamError, Snapshotted};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    UnlockAll,
+    Lock,
+    Export,
+    Import,
+}
+
+impl From<Kind> for Operation {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::UnlockAll => Self::UnlockAll,
+            Kind::Lock => Self::Lock,
+            Kind::Import => Self::Import,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Done,
+    /// The app answered, but Steam did not store what it was sent.
+    NotStored,
+    Failed,
+    /// Cancelled, or cut short, before its turn.
+    NotStarted,
+}
+
+impl Outcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Done => "done",
+            Self::NotStored => "not_stored",
+            Self::Failed => "failed",
+            Self::NotStarted => "not_started",
+        }
+    }
+}
+
+/// One app's line. The counts and lists are an import's; the other operations
+/// only fill in the outcome and errors.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppReport {
+    pub app_id: u32,
+    pub app_name: String,
+    pub outcome: Outcome,
+    pub achievements_applied: usize,
+    pub stats_applied: usize,
+    pub skipped_protected: Vec<String>,
+    pub skipped_unwriteable: Vec<String>,
+    pub errors: Vec<String>,
+    pub reset_would_help: bool,
+}
+
+impl AppReport {
+    fn new(app_id: u32, app_name: &str) -> Self {
+        Self {
+            app_id,
+            app_name: app_name.to_string(),
+            outcome: Outcome::NotStarted,
+            achievements_applied: 0,
+            stats_applied: 0,
+            skipped_protected: Vec::new(),
+            skipped_unwriteable: Vec::new(),
+            errors: Vec::new(),
+            reset_would_help: false,
+        }
+    }
+
+    /// What the report sorts by. Untranslated: the dialog names apps with no
+    /// name through `tr` instead.
+    fn label(&self) -> String {
+        if self.app_name.is_empty() {
+            format!("App {}", self.app_id)
+        } else {
+            self.app_name.clone()
+        }
+    }
+
+    /// Whether anything about this app calls for a look.
+    pub fn has_problems(&self) -> bool {
+        self.outcome != Outcome::Done
+            || !self.skipped_protected.is_empty()
+            || !self.skipped_unwriteable.is_empty()
+            || self.reset_would_help
+    }
+
+    fn answered(&mut self, kind: Kind, result: Value) {
+        match kind {
+            Kind::UnlockAll | Kind::Lock => {
+                if let Ok(stored) = serde_json::from_value::<Snapshotted<bool>>(result)
+                    && !stored.result
+                {
+                    self.outcome = Outcome::NotStored;
+                }
+            }
+            Kind::Import => {
+                if let Ok(summary) = serde_json::from_value::<Snapshotted<ImportSummary>>(result) {
+                    self.imported(summary.result);
+                }
+            }
+        }
+    }
+
+    fn imported(&mut self, summary: ImportSummary) {
+        self.achievements_applied = summary.achievements_applied;
+        self.stats_applied = summary.stats_applied;
+        self.skipped_protected = summary.skipped_protected;
+        self.skipped_unwriteable = summary.skipped_unwriteable;
+        self.reset_would_help = summary.reset_would_help;
+        if !summary.errors.is_empty() {
+            self.outcome = Outcome::Failed;
+            self.errors.extend(summary.errors);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Report {
+    pub operation: Operation,
+    /// "YYYY-MM-DDTHH:MM:SSZ", like export files.
+    pub finished_at: String,
+    pub cancelled: bool,
+    /// Why the request failed as a whole, leaving the apps it never got to
+    /// not started.
+    pub error: Option<String>,
+    pub apps: Vec<AppReport>,
+}
+
+impl Report {
+    fn new(operation: Operation, cancelled: bool, failure: Option<&SamError>) -> Self {
+        Self {
+            operation,
+            finished_at: iso8601_utc_now(),
+            cancelled,
+            error: failure.map(|e| e.to_string()),
+            apps: Vec::new(),
+        }
+    }
+
+    /// Once `Running::conclude` has recorded the request's answer.
+    pub fn from_job(job: &Job, failure: Option<&SamError>) -> Self {
+        let mut report = Self::new(job.kind.into(), job.state == State::Cancelled, failure);
+        for item in &job.items {
+            let mut app = AppReport::new(item.app_id, &item.app_name);
+            app.outcome = match item.status {
+                Status::Done => Outcome::Done,
+                Status::Failed => Outcome::Failed,
+                Status::Pending => Outcome::NotStarted,
+            };
+            app.errors.extend(item.error.clone());
+            if let Some(result) = item.result.clone() {
+                app.answered(job.kind, result);
+            }
+            report.apps.push(app);
+        }
+        report.sort();
+        report
+    }
+
+    pub fn from_export(
+        names: &HashMap<u32, String>,
+        response: &Result<PerApp<AppExport>, SamError>,
+        cancelled: bool,
+    ) -> Self {
+        let mut report = Self::new(Operation::Export, cancelled, response.as_ref().err());
+        let mut apps: HashMap<u32, AppReport> = names
+            .iter()
+            .map(|(id, name)| (*id, AppReport::new(*id, name)))
+            .collect();
+        for (app_id, result) in response.iter().flatten() {
+            let app = apps
+                .entry(*app_id)
+                .or_insert_with(|| AppReport::new(*app_id, ""));
+            match result {
+                Ok(_) => app.outcome = Outcome::Done,
+                Err(e) => {
+                    app.outcome = Outcome::Failed;
+                    app.errors.push(e.to_string());
+                }
+            }
+        }
+        report.apps = apps.into_values().collect();
+        report.sort();
+        report
+    }
+
+    /// The apps that need a look first, then by name.
+    fn sort(&mut self) {
+        self.apps.sort_by(|a, b| {
+            b.has_problems()
+                .cmp(&a.has_problems())
+                .then_with(|| a.label().to_lowercase().cmp(&b.label().to_lowercase()))
+        });
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.apps
+            .iter()
+            .filter(|app| app.outcome == outcome)
+            .count()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// One row per app; the lists are joined with "; ".
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "app_id,app_name,outcome,achievements_applied,stats_applied,\
+             skipped_protected,skipped_unwriteable,errors,reset_would_help\n",
+        );
+        for app in &self.apps {
+            let fields = [
+                app.app_id.to_string(),
+                app.app_name.clone(),
+                app.outcome.as_str().to_string(),
+                app.achievements_applied.to_string(),
+                app.stats_applied.to_string(),
+                app.skipped_protected.join("; "),
+                app.skipped_unwriteable.join("; "),
+                app.errors.join("; "),
+                app.reset_would_help.to_string(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Quoted only when it has to be, with inner quotes doubled (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bulk_jobs::{FORMAT_VERSION, Item};
+
+    #[test]
+    fn an_import_report_lists_every_app_problems_first() {
+        let mut clean = Item::new(10, "Alpha");
+        clean.status = Status::Done;
+        clean.result = serde_json::to_value(Snapshotted {
+            result: ImportSummary {
+                achievements_applied: 3,
+                ..Default::default()
+            },
+            before: None,
+        })
+        .ok();
+        let mut partial = Item::new(20, "Beta, \"the sequel\"");
+        partial.status = Status::Done;
+        partial.result = serde_json::to_value(Snapshotted {
+            result: ImportSummary {
+                stats_applied: 1,
+                skipped_protected: vec!["ACH_A".into(), "ACH_B".into()],
+                reset_would_help: true,
+                ..Default::default()
+            },
+            before: None,
+        })
+        .ok();
+        let job = Job {
+            v: FORMAT_VERSION,
+            id: 1,
+            kind: Kind::Import,
+            created_at: 0,
+            updated_at: 0,
+            state: State::Cancelled,
+            items: vec![clean, partial, Item::new(30, "")],
+        };
+
+        let report = Report::from_job(&job, None);
+        assert!(report.cancelled);
+        let order: Vec<u32> = report.apps.iter().map(|app| app.app_id).collect();
+        assert_eq!(order, [30, 20, 10]);
+        assert_eq!(report.count(Outcome::NotStarted), 1);
+
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "30,,not_started,0,0,,,,false");
+        assert_eq!(
+            lines[2],
+            "20,\"Beta, \"\"the sequel\"\"\",done,0,1,ACH_A; ACH_B,,,true"
+        );
+    }
+}
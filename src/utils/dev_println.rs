@@ -13,6 +13,21 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by the terminal UI, whose screen the log lines would otherwise land on.
+/// Only that process: its backend children log to a file instead.
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "tui")]
+pub fn mute() {
+    MUTED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    cfg!(debug_assertions) && !MUTED.load(Ordering::Relaxed)
+}
+
 /// Wall-clock time of day as `HH:MM:SS` in UTC.
 ///
 /// UTC keeps this dependency-free: `std` exposes no local timezone offset. The
@@ -36,12 +51,12 @@ pub fn log_timestamp() -> String {
 #[macro_export]
 macro_rules! dev_println {
     ($scope:literal, $($arg:tt)+) => {
-        if cfg!(debug_assertions) {
+        if $crate::utils::dev_println::enabled() {
             println!("[{}\t{}] {}", $scope, $crate::utils::dev_println::log_timestamp(), format_args!($($arg)+));
         }
     };
     ($($arg:tt)*) => {
-        if cfg!(debug_assertions) {
+        if $crate::utils::dev_println::enabled() {
             println!("[{}] {}", $crate::utils::dev_println::log_timestamp(), format_args!($($arg)*));
         }
     };
@@ -50,12 +65,12 @@ macro_rules! dev_println {
 #[macro_export]
 macro_rules! dev_print {
     ($scope:literal, $($arg:tt)+) => {
-        if cfg!(debug_assertions) {
+        if $crate::utils::dev_println::enabled() {
             print!("[{}\t{}] {}", $scope, $crate::utils::dev_println::log_timestamp(), format_args!($($arg)+));
         }
     };
     ($($arg:tt)*) => {
-        if cfg!(debug_assertions) {
+        if $crate::utils::dev_println::enabled() {
             print!("[{}] {}", $crate::utils::dev_println::log_timestamp(), format_args!($($arg)*));
         }
     };
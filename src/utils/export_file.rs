@@ -15,6 +15,7 @@
 
 use crate::utils::ipc_types::AppExport;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::time::SystemTime;
 
 pub const FORMAT_VERSION: u32 = 1;
@@ -26,6 +27,46 @@ pub struct ExportFile {
     pub apps: Vec<AppExport>,
 }
 
+/// Why a file cannot be imported.
+#[derive(Debug)]
+pub enum ReadError {
+    Read(std::io::Error),
+    Parse(serde_json::Error),
+    /// The format version the file was written in.
+    Version(u32),
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Read(e) => write!(f, "Could not read file: {e}"),
+            ReadError::Parse(e) => write!(f, "Could not parse file: {e}"),
+            ReadError::Version(found) => write!(
+                f,
+                "Unsupported format version: {found} (this build expects {FORMAT_VERSION})"
+            ),
+        }
+    }
+}
+
+impl ExportFile {
+    /// Itself, if this build reads the format it was written in.
+    pub fn checked(self) -> Result<Self, ReadError> {
+        if self.format_version != FORMAT_VERSION {
+            return Err(ReadError::Version(self.format_version));
+        }
+        Ok(self)
+    }
+}
+
+/// The file at `path`, read, parsed and `checked`, as every import reads it.
+pub fn load(path: &Path) -> Result<ExportFile, ReadError> {
+    let contents = std::fs::read_to_string(path).map_err(ReadError::Read)?;
+    serde_json::from_str::<ExportFile>(&contents)
+        .map_err(ReadError::Parse)?
+        .checked()
+}
+
 /// Current UTC time as "YYYY-MM-DDTHH:MM:SSZ". Uses only std so the CLI build
 /// (no glib) can produce the same format as the GUI.
 pub fn iso8601_utc_now() -> String {
@@ -45,6 +86,62 @@ pub fn iso8601_utc_now() -> String {
     )
 }
 
+/// Inverse of `iso8601_utc_now`, also taking a bare "YYYY-MM-DD" (midnight UTC).
+/// Offsets other than `Z` are not understood and read as `None`.
+pub fn parse_iso8601_utc(text: &str) -> Option<SystemTime> {
+    let text = text.trim();
+    let (date, time) = match text.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z').unwrap_or(time))),
+        None => (text, None),
+    };
+
+    let mut ymd = date.splitn(3, '-');
+    let year: i64 = ymd.next()?.parse().ok()?;
+    let month: u32 = ymd.next()?.parse().ok()?;
+    let day: u32 = ymd.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let seconds_today = match time {
+        Some(time) => {
+            let mut hms = time.splitn(3, ':');
+            let h: u32 = hms.next()?.parse().ok()?;
+            let m: u32 = hms.next()?.parse().ok()?;
+            let s: u32 = hms.next().map_or(Some(0), |s| s.parse().ok())?;
+            if h > 23 || m > 59 || s > 60 {
+                return None;
+            }
+            i64::from(h * 3600 + m * 60 + s)
+        }
+        None => 0,
+    };
+
+    let secs = ymd_to_days_since_epoch(year, month, day) * 86400 + seconds_today;
+    let secs = u64::try_from(secs).ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Howard Hinnant's date algorithm.
+fn ymd_to_days_since_epoch(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
 // Howard Hinnant's date algorithm.
 fn days_since_epoch_to_ymd(days_since_epoch: i64) -> (i64, u32, u32) {
     let days = days_since_epoch + 719468;
@@ -76,4 +173,22 @@ mod tests {
         // 2000-02-29 (leap year)
         assert_eq!(days_since_epoch_to_ymd(11016), (2000, 2, 29));
     }
+
+    #[test]
+    fn parses_what_it_writes() {
+        let at = |secs: u64| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        assert_eq!(ymd_to_days_since_epoch(2026, 5, 14), 20587);
+        assert_eq!(ymd_to_days_since_epoch(2000, 2, 29), 11016);
+        assert_eq!(ymd_to_days_since_epoch(-400, 12, 31), -865260);
+        assert_eq!(parse_iso8601_utc("1970-01-01"), Some(at(0)));
+        assert_eq!(
+            parse_iso8601_utc("2026-05-14T01:02:03Z"),
+            Some(at(20587 * 86400 + 3723))
+        );
+        assert_eq!(parse_iso8601_utc("2026-13-01"), None);
+        assert_eq!(parse_iso8601_utc("2024-02-31"), None);
+        assert_eq!(parse_iso8601_utc("2023-02-29"), None);
+        assert!(parse_iso8601_utc("2024-02-29").is_some());
+        assert_eq!(parse_iso8601_utc("yesterday"), None);
+    }
 }
@@ -13,36 +13,102 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::dev_println;
 use crate::utils::bidir_child::BidirChild;
+use crate::utils::ipc_recording::{self, Direction, Peer};
 use crate::utils::ipc_types::{
-    SamError, SteamResponse, read_frame_raw, read_message, write_message,
+    HEADER_LEN, SamError, SteamResponse, decode, read_frame, read_frame_raw, read_tagged_frame,
+    write_message, write_tagged,
 };
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::process::ExitStatus;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+#[cfg(target_os = "linux")]
+use std::net::Shutdown;
+#[cfg(target_os = "linux")]
+use std::os::unix::net::UnixStream;
+use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
 
-/// Synchronous request/response client over a `BidirChild`. Owns the child
-/// process and its two pipes, exposes a single round-trip API to callers, and
+/// Synchronous request/response client over a `BidirChild`, or a socket to a
+/// `--daemon` orchestrator. Exposes a single round-trip API to callers and
 /// keeps all byte-level framing in one place.
 pub struct IpcClient {
-    pub child: BidirChild,
+    link: Link,
+    /// The app server's app id, to record its frames under.
+    app_id: Option<u32>,
+}
+
+enum Link {
+    Child(BidirChild),
+    #[cfg(target_os = "linux")]
+    Daemon(UnixStream),
 }
 
 impl IpcClient {
     pub fn new(child: BidirChild) -> Self {
-        Self { child }
+        Self {
+            link: Link::Child(child),
+            app_id: None,
+        }
+    }
+
+    /// The orchestrator's link to the app server for `app_id`, whose frames go
+    /// in an IPC recording.
+    pub fn app_server(child: BidirChild, app_id: u32) -> Self {
+        Self {
+            link: Link::Child(child),
+            app_id: Some(app_id),
+        }
+    }
+
+    /// The daemon outlives us: `wait` only hangs up.
+    #[cfg(target_os = "linux")]
+    pub fn attached(stream: UnixStream) -> Self {
+        Self {
+            link: Link::Daemon(stream),
+            app_id: None,
+        }
     }
 
     pub fn send<T: Serialize + ?Sized>(&mut self, cmd: &T) -> Result<(), SamError> {
-        write_message(&mut self.child.tx, cmd)
+        if let Some(app_id) = self.app_id {
+            ipc_recording::record(Peer::AppServer(app_id), Direction::Request, 0, cmd);
+        }
+        match &mut self.link {
+            Link::Child(child) => write_message(&mut child.tx, cmd),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(stream) => write_message(stream, cmd),
+        }
     }
 
     pub fn recv<R: DeserializeOwned>(&mut self) -> Result<R, SamError> {
-        read_message(&mut self.child.rx)
+        let payload = match &mut self.link {
+            Link::Child(child) => read_frame(&mut child.rx),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(stream) => read_frame(stream),
+        }?;
+        self.recorded(&payload);
+        decode(&payload)
     }
 
     pub fn recv_frame(&mut self) -> Result<Vec<u8>, SamError> {
-        read_frame_raw(&mut self.child.rx)
+        let frame = match &mut self.link {
+            Link::Child(child) => read_frame_raw(&mut child.rx),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(stream) => read_frame_raw(stream),
+        }?;
+        self.recorded(&frame[HEADER_LEN..]);
+        Ok(frame)
+    }
+
+    fn recorded(&self, payload: &[u8]) {
+        if let Some(app_id) = self.app_id {
+            ipc_recording::record_payload(Peer::AppServer(app_id), Direction::Response, 0, payload);
+        }
     }
 
     pub fn request<R: DeserializeOwned, C: Serialize + ?Sized>(
@@ -61,7 +127,254 @@ impl IpcClient {
         response.into()
     }
 
-    pub fn wait(&mut self) -> std::io::Result<ExitStatus> {
-        self.child.process.wait()
+    /// `request_response` to a child that may have hung. Past `deadline` it is
+    /// killed, which also ends the wait for its answer, and this fails with
+    /// `Timeout`.
+    pub fn request_within<R: DeserializeOwned, C: Serialize + Sync + ?Sized>(
+        &mut self,
+        cmd: &C,
+        deadline: Duration,
+    ) -> Result<R, SamError> {
+        let Link::Child(child) = &mut self.link else {
+            return self.request_response(cmd);
+        };
+        if let Some(app_id) = self.app_id {
+            ipc_recording::record(Peer::AppServer(app_id), Direction::Request, 0, cmd);
+        }
+        let BidirChild { process, tx, rx } = child;
+        let payload = std::thread::scope(|scope| {
+            let (answered, answer) = mpsc::channel();
+            scope.spawn(move || {
+                let _ = answered.send(write_message(tx, cmd).and_then(|()| read_frame(rx)));
+            });
+            answer.recv_timeout(deadline).unwrap_or_else(|_| {
+                let _ = process.kill();
+                Err(SamError::Timeout)
+            })
+        })?;
+        self.recorded(&payload);
+        let response: SteamResponse<R> = decode(&payload)?;
+        response.into()
+    }
+
+    pub fn wait(&mut self) -> std::io::Result<()> {
+        match &mut self.link {
+            Link::Child(child) => child.process.wait().map(|_| ()),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+
+    /// The child's process id. `None` for the daemon, which is not ours.
+    pub fn pid(&self) -> Option<u32> {
+        match &self.link {
+            Link::Child(child) => Some(child.process.id()),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(_) => None,
+        }
+    }
+
+    /// How the child ended, once it has, without waiting for it.
+    pub fn exited(&mut self) -> Option<ExitStatus> {
+        match &mut self.link {
+            Link::Child(child) => child.process.try_wait().ok().flatten(),
+            #[cfg(target_os = "linux")]
+            Link::Daemon(_) => None,
+        }
+    }
+
+    /// For a child that stopped answering: nothing it says can be trusted.
+    pub fn kill(&mut self) {
+        if let Link::Child(child) = &mut self.link {
+            let _ = child.process.kill();
+            let _ = child.process.wait();
+        }
+    }
+
+    /// Hand the link over to a `SharedClient`, with a thread of its own reading
+    /// the responses.
+    pub fn shared(self) -> SharedClient {
+        let (writer, reader, end): (Box<dyn Write + Send>, Option<Box<dyn Read + Send>>, End) =
+            match self.link {
+                Link::Child(BidirChild { process, tx, rx }) => {
+                    (Box::new(tx), Some(Box::new(rx)), End::Child(process))
+                }
+                #[cfg(target_os = "linux")]
+                Link::Daemon(stream) => {
+                    let reader = stream.try_clone().ok();
+                    let end = stream.try_clone().ok();
+                    match reader.zip(end) {
+                        Some((reader, end)) => {
+                            (Box::new(stream), Some(Box::new(reader)), End::Daemon(end))
+                        }
+                        None => {
+                            eprintln!("[CLIENT] Failed to share the daemon socket");
+                            let end = End::Daemon(stream);
+                            (Box::new(std::io::sink()), None, end)
+                        }
+                    }
+                }
+            };
+
+        let pending = Arc::new(Mutex::new(Some(HashMap::new())));
+        match reader {
+            Some(reader) => {
+                let pending = pending.clone();
+                std::thread::spawn(move || route_responses(reader, &pending));
+            }
+            None => *pending.lock().unwrap() = None,
+        }
+        SharedClient {
+            writer: Mutex::new(writer),
+            pending,
+            next_id: AtomicU32::new(1),
+            end: Mutex::new(end),
+        }
+    }
+}
+
+type Pending = Mutex<Option<HashMap<u32, mpsc::Sender<Vec<u8>>>>>;
+
+/// An `IpcClient` for many threads at once: the frontend's link to the
+/// orchestrator. Each request goes out under an id of its own, and a reader
+/// thread hands response frames to whoever waits on their id, in whatever order
+/// the orchestrator finishes them.
+pub struct SharedClient {
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// Who waits on which request. `None` once the link is gone, so that nobody
+    /// starts waiting on it.
+    pending: Arc<Pending>,
+    next_id: AtomicU32,
+    end: Mutex<End>,
+}
+
+enum End {
+    Child(Child),
+    #[cfg(target_os = "linux")]
+    Daemon(UnixStream),
+}
+
+/// A request in flight, until its last response frame has been read.
+pub struct Call<'a> {
+    client: &'a SharedClient,
+    id: u32,
+    responses: mpsc::Receiver<Vec<u8>>,
+}
+
+impl SharedClient {
+    /// Send `cmd` as a new request. Most get one response frame; a bulk
+    /// command's progress comes as several.
+    pub fn call<C: Serialize + ?Sized>(&self, cmd: &C) -> Result<Call<'_>, SamError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, responses) = mpsc::channel();
+        match self.pending.lock().unwrap().as_mut() {
+            Some(waiting) => waiting.insert(id, tx),
+            None => {
+                eprintln!("[CLIENT] The link to the orchestrator is gone");
+                return Err(SamError::SocketCommunicationFailed);
+            }
+        };
+        let call = Call {
+            client: self,
+            id,
+            responses,
+        };
+        write_tagged(&mut *self.writer.lock().unwrap(), id, cmd)?;
+        Ok(call)
+    }
+
+    pub fn request_response<R: DeserializeOwned, C: Serialize + ?Sized>(
+        &self,
+        cmd: &C,
+    ) -> Result<R, SamError> {
+        let response: SteamResponse<R> = self.call(cmd)?.recv()?;
+        response.into()
+    }
+
+    pub fn wait(&self) -> std::io::Result<()> {
+        match &mut *self.end.lock().unwrap() {
+            End::Child(process) => process.wait().map(|_| ()),
+            #[cfg(target_os = "linux")]
+            End::Daemon(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+
+    /// A `--daemon` is shared with other frontends: not ours to restart.
+    #[cfg(any(feature = "gui", feature = "tui"))]
+    pub fn is_daemon(&self) -> bool {
+        match &*self.end.lock().unwrap() {
+            End::Child(_) => false,
+            #[cfg(target_os = "linux")]
+            End::Daemon(_) => true,
+        }
+    }
+}
+
+impl Call<'_> {
+    /// The id the request went out under, for a later `Cancel`.
+    #[cfg(any(feature = "cli", feature = "gui"))]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn recv<R: DeserializeOwned>(&self) -> Result<R, SamError> {
+        let payload = self.responses.recv().map_err(|_| {
+            eprintln!(
+                "[CLIENT] The orchestrator went away before answering request {}",
+                self.id
+            );
+            SamError::SocketCommunicationFailed
+        })?;
+        decode(&payload)
+    }
+}
+
+impl Drop for Call<'_> {
+    fn drop(&mut self) {
+        if let Some(waiting) = self.client.pending.lock().unwrap().as_mut() {
+            waiting.remove(&self.id);
+        }
+    }
+}
+
+fn route_responses(mut reader: impl Read, pending: &Pending) {
+    while let Ok((id, payload)) = read_tagged_frame(&mut reader) {
+        let mut pending = pending.lock().unwrap();
+        let Some(waiting) = pending.as_mut() else {
+            break;
+        };
+        let delivered = waiting.get(&id).is_some_and(|tx| tx.send(payload).is_ok());
+        if !delivered {
+            dev_println!("CLIENT", "Nobody waits on request {id} anymore");
+            waiting.remove(&id);
+        }
+    }
+    // Wakes everyone still waiting, with an error.
+    *pending.lock().unwrap() = None;
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::utils::ipc_types::read_tagged;
+
+    #[test]
+    fn responses_reach_their_caller_in_any_order() {
+        let (ours, mut theirs) = UnixStream::pair().unwrap();
+        let client = IpcClient::attached(ours).shared();
+        std::thread::scope(|scope| {
+            let first = scope.spawn(|| client.request_response::<usize, _>("first"));
+            let second = scope.spawn(|| client.request_response::<usize, _>("second"));
+
+            let mut requests: Vec<(u32, String)> =
+                (0..2).map(|_| read_tagged(&mut theirs).unwrap()).collect();
+            requests.reverse();
+            for (id, name) in requests {
+                write_tagged(&mut theirs, id, &SteamResponse::Success(name.len())).unwrap();
+            }
+
+            assert_eq!(first.join().unwrap(), Ok(5));
+            assert_eq!(second.join().unwrap(), Ok(6));
+        });
     }
 }
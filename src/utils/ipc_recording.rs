@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Opt-in record of the orchestrator's IPC traffic, to reproduce what a user
+//! saw without their Steam. With `SAM_IPC_RECORD=<file>`, the orchestrator
+//! appends every frame it exchanges with the frontend and with app servers to
+//! the file, one JSON object per line. With `SAM_IPC_REPLAY=<file>`, it answers
+//! the frontend from such a file and never loads Steam.
+
+use crate::dev_println;
+use crate::utils::ipc_types::{SamError, SteamResponse, read_tagged_frame, write_tagged};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const RECORD_ENV: &str = "SAM_IPC_RECORD";
+const REPLAY_ENV: &str = "SAM_IPC_REPLAY";
+
+/// Who the orchestrator exchanged a frame with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Peer {
+    Frontend,
+    AppServer(u32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// To the process that answers it: the orchestrator, or an app server.
+    Request,
+    Response,
+}
+
+/// One line of a recording.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Frame {
+    /// Since the recording started.
+    pub ms: u64,
+    pub peer: Peer,
+    pub direction: Direction,
+    /// The request id. Always 0 with an app server.
+    pub id: u32,
+    pub message: Value,
+}
+
+struct Recorder {
+    file: Mutex<File>,
+    started: Instant,
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Only a private orchestrator records or replays: a daemon already running
+/// would do neither.
+#[cfg(target_os = "linux")]
+pub fn wanted() -> bool {
+    env_path(RECORD_ENV).is_some() || env_path(REPLAY_ENV).is_some()
+}
+
+/// Start recording, if asked to. Only the orchestrator calls this, so that the
+/// frontend and app servers, which see the same variable, do not write the same
+/// frames again.
+pub fn start() {
+    let Some(path) = env_path(RECORD_ENV) else {
+        return;
+    };
+    match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(file) => {
+            eprintln!("[IPC] Recording to {}", path.display());
+            let _ = RECORDER.set(Recorder {
+                file: Mutex::new(file),
+                started: Instant::now(),
+            });
+        }
+        Err(e) => eprintln!("[IPC] Cannot record to {}: {e}", path.display()),
+    }
+}
+
+pub fn record<T: Serialize + ?Sized>(peer: Peer, direction: Direction, id: u32, message: &T) {
+    if let Some(recorder) = RECORDER.get() {
+        let message = serde_json::to_value(message).unwrap_or(Value::Null);
+        recorder.write(peer, direction, id, message);
+    }
+}
+
+/// `record` for a payload that is already serialized, as proxied responses are.
+pub fn record_payload(peer: Peer, direction: Direction, id: u32, payload: &[u8]) {
+    if let Some(recorder) = RECORDER.get() {
+        let message = serde_json::from_slice(payload)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(payload).into_owned()));
+        recorder.write(peer, direction, id, message);
+    }
+}
+
+impl Recorder {
+    fn write(&self, peer: Peer, direction: Direction, id: u32, message: Value) {
+        let frame = Frame {
+            ms: self.started.elapsed().as_millis() as u64,
+            peer,
+            direction,
+            id,
+            message,
+        };
+        let Ok(mut line) = serde_json::to_vec(&frame) else {
+            return;
+        };
+        line.push(b'\n');
+        // One write per line, so that threads do not interleave and a crash
+        // loses nothing already said.
+        if let Err(e) = self.file.lock().unwrap().write_all(&line) {
+            eprintln!("[IPC] Failed to record a frame: {e}");
+        }
+    }
+}
+
+/// The recording to answer from, when one was asked for.
+pub fn replay_path() -> Option<PathBuf> {
+    env_path(REPLAY_ENV)
+}
+
+/// What the frontend asked in a recording, and what it was told.
+pub struct Replay {
+    exchanges: Vec<Exchange>,
+}
+
+struct Exchange {
+    command: Value,
+    responses: Vec<Value>,
+    replayed: bool,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, SamError> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            eprintln!("[REPLAY] Cannot read {}: {e}", path.display());
+            SamError::UnknownError
+        })?;
+        Self::parse(&text)
+    }
+
+    /// App-server frames are only there to be read.
+    fn parse(text: &str) -> Result<Self, SamError> {
+        let mut exchanges: Vec<Exchange> = Vec::new();
+        // Ids come back once the frontend restarts; a response goes with the
+        // latest request under its id.
+        let mut by_id = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame: Frame = serde_json::from_str(line).map_err(|e| {
+                eprintln!("[REPLAY] Line {} of the recording: {e}", number + 1);
+                SamError::SerializationFailed
+            })?;
+            if frame.peer != Peer::Frontend {
+                continue;
+            }
+            match frame.direction {
+                Direction::Request => {
+                    by_id.insert(frame.id, exchanges.len());
+                    exchanges.push(Exchange {
+                        command: frame.message,
+                        responses: Vec::new(),
+                        replayed: false,
+                    });
+                }
+                Direction::Response => {
+                    if let Some(&index) = by_id.get(&frame.id) {
+                        exchanges[index].responses.push(frame.message);
+                    }
+                }
+            }
+        }
+        Ok(Self { exchanges })
+    }
+
+    /// The answer to the first time `command` was sent that has not been
+    /// replayed yet, or to the last time once all have been.
+    fn answer(&mut self, command: &Value) -> Option<&[Value]> {
+        let index = self
+            .exchanges
+            .iter()
+            .position(|e| !e.replayed && e.command == *command)
+            .or_else(|| self.exchanges.iter().rposition(|e| e.command == *command))?;
+        let exchange = &mut self.exchanges[index];
+        exchange.replayed = true;
+        Some(&exchange.responses)
+    }
+
+    /// Answer the frontend until it sends `Shutdown` or hangs up. Requests run
+    /// one at a time, each answered in full at once, whatever the recorded
+    /// timings.
+    pub fn serve(mut self, out: &mut impl Write, input: &mut impl Read) -> u8 {
+        while let Ok((id, payload)) = read_tagged_frame(input) {
+            let command: Value = serde_json::from_slice(&payload).unwrap_or(Value::Null);
+            dev_println!("REPLAY", "Request {id}: {command}");
+            let shutdown = command == "Shutdown";
+            let written = match self.answer(&command) {
+                Some(responses) => responses
+                    .iter()
+                    .try_for_each(|response| write_tagged(out, id, response)),
+                None if shutdown => write_tagged(out, id, &SteamResponse::Success(true)),
+                None => {
+                    eprintln!("[REPLAY] Not in the recording: {command}");
+                    let error = SamError::UnknownError.context("Not in the recording");
+                    write_tagged(out, id, &SteamResponse::<()>::Error(error))
+                }
+            };
+            if shutdown || written.is_err() {
+                break;
+            }
+        }
+        0
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::utils::ipc_types::{SteamCommand, read_tagged};
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn a_replay_answers_requests_as_recorded_and_repeats_the_last() {
+        let lines = [
+            (
+                Peer::Frontend,
+                Direction::Request,
+                1,
+                json(&SteamCommand::GetCurrentUser),
+            ),
+            (
+                Peer::AppServer(480),
+                Direction::Request,
+                0,
+                json(&SteamCommand::Status),
+            ),
+            (
+                Peer::Frontend,
+                Direction::Response,
+                1,
+                json(&SteamResponse::Success(7u64)),
+            ),
+            (
+                Peer::Frontend,
+                Direction::Request,
+                2,
+                json(&SteamCommand::GetCurrentUser),
+            ),
+            (
+                Peer::Frontend,
+                Direction::Response,
+                2,
+                json(&SteamResponse::Success(8u64)),
+            ),
+        ];
+        let recording: String = lines
+            .into_iter()
+            .map(|(peer, direction, id, message)| {
+                let frame = Frame {
+                    ms: 0,
+                    peer,
+                    direction,
+                    id,
+                    message,
+                };
+                serde_json::to_string(&frame).unwrap() + "\n"
+            })
+            .collect();
+        let replay = Replay::parse(&recording).unwrap();
+
+        let (mut ours, theirs) = UnixStream::pair().unwrap();
+        let mut input = theirs.try_clone().unwrap();
+        let mut out = theirs;
+        let server = std::thread::spawn(move || replay.serve(&mut out, &mut input));
+
+        let mut ask = |id: u32, command: &SteamCommand| {
+            write_tagged(&mut ours, id, command).unwrap();
+            read_tagged::<SteamResponse<u64>>(&mut ours).unwrap()
+        };
+        let user = SteamCommand::GetCurrentUser;
+        assert!(matches!(ask(5, &user), (5, SteamResponse::Success(7))));
+        assert!(matches!(ask(6, &user), (6, SteamResponse::Success(8))));
+        assert!(matches!(ask(7, &user), (7, SteamResponse::Success(8))));
+        let (_, missing) = ask(8, &SteamCommand::GetFriends);
+        assert!(matches!(missing, SteamResponse::Error(e) if *e.kind() == SamError::UnknownError));
+
+        write_tagged(&mut ours, 9, &SteamCommand::Shutdown).unwrap();
+        let shutdown = read_tagged::<SteamResponse<bool>>(&mut ours).unwrap();
+        assert!(matches!(shutdown, (9, SteamResponse::Success(true))));
+        assert_eq!(server.join().unwrap(), 0);
+    }
+
+    fn json<T: Serialize>(message: &T) -> Value {
+        serde_json::to_value(message).unwrap()
+    }
+}
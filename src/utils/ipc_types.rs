@@ -29,6 +29,114 @@ pub enum SamError {
     Timeout,
     ProfilePrivate,
     UnknownError,
+    /// The other process speaks this IPC protocol version, 0 when it does not
+    /// frame messages like any SamRewritten build. Usually a stale process left
+    /// running across an update.
+    ProtocolMismatch(u16),
+    /// A frame announced this many bytes, more than `MAX_FRAME_LEN`.
+    FrameTooLarge(u32),
+    /// One of the variants above, with what is known about where and why it
+    /// happened. Built by the methods below; match on `kind()` to see through it.
+    Detailed(Box<ErrorDetail>),
+}
+
+/// The context a `SamError` picks up on its way to the frontend. Each fact is
+/// the innermost one known: whoever first attaches an app id keeps it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ErrorDetail {
+    /// Never `Detailed` itself.
+    pub kind: SamError,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<u32>,
+    /// The achievement or stat being acted on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+    /// Steam's `EResult` code, when a callback gave one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eresult: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<Process>,
+    /// What was being done, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+}
+
+/// The process an error came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Process {
+    Orchestrator,
+    AppServer,
+}
+
+impl std::fmt::Display for Process {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Process::Orchestrator => write!(f, "orchestrator"),
+            Process::AppServer => write!(f, "app server"),
+        }
+    }
+}
+
+impl SamError {
+    /// The plain variant, whatever context it carries.
+    pub fn kind(&self) -> &SamError {
+        match self {
+            SamError::Detailed(detail) => &detail.kind,
+            kind => kind,
+        }
+    }
+
+    pub fn detail(&self) -> Option<&ErrorDetail> {
+        match self {
+            SamError::Detailed(detail) => Some(detail),
+            _ => None,
+        }
+    }
+
+    fn detail_mut(&mut self) -> &mut ErrorDetail {
+        if !matches!(self, SamError::Detailed(_)) {
+            let kind = std::mem::replace(self, SamError::UnknownError);
+            *self = SamError::Detailed(Box::new(ErrorDetail {
+                kind,
+                app_id: None,
+                item: None,
+                eresult: None,
+                process: None,
+                context: Vec::new(),
+            }));
+        }
+        let SamError::Detailed(detail) = self else {
+            unreachable!()
+        };
+        detail
+    }
+
+    pub fn app(mut self, app_id: u32) -> Self {
+        self.detail_mut().app_id.get_or_insert(app_id);
+        self
+    }
+
+    pub fn item(mut self, item: impl Into<String>) -> Self {
+        self.detail_mut().item.get_or_insert_with(|| item.into());
+        self
+    }
+
+    pub fn eresult(mut self, code: i32) -> Self {
+        self.detail_mut().eresult.get_or_insert(code);
+        self
+    }
+
+    pub fn in_process(mut self, process: Process) -> Self {
+        self.detail_mut().process.get_or_insert(process);
+        self
+    }
+
+    /// Say what was being done when it failed, around what is already said.
+    pub fn context(mut self, what: impl Into<String>) -> Self {
+        self.detail_mut().context.insert(0, what.into());
+        self
+    }
 }
 
 impl std::fmt::Display for SamError {
@@ -48,6 +156,40 @@ impl std::fmt::Display for SamError {
             }
             SamError::Timeout => write!(f, "SAM: Steam is busy, try again with a smaller batch"),
             SamError::ProfilePrivate => write!(f, "SAM: That user's profile is private"),
+            SamError::ProtocolMismatch(0) => write!(
+                f,
+                "SAM: The other process is not a compatible SamRewritten; close every SamRewritten window and start it again"
+            ),
+            SamError::ProtocolMismatch(theirs) => write!(
+                f,
+                "SAM: The other process speaks IPC v{theirs}, this one v{PROTOCOL_VERSION}; close every SamRewritten window and start it again"
+            ),
+            SamError::FrameTooLarge(len) => {
+                write!(f, "SAM: IPC message of {len} bytes is over the limit")
+            }
+            SamError::Detailed(detail) => {
+                for what in &detail.context {
+                    write!(f, "{what}: ")?;
+                }
+                write!(f, "{}", detail.kind)?;
+                let mut facts = Vec::new();
+                if let Some(app_id) = detail.app_id {
+                    facts.push(format!("app {app_id}"));
+                }
+                if let Some(item) = &detail.item {
+                    facts.push(format!("'{item}'"));
+                }
+                if let Some(code) = detail.eresult {
+                    facts.push(format!("EResult {code}"));
+                }
+                if let Some(process) = detail.process {
+                    facts.push(format!("in the {process}"));
+                }
+                if !facts.is_empty() {
+                    write!(f, " ({})", facts.join(", "))?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -92,6 +234,121 @@ pub struct ImportSummary {
     pub skipped_unwriteable: Vec<String>,
     pub errors: Vec<String>,
     pub reset_would_help: bool,
+    /// What Steam unlocked by itself on the import's store.
+    #[serde(default)]
+    pub granted: Vec<GrantedAchievement>,
+}
+
+/// An achievement Steam unlocked by itself when a store went through, because
+/// a stat it is driven by got there. SamRewritten never asked for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GrantedAchievement {
+    pub id: String,
+    pub name: String,
+}
+
+/// A write's answer, with what Steam unlocked by itself when it stored. Always
+/// empty for a write held back for a later store.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Stored<T> {
+    pub result: T,
+    pub granted: Vec<GrantedAchievement>,
+}
+
+/// A bulk command's answer for one app, with the app's progress as the
+/// command found it: what an undo writes back. `None` when the app server
+/// could not read it first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Snapshotted<T> {
+    pub result: T,
+    pub before: Option<AppExport>,
+}
+
+/// One app server the orchestrator keeps, as `GetAppServers` reports it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppServerStatus {
+    pub app_id: u32,
+    /// How many launches hold it.
+    pub holders: usize,
+    /// `None` while it is down: exited, or stopped for want of use, and not
+    /// started again yet.
+    pub pid: Option<u32>,
+    pub uptime_secs: Option<u64>,
+    /// Since its last command.
+    pub idle_secs: u64,
+    /// How often it was started again after exiting on its own.
+    pub restarts: u32,
+    pub last_error: Option<SamError>,
+}
+
+/// What the orchestrator pushes to a frontend that sent `SubscribeEvents`, as it
+/// happens rather than in answer to anything.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum OrchestratorEvent {
+    /// Steam started, as far as can be told without connecting to it.
+    SteamConnected,
+    SteamDisconnected,
+    /// Flatpak Steam started after the orchestrator, which is outside its PID
+    /// namespace and cannot join it anymore. Only a new orchestrator can reach
+    /// Steam; until then this stands in for `SteamConnected`.
+    Stranded,
+    /// Another account signed in to Steam.
+    UserChanged {
+        steam_id64: u64,
+    },
+    /// An app server exited on its own. It is started again, with its holds.
+    AppServerDied {
+        app_id: u32,
+        error: SamError,
+    },
+    /// An app is in-game for its first holder.
+    IdleStarted {
+        app_id: u32,
+    },
+    /// Nothing holds the app anymore.
+    IdleStopped {
+        app_id: u32,
+    },
+    /// Steam stored the app's achievements and stats.
+    StatsStored {
+        app_id: u32,
+    },
+    /// An entry of the schedule ran; `ok` unless some of it failed.
+    ScheduleRan {
+        id: u64,
+        ok: bool,
+    },
+    /// Another window asked this one to open the app's page.
+    OpenApp {
+        app_id: u32,
+    },
+}
+
+/// How a bulk fan-out paces its app servers and retries the apps Steam timed
+/// out on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FanOutPolicy {
+    /// App servers running at once, at most.
+    pub concurrency: usize,
+    /// Further tries for an app after a timeout, each.
+    pub retries: u32,
+    /// Wait before an app's first retry, doubled for each one after it.
+    pub backoff_ms: u64,
+    /// Run fewer app servers at once while Steam times out, and more again as
+    /// it answers.
+    pub adaptive: bool,
+}
+
+impl Default for FanOutPolicy {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retries: 1,
+            backoff_ms: 1000,
+            adaptive: true,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -104,30 +361,49 @@ pub enum SteamCommand {
     StopApp(u32),
     StopApps,
     GetRunningApps,
+    /// Every app server held open, with how it has been doing.
+    GetAppServers,
+    /// Never answered as such: each `OrchestratorEvent` comes as a frame under
+    /// this request's id, starting with how Steam is doing, until the link
+    /// closes. `true` from a window, which `OpenApp` may then go to.
+    SubscribeEvents(bool),
+    /// Have another window attached to the daemon open the app's page. `false`
+    /// when there is none, and always from a private orchestrator.
+    OpenApp(u32),
+    /// Run the schedule's entries as they fall due, for as long as this
+    /// orchestrator lives. A `--daemon` always does.
+    FollowSchedule,
     Shutdown,
     Status, // Ask for status of the process
     /// `(app_id, language)` where `language` is a Steam schema language name
     /// (e.g. `french`). Empty means the game's own language.
     GetAchievements(u32, String),
     GetStats(u32, String),
+    /// `(app_id, unlocked, achievement_id, store)`. This, the stat writes and
+    /// `StoreStatsAndAchievements` answer a `Stored`.
     SetAchievement(u32, bool, String, bool),
-    SetIntStat(u32, String, i32),
-    SetFloatStat(u32, String, f32),
+    /// `(app_id, stat_id, value, store)`. Unless `store` is set, the value waits
+    /// in Steam's client for a `StoreStatsAndAchievements`.
+    SetIntStat(u32, String, i32, bool),
+    SetFloatStat(u32, String, f32, bool),
     ResetStats(u32, bool),
     UnlockAllAchievements(u32),
     StoreStatsAndAchievements(u32),
     ExportAppProgress(u32),
     ImportAppProgress(u32, AppExport),
+    /// Child-only: read the app's progress, cut down to what the command can
+    /// change, then run it. Answered as a `Snapshotted` of its own answer.
+    SnapshotThen(u32, Box<SteamCommand>),
     /// Multi-app fan-out: the orchestrator spawns one child per id, so they
-    /// inherit its namespace. Progress reporting is intentionally not surfaced yet.
-    ExportApps(Vec<u32>),
-    ImportApps(Vec<AppExport>),
-    UnlockAllApps(Vec<u32>),
-    ResetApps(Vec<u32>, bool),
+    /// inherit its namespace, and streams a `ProgressMsg` as each finishes.
+    ExportApps(Vec<u32>, FanOutPolicy),
+    ImportApps(Vec<AppExport>, FanOutPolicy),
+    UnlockAllApps(Vec<u32>, FanOutPolicy),
+    ResetApps(Vec<u32>, bool, FanOutPolicy),
     GetAchievementCounts(Vec<u32>, bool),
     /// Fetch `app_id`'s achievements and stats in a single round-trip, so an
-    /// unrelated batch command can't interleave between the two fetches on the
-    /// serial channel. When `launch` is true the app is launched (or its
+    /// unrelated command can't interleave between the two fetches on the app
+    /// server's pipe. When `launch` is true the app is launched (or its
     /// refcount bumped) first; otherwise it must already be running. `language`
     /// is a Steam schema language name; empty means the game's own language.
     /// Returns `(achievements, stats, languages the schema offers)`.
@@ -150,6 +426,10 @@ pub enum SteamCommand {
     /// `(app_id, steam_id64)`. Counts a user's achieved vs total achievements for
     /// `app_id` — the picker's per-row hint. App-scoped like `GetFriendUnlockTimes`.
     GetFriendAchievementCount(u32, u64),
+    /// Stop the bulk fan-out sent under this request id from starting more
+    /// apps. Those already running finish, and its `Done` lists only the apps
+    /// that were processed. Answers whether such a request was still running.
+    Cancel(u32),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -164,71 +444,167 @@ pub enum SteamResponse<T> {
 /// using `SteamResponse<T>` directly on the wire.
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ProgressMsg<T> {
-    Progress { done: usize, total: usize },
+    /// `app_id` is the app that just finished, after `retries` retries. `ok`
+    /// unless it failed or Steam did not store what it was asked to.
+    Progress {
+        done: usize,
+        total: usize,
+        app_id: u32,
+        retries: u32,
+        ok: bool,
+    },
     Done(T),
 }
 
-/// Serialize a message as a length-prefixed (`usize` little-endian) JSON frame.
-pub fn frame_message<T: Serialize + ?Sized>(msg: &T) -> Vec<u8> {
+/// Every frame starts with this, then the protocol version (`u16`), two reserved
+/// bytes, the payload length (`u32`) and the id of the request the frame belongs
+/// to (`u32`), all little-endian. A fixed width keeps both ends in agreement
+/// whatever their pointer size.
+pub const MAGIC: [u8; 4] = *b"SAMR";
+/// Bump on any change to the header or to what a message means.
+pub const PROTOCOL_VERSION: u16 = 13;
+pub const HEADER_LEN: usize = 16;
+/// A whole library's export stays far below this. A larger length is a broken or
+/// foreign peer, refused before anything is allocated for it.
+pub const MAX_FRAME_LEN: u32 = 128 * 1024 * 1024;
+
+fn header(id: u32, len: usize) -> [u8; HEADER_LEN] {
+    let len = u32::try_from(len).unwrap_or(u32::MAX);
+    let mut header = [0u8; HEADER_LEN];
+    header[..4].copy_from_slice(&MAGIC);
+    header[4..6].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+    header[8..12].copy_from_slice(&len.to_le_bytes());
+    header[12..].copy_from_slice(&id.to_le_bytes());
+    header
+}
+
+/// The version a header was written with, the length of its payload and its
+/// request id.
+fn parse_header(header: &[u8; HEADER_LEN]) -> Result<(u16, u32, u32), SamError> {
+    if header[..4] != MAGIC {
+        return Err(SamError::ProtocolMismatch(0));
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    let len = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    let id = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    if len > MAX_FRAME_LEN {
+        return Err(SamError::FrameTooLarge(len));
+    }
+    Ok((version, len, id))
+}
+
+/// Serialize a message as a JSON frame behind a `HEADER_LEN`-byte header, for
+/// request `id`. Links that carry one request at a time use id 0.
+pub fn frame_tagged<T: Serialize + ?Sized>(id: u32, msg: &T) -> Vec<u8> {
     let serialized = serde_json::to_vec(msg).expect("Serializing IPC message must not fail");
-    let length_bytes = serialized.len().to_le_bytes();
-    let mut frame = Vec::with_capacity(length_bytes.len() + serialized.len());
-    frame.extend_from_slice(&length_bytes);
+    let mut frame = Vec::with_capacity(HEADER_LEN + serialized.len());
+    frame.extend_from_slice(&header(id, serialized.len()));
     frame.extend_from_slice(&serialized);
     frame
 }
 
-/// Frame `msg` and write it to `w`. Used by both ends of the pipe.
-pub fn write_message<T: Serialize + ?Sized>(w: &mut impl Write, msg: &T) -> Result<(), SamError> {
-    let frame = frame_message(msg);
+/// Move a whole frame, as `read_frame_raw` returns it, over to request `id`.
+pub fn retag(frame: &mut [u8], id: u32) {
+    frame[12..HEADER_LEN].copy_from_slice(&id.to_le_bytes());
+}
+
+/// Frame `msg` for request `id` and write it to `w`.
+pub fn write_tagged<T: Serialize + ?Sized>(
+    w: &mut (impl Write + ?Sized),
+    id: u32,
+    msg: &T,
+) -> Result<(), SamError> {
+    let frame = frame_tagged(id, msg);
+    let len = frame.len() - HEADER_LEN;
+    if len > MAX_FRAME_LEN as usize {
+        eprintln!("[IPC] Refusing to write a {len} byte message");
+        return Err(SamError::FrameTooLarge(
+            u32::try_from(len).unwrap_or(u32::MAX),
+        ));
+    }
     w.write_all(&frame).map_err(|e| {
         eprintln!("[IPC] Failed to write framed message: {e}");
         SamError::SocketCommunicationFailed
     })
 }
 
-/// Read a length-prefixed JSON frame and return the JSON payload (no prefix).
-pub fn read_frame(r: &mut impl Read) -> Result<Vec<u8>, SamError> {
-    let mut len_buf = [0u8; size_of::<usize>()];
-    r.read_exact(&mut len_buf).map_err(|e| {
-        eprintln!("[IPC] Failed to read message length: {e}");
+/// Frame `msg` and write it to `w`. Used by both ends of the pipe.
+pub fn write_message<T: Serialize + ?Sized>(
+    w: &mut (impl Write + ?Sized),
+    msg: &T,
+) -> Result<(), SamError> {
+    write_tagged(w, 0, msg)
+}
+
+/// Read a header and its payload, whatever protocol version the header names.
+fn read_versioned_frame(r: &mut impl Read) -> Result<(u16, u32, Vec<u8>), SamError> {
+    let mut header = [0u8; HEADER_LEN];
+    r.read_exact(&mut header).map_err(|e| {
+        // Between frames, that is the other end hanging up.
+        if e.kind() != std::io::ErrorKind::UnexpectedEof {
+            eprintln!("[IPC] Failed to read message header: {e}");
+        }
         SamError::SocketCommunicationFailed
     })?;
-    let data_len = usize::from_le_bytes(len_buf);
-    let mut payload = vec![0u8; data_len];
+    let (version, len, id) = parse_header(&header).inspect_err(|e| eprintln!("[IPC] {e}"))?;
+    let mut payload = vec![0u8; len as usize];
     r.read_exact(&mut payload).map_err(|e| {
         eprintln!("[IPC] Failed to read message payload: {e}");
         SamError::SocketCommunicationFailed
     })?;
-    Ok(payload)
+    Ok((version, id, payload))
 }
 
-/// Read a framed message and deserialize the JSON payload.
-pub fn read_message<T: DeserializeOwned>(r: &mut impl Read) -> Result<T, SamError> {
-    let payload = read_frame(r)?;
-    serde_json::from_slice(&payload).map_err(|e| {
+/// Read a framed message and return its request id and JSON payload.
+pub fn read_tagged_frame(r: &mut impl Read) -> Result<(u32, Vec<u8>), SamError> {
+    let (version, id, payload) = read_versioned_frame(r)?;
+    if version != PROTOCOL_VERSION {
+        let e = SamError::ProtocolMismatch(version);
+        eprintln!("[IPC] {e}");
+        return Err(e);
+    }
+    Ok((id, payload))
+}
+
+/// Read a framed message and return the JSON payload (no header).
+pub fn read_frame(r: &mut impl Read) -> Result<Vec<u8>, SamError> {
+    read_tagged_frame(r).map(|(_, payload)| payload)
+}
+
+/// Deserialize a payload as `read_frame` returns it.
+pub fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, SamError> {
+    serde_json::from_slice(payload).map_err(|e| {
         eprintln!("[IPC] Failed to deserialize message: {e}");
         SamError::SerializationFailed
     })
 }
 
-/// Read a framed message and return its bytes *with the length prefix intact*.
+/// Read a framed message and deserialize the JSON payload.
+pub fn read_message<T: DeserializeOwned>(r: &mut impl Read) -> Result<T, SamError> {
+    decode(&read_frame(r)?)
+}
+
+/// `read_message` for links that multiplex requests: also says which one.
+pub fn read_tagged<T: DeserializeOwned>(r: &mut impl Read) -> Result<(u32, T), SamError> {
+    let (id, payload) = read_tagged_frame(r)?;
+    Ok((id, decode(&payload)?))
+}
+
+/// Read a framed message and return its bytes *with the header intact*.
 /// Used by the orchestrator to proxy a child's response to the parent verbatim.
 pub fn read_frame_raw(r: &mut impl Read) -> Result<Vec<u8>, SamError> {
-    let payload = read_frame(r)?;
-    let mut frame = Vec::with_capacity(size_of::<usize>() + payload.len());
-    frame.extend_from_slice(&payload.len().to_le_bytes());
+    let (id, payload) = read_tagged_frame(r)?;
+    let mut frame = Vec::with_capacity(HEADER_LEN + payload.len());
+    frame.extend_from_slice(&header(id, payload.len()));
     frame.extend_from_slice(&payload);
     Ok(frame)
 }
 
-/// Parse a framed `SteamResponse<T>` payload (length prefix + JSON) into a
-/// `Result<T, SamError>`. The input may be either with or without the
-/// `usize` length prefix.
+/// Parse a `SteamResponse<T>` frame into a `Result<T, SamError>`. The input may
+/// be either with or without its header.
 pub fn parse_response_bytes<T: DeserializeOwned>(framed: &[u8]) -> Result<T, SamError> {
-    let len_size = size_of::<usize>();
-    let json_bytes = if framed.len() >= len_size {
-        &framed[len_size..]
+    let json_bytes = if framed.len() >= HEADER_LEN && framed[..4] == MAGIC {
+        &framed[HEADER_LEN..]
     } else {
         framed
     };
@@ -239,6 +615,60 @@ pub fn parse_response_bytes<T: DeserializeOwned>(framed: &[u8]) -> Result<T, Sam
     response.into()
 }
 
+/// The first frame each way on a new pipe or socket. Its shape must never
+/// change, so that any two builds can still tell each other apart.
+#[derive(Serialize, Deserialize, Debug)]
+struct Hello {
+    protocol: u16,
+    build: String,
+}
+
+impl Hello {
+    fn ours() -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            build: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    }
+}
+
+fn read_hello(r: &mut impl Read) -> Result<Hello, SamError> {
+    let (_, _, payload) = read_versioned_frame(r)?;
+    serde_json::from_slice(&payload).map_err(|e| {
+        eprintln!("[IPC] Failed to read the peer's hello: {e}");
+        SamError::ProtocolMismatch(0)
+    })
+}
+
+fn check_hello(theirs: &Hello) -> Result<(), SamError> {
+    if theirs.protocol == PROTOCOL_VERSION {
+        return Ok(());
+    }
+    eprintln!(
+        "[IPC] This is SamRewritten {} (IPC v{PROTOCOL_VERSION}) but the other process is {} (IPC v{})",
+        env!("CARGO_PKG_VERSION"),
+        theirs.build,
+        theirs.protocol
+    );
+    Err(SamError::ProtocolMismatch(theirs.protocol))
+}
+
+/// The spawning or connecting side: say which protocol we speak and check the
+/// answer, before any command goes out.
+pub fn offer_handshake(w: &mut (impl Write + ?Sized), r: &mut impl Read) -> Result<(), SamError> {
+    write_message(w, &Hello::ours())?;
+    check_hello(&read_hello(r)?)
+}
+
+/// The spawned or accepting side. A peer with a different version still gets
+/// our hello back, so the mismatch is reported at both ends. One that does not
+/// frame messages like us at all gets nothing it could misread.
+pub fn answer_handshake(w: &mut (impl Write + ?Sized), r: &mut impl Read) -> Result<(), SamError> {
+    let theirs = read_hello(r)?;
+    write_message(w, &Hello::ours())?;
+    check_hello(&theirs)
+}
+
 impl<T> From<SteamResponse<T>> for Result<T, SamError> {
     fn from(val: SteamResponse<T>) -> Self {
         match val {
@@ -256,3 +686,97 @@ impl<T> From<Result<T, SamError>> for SteamResponse<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn frames_round_trip_behind_a_fixed_header() {
+        let frame = frame_tagged(0, &SteamCommand::LaunchApp(480));
+        assert_eq!(frame[..4], MAGIC);
+        assert_eq!(frame.len(), HEADER_LEN + 17);
+        let command: SteamCommand = read_message(&mut Cursor::new(&frame)).unwrap();
+        assert_eq!(command, SteamCommand::LaunchApp(480));
+        assert_eq!(read_frame_raw(&mut Cursor::new(&frame)).unwrap(), frame);
+    }
+
+    #[test]
+    fn a_proxied_frame_can_answer_another_request() {
+        let mut frame = frame_tagged(0, &SteamResponse::Success(true));
+        retag(&mut frame, 7);
+        let (id, response): (u32, SteamResponse<bool>) =
+            read_tagged(&mut Cursor::new(&frame)).unwrap();
+        assert_eq!(id, 7);
+        assert!(matches!(response, SteamResponse::Success(true)));
+        assert_eq!(parse_response_bytes::<bool>(&frame), Ok(true));
+    }
+
+    #[test]
+    fn context_survives_the_pipe_and_keeps_the_variant_matchable() {
+        let error = SamError::StatStoreFailed
+            .item("ACH_WIN")
+            .eresult(8)
+            .in_process(Process::AppServer)
+            .app(480)
+            .context("Unlocking ACH_WIN")
+            .app(10);
+        let frame = frame_tagged(0, &SteamResponse::<()>::Error(error.clone()));
+        let received = parse_response_bytes::<()>(&frame).unwrap_err();
+
+        assert_eq!(received, error);
+        assert!(matches!(received.kind(), SamError::StatStoreFailed));
+        assert_eq!(received.detail().and_then(|d| d.app_id), Some(480));
+        assert_eq!(
+            received.to_string(),
+            "Unlocking ACH_WIN: SAM: Stat/ach store failed (app 480, 'ACH_WIN', EResult 8, in the app server)"
+        );
+    }
+
+    #[test]
+    fn foreign_and_oversized_headers_are_refused_before_reading_on() {
+        // The old framing: a native `usize` length.
+        let mut old = 17usize.to_le_bytes().to_vec();
+        old.extend_from_slice(b"{\"LaunchApp\":480}");
+        assert_eq!(
+            read_frame(&mut Cursor::new(&old)),
+            Err(SamError::ProtocolMismatch(0))
+        );
+
+        let mut huge = header(0, 0);
+        huge[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            read_frame(&mut Cursor::new(&huge)),
+            Err(SamError::FrameTooLarge(u32::MAX))
+        );
+
+        let mut newer = frame_tagged(0, &SteamCommand::Status);
+        newer[4..6].copy_from_slice(&(PROTOCOL_VERSION + 1).to_le_bytes());
+        assert_eq!(
+            read_frame(&mut Cursor::new(&newer)),
+            Err(SamError::ProtocolMismatch(PROTOCOL_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn handshake_reports_the_peer_version_at_both_ends() {
+        let theirs = Hello {
+            protocol: PROTOCOL_VERSION + 1,
+            build: "99.0.0".to_owned(),
+        };
+        let mut hello = frame_tagged(0, &theirs);
+        hello[4..6].copy_from_slice(&theirs.protocol.to_le_bytes());
+
+        let mut reply = Vec::new();
+        assert_eq!(
+            answer_handshake(&mut reply, &mut Cursor::new(&hello)),
+            Err(SamError::ProtocolMismatch(PROTOCOL_VERSION + 1))
+        );
+        let ours: Hello = read_message(&mut Cursor::new(&reply)).unwrap();
+        assert_eq!(ours.protocol, PROTOCOL_VERSION);
+
+        let mut sent = Vec::new();
+        assert!(offer_handshake(&mut sent, &mut Cursor::new(&reply)).is_ok());
+    }
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Records kept one JSON file each in a cache folder, next to a lock file that
+//! a process holds while it changes or runs one: the bulk jobs and the
+//! schedule. A record left running whose lock anyone can take was cut short.
+
+use crate::utils::app_paths::get_app_cache_dir;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::cmp::Ordering as Order;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seeded from the clock, with the process id in the low bits: SamRewritten is
+/// `NON_UNIQUE`, and two windows sharing a folder must not mint the same ids.
+static NEXT_ID: LazyLock<AtomicU64> =
+    LazyLock::new(|| AtomicU64::new((now_millis() << 16) | u64::from(std::process::id() as u16)));
+
+pub fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub trait Record: Serialize + DeserializeOwned {
+    fn id(&self) -> u64;
+    /// Whether a process was running it when it was last saved.
+    fn running(&self) -> bool;
+    /// What it reads as once that process is gone.
+    fn interrupt(&mut self);
+    /// Whether `prune` may delete it.
+    fn finished(&self) -> bool;
+}
+
+pub struct Records<T> {
+    folder: &'static str,
+    /// What the log calls the process and a record.
+    tag: &'static str,
+    noun: &'static str,
+    /// Older finished records are deleted as new ones are added.
+    keep_finished: usize,
+    record: PhantomData<fn() -> T>,
+}
+
+impl<T: Record> Records<T> {
+    pub const fn new(
+        folder: &'static str,
+        tag: &'static str,
+        noun: &'static str,
+        keep_finished: usize,
+    ) -> Self {
+        Self {
+            folder,
+            tag,
+            noun,
+            keep_finished,
+            record: PhantomData,
+        }
+    }
+
+    fn folder(&self) -> PathBuf {
+        get_app_cache_dir().join(self.folder)
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.folder().join(format!("{id}.json"))
+    }
+
+    fn lock_path(&self, id: u64) -> PathBuf {
+        self.folder().join(format!("{id}.lock"))
+    }
+
+    /// The record's lock, unless another process holds it.
+    pub fn claim(&self, id: u64) -> Option<File> {
+        let _ = std::fs::create_dir_all(self.folder());
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_path(id))
+            .ok()?;
+        file.try_lock().ok()?;
+        Some(file)
+    }
+
+    /// As it is on disk, running or not.
+    pub fn read(&self, id: u64) -> Option<T> {
+        let bytes = std::fs::read(self.path(id)).ok()?;
+        match serde_json::from_slice::<T>(&bytes) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                eprintln!("{} Skipping unreadable {} {id}: {e}", self.tag, self.noun);
+                None
+            }
+        }
+    }
+
+    /// Running ones nobody holds read as interrupted.
+    fn settled(&self, mut record: T) -> T {
+        if record.running() && self.claim(record.id()).is_some() {
+            record.interrupt();
+        }
+        record
+    }
+
+    pub fn get(&self, id: u64) -> Option<T> {
+        self.read(id).map(|record| self.settled(record))
+    }
+
+    /// Every record on disk, in no particular order.
+    pub fn load(&self) -> Vec<T> {
+        let Ok(files) = std::fs::read_dir(self.folder()) else {
+            return Vec::new();
+        };
+        files
+            .flatten()
+            .filter_map(|file| {
+                let name = file.file_name();
+                let id = name.to_str()?.strip_suffix(".json")?.parse().ok()?;
+                self.read(id)
+            })
+            .map(|record| self.settled(record))
+            .collect()
+    }
+
+    /// Written aside and renamed over, so a crash mid-write leaves the last
+    /// whole copy.
+    pub fn save(&self, record: &T) {
+        let path = self.path(record.id());
+        let temporary = path.with_extension("json.tmp");
+        let written = std::fs::create_dir_all(self.folder())
+            .and_then(|()| serde_json::to_vec(record).map_err(std::io::Error::other))
+            .and_then(|bytes| {
+                let mut file = File::create(&temporary)?;
+                file.write_all(&bytes)?;
+                file.sync_data()
+            })
+            .and_then(|()| std::fs::rename(&temporary, &path));
+        if let Err(e) = written {
+            eprintln!(
+                "{} Could not save {} {}: {e}",
+                self.tag,
+                self.noun,
+                record.id()
+            );
+            let _ = std::fs::remove_file(&temporary);
+        }
+    }
+
+    /// False if a process holds it.
+    pub fn discard(&self, id: u64) -> bool {
+        let Some(lock) = self.claim(id) else {
+            return false;
+        };
+        if let Err(e) = std::fs::remove_file(self.path(id))
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            eprintln!("{} Could not delete {} {id}: {e}", self.tag, self.noun);
+        }
+        let _ = std::fs::remove_file(self.lock_path(id));
+        drop(lock);
+        true
+    }
+
+    /// Deletes the finished records past the first `keep_finished`, in the
+    /// order `newest_first` puts them.
+    pub fn prune(&self, newest_first: impl FnMut(&T, &T) -> Order) {
+        let mut finished: Vec<T> = self.load().into_iter().filter(Record::finished).collect();
+        finished.sort_by(newest_first);
+        for record in finished.into_iter().skip(self.keep_finished) {
+            self.discard(record.id());
+        }
+    }
+}
@@ -13,17 +13,30 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-#[cfg(feature = "gui")]
+// The GUI and the schedule record; the CLI reads it back.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 pub mod action_journal;
 pub mod app_paths;
 pub mod arguments;
 pub mod bidir_child;
+#[cfg(any(feature = "gui", feature = "cli"))]
+pub mod bulk_jobs;
+#[cfg(feature = "gui")]
+pub mod bulk_report;
 pub mod dev_println;
+#[cfg(any(feature = "cli", feature = "gui"))]
 pub mod export_file;
 pub mod format;
 pub mod inherit;
 pub mod ipc_client;
+pub mod ipc_recording;
 pub mod ipc_types;
+// The bulk jobs and the schedule.
+#[cfg_attr(not(any(feature = "gui", feature = "cli")), allow(dead_code))]
+pub mod json_records;
+// The orchestrator runs it; the frontends add to it.
+#[cfg_attr(not(any(feature = "gui", feature = "cli")), allow(dead_code))]
+pub mod schedule;
 #[cfg(all(unix, feature = "gui"))]
 pub mod snap;
 pub mod steam_locator;
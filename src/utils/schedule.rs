@@ -0,0 +1,426 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2026 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Operations queued for a time of day, one JSON file per entry in the cache
+//! folder. Whichever orchestrator follows the schedule runs an entry once it
+//! is due: a `--daemon`, or the one a GUI window started. One that was not
+//! running then runs it late, at its first sweep.
+
+use crate::utils::ipc_types::{
+    AppAchievementExport, AppExport, AppStatExport, AppStatValue, FanOutPolicy, SteamCommand,
+};
+use crate::utils::json_records::{Record, Records, next_id, now_secs};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Older entries that ran or were cancelled are deleted as new ones are added.
+const KEEP_FINISHED: usize = 50;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScheduledStat {
+    pub id: String,
+    pub value: AppStatValue,
+    /// `value` is added to what the stat holds when the entry runs, rather
+    /// than written as it is.
+    #[serde(default)]
+    pub relative: bool,
+}
+
+impl ScheduledStat {
+    /// What to write over `current`, the value the stat holds now.
+    fn target(&self, current: &AppStatValue) -> Result<AppStatValue, String> {
+        if !self.relative {
+            return Ok(self.value.clone());
+        }
+        let sum = match (current, &self.value) {
+            (AppStatValue::Int(current), AppStatValue::Int(change)) => {
+                current.checked_add(*change).map(AppStatValue::Int)
+            }
+            (AppStatValue::Float(current), AppStatValue::Float(change)) => Some(current + change)
+                .filter(|sum| sum.is_finite())
+                .map(AppStatValue::Float),
+            _ => {
+                return Err(format!(
+                    "stat:{} is not the kind it was scheduled as",
+                    self.id
+                ));
+            }
+        };
+        sum.ok_or_else(|| format!("stat:{} would overflow", self.id))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operation {
+    Achievements {
+        app_id: u32,
+        ids: Vec<String>,
+        unlocked: bool,
+    },
+    Stats {
+        app_id: u32,
+        stats: Vec<ScheduledStat>,
+    },
+    /// Progress read from a file when the entry was added.
+    Import {
+        apps: Vec<AppExport>,
+    },
+    StartIdle {
+        app_id: u32,
+    },
+    /// Drops the hold a `StartIdle` took, as `StopApp` does.
+    StopIdle {
+        app_id: u32,
+    },
+}
+
+impl Operation {
+    pub fn app_ids(&self) -> Vec<u32> {
+        match self {
+            Operation::Achievements { app_id, .. }
+            | Operation::Stats { app_id, .. }
+            | Operation::StartIdle { app_id }
+            | Operation::StopIdle { app_id } => vec![*app_id],
+            Operation::Import { apps } => apps.iter().map(|app| app.app_id).collect(),
+        }
+    }
+
+    /// What the journal calls it, as an untranslated message id.
+    pub fn detail(&self) -> &'static str {
+        match self {
+            Operation::Achievements { unlocked: true, .. } => {
+                "achievements unlocked at a scheduled time"
+            }
+            Operation::Achievements {
+                unlocked: false, ..
+            } => "achievements locked at a scheduled time",
+            Operation::Stats { .. } => "stats set at a scheduled time",
+            Operation::Import { .. } => "progress written from a file at a scheduled time",
+            Operation::StartIdle { .. } => "idling started at a scheduled time",
+            Operation::StopIdle { .. } => "idling stopped at a scheduled time",
+        }
+    }
+
+    /// The app to read just before the command is built, for the operations
+    /// that write over how it stands then.
+    pub fn reads_first(&self) -> Option<u32> {
+        match self {
+            Operation::Achievements { app_id, .. } | Operation::Stats { app_id, .. } => {
+                Some(*app_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The command that carries it out. Writes go through an import, so that
+    /// they are checked like one and come back with what they replaced.
+    /// `current` is the app `reads_first` names, as it stands: an item it
+    /// protects fails the entry, as `unlock` and `set-stat` refuse one, and
+    /// relative stat changes add to its values.
+    pub fn command(
+        &self,
+        app_name: &str,
+        current: Option<&AppExport>,
+    ) -> Result<SteamCommand, String> {
+        let export = |app_id: u32, achievements, stats| AppExport {
+            app_id,
+            app_name: app_name.to_string(),
+            achievements,
+            stats,
+        };
+        let unknown = |kind: &str, id: &str| format!("{kind}:{id} is not in the app's schema");
+        let protected = |kind: &str, id: &str| format!("{kind}:{id} is protected");
+        let apps = match self {
+            Operation::StartIdle { app_id } => return Ok(SteamCommand::LaunchApp(*app_id)),
+            Operation::StopIdle { app_id } => return Ok(SteamCommand::StopApp(*app_id)),
+            Operation::Import { apps } => apps.clone(),
+            Operation::Achievements {
+                app_id,
+                ids,
+                unlocked,
+            } => {
+                let achievements = ids
+                    .iter()
+                    .map(|id| {
+                        let permission = current
+                            .and_then(|app| app.achievements.iter().find(|a| &a.id == id))
+                            .ok_or_else(|| unknown("ach", id))?
+                            .permission;
+                        if permission != 0 {
+                            return Err(protected("ach", id));
+                        }
+                        Ok(AppAchievementExport {
+                            id: id.clone(),
+                            is_achieved: *unlocked,
+                            permission,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?;
+                vec![export(*app_id, achievements, Vec::new())]
+            }
+            Operation::Stats { app_id, stats } => {
+                let stats = stats
+                    .iter()
+                    .map(|stat| {
+                        let now = current
+                            .and_then(|app| app.stats.iter().find(|s| s.id == stat.id))
+                            .ok_or_else(|| unknown("stat", &stat.id))?;
+                        if (now.permission & 2) != 0 {
+                            return Err(protected("stat", &stat.id));
+                        }
+                        Ok(AppStatExport {
+                            id: stat.id.clone(),
+                            value: stat.target(&now.value)?,
+                            permission: now.permission,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?;
+                vec![export(*app_id, Vec::new(), stats)]
+            }
+        };
+        Ok(SteamCommand::ImportApps(apps, FanOutPolicy::default()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Running,
+    /// The orchestrator running it died. It is not tried again.
+    Interrupted,
+    Done,
+    /// It ran, and something in it failed; `error` says what.
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub v: u32,
+    pub id: u64,
+    /// Unix seconds it is due at.
+    pub at: u64,
+    pub created_at: u64,
+    #[serde(default)]
+    pub app_name: String,
+    pub operation: Operation,
+    /// Whether the run goes into the action journal, as recording stood when
+    /// the entry was added.
+    #[serde(default)]
+    pub journal: bool,
+    /// The Steam account the journal files the run under; 0 when unknown.
+    #[serde(default)]
+    pub account: u32,
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ran_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Record for Entry {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn running(&self) -> bool {
+        self.status == Status::Running
+    }
+
+    fn interrupt(&mut self) {
+        self.status = Status::Interrupted;
+    }
+
+    fn finished(&self) -> bool {
+        !matches!(self.status, Status::Pending | Status::Running)
+    }
+}
+
+/// An entry's lock is held while it is changed or run, so that two
+/// orchestrators following the schedule never run it twice and a cancel cannot
+/// land mid-run.
+static ENTRIES: Records<Entry> = Records::new("schedule", "[SCHEDULE]", "entry", KEEP_FINISHED);
+
+/// Every entry on disk, soonest due first.
+pub fn load() -> Vec<Entry> {
+    let mut entries = ENTRIES.load();
+    entries.sort_by(|a, b| a.at.cmp(&b.at).then(a.id.cmp(&b.id)));
+    entries
+}
+
+/// Queue `operation` for `at`, in Unix seconds.
+pub fn add(at: u64, app_name: &str, operation: Operation, journal: bool, account: u32) -> Entry {
+    ENTRIES.prune(|a, b| b.at.cmp(&a.at).then(b.id.cmp(&a.id)));
+    let entry = Entry {
+        v: FORMAT_VERSION,
+        id: next_id(),
+        at,
+        created_at: now_secs(),
+        app_name: app_name.to_string(),
+        operation,
+        journal,
+        account,
+        status: Status::Pending,
+        ran_at: None,
+        error: None,
+    };
+    ENTRIES.save(&entry);
+    entry
+}
+
+/// Why an entry cannot be cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CancelError {
+    NotFound,
+    /// An orchestrator is running it right now.
+    Busy,
+    /// It ran, or was cancelled, already.
+    NotPending,
+}
+
+impl std::fmt::Display for CancelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancelError::NotFound => write!(f, "No such scheduled operation"),
+            CancelError::Busy => write!(f, "The scheduled operation is running"),
+            CancelError::NotPending => write!(f, "The scheduled operation is not waiting anymore"),
+        }
+    }
+}
+
+/// Kept on disk as cancelled, so that it still shows in the list.
+pub fn cancel(id: u64) -> Result<Entry, CancelError> {
+    let lock = ENTRIES.claim(id).ok_or(CancelError::Busy)?;
+    let mut entry = ENTRIES.read(id).ok_or(CancelError::NotFound)?;
+    if entry.status != Status::Pending {
+        return Err(CancelError::NotPending);
+    }
+    entry.status = Status::Cancelled;
+    ENTRIES.save(&entry);
+    drop(lock);
+    Ok(entry)
+}
+
+/// An entry this process is running, held until `finish`.
+pub struct Claimed {
+    entry: Entry,
+    _lock: File,
+}
+
+impl Claimed {
+    pub fn entry(&self) -> &Entry {
+        &self.entry
+    }
+
+    /// `errors` empty means every part of it went through.
+    pub fn finish(mut self, errors: Vec<String>) {
+        self.entry.status = if errors.is_empty() {
+            Status::Done
+        } else {
+            Status::Failed
+        };
+        self.entry.error = (!errors.is_empty()).then(|| errors.join("; "));
+        ENTRIES.save(&self.entry);
+    }
+}
+
+/// Every entry due by now that no one else has taken, marked running.
+pub fn claim_due() -> Vec<Claimed> {
+    let now = now_secs();
+    load()
+        .into_iter()
+        .filter(|entry| entry.status == Status::Pending && entry.at <= now)
+        .filter_map(|entry| {
+            let lock = ENTRIES.claim(entry.id)?;
+            // Read again under the lock: another orchestrator may have run it
+            // since the listing, or the user cancelled it.
+            let mut entry = ENTRIES
+                .read(entry.id)
+                .filter(|e| e.status == Status::Pending)?;
+            entry.status = Status::Running;
+            entry.ran_at = Some(now);
+            ENTRIES.save(&entry);
+            Some(Claimed { entry, _lock: lock })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_stat_edit_runs_against_the_app_as_it_stands() {
+        let operation = Operation::Stats {
+            app_id: 480,
+            stats: vec![
+                ScheduledStat {
+                    id: "NumGames".to_string(),
+                    value: AppStatValue::Int(10),
+                    relative: false,
+                },
+                ScheduledStat {
+                    id: "NumWins".to_string(),
+                    value: AppStatValue::Int(-2),
+                    relative: true,
+                },
+                ScheduledStat {
+                    id: "FeetTraveled".to_string(),
+                    value: AppStatValue::Float(1.0),
+                    relative: false,
+                },
+            ],
+        };
+        let line = serde_json::to_string(&operation).unwrap();
+        let operation: Operation = serde_json::from_str(&line).unwrap();
+        assert_eq!(operation.reads_first(), Some(480));
+        let stat = |id: &str, value, permission| AppStatExport {
+            id: id.to_string(),
+            value,
+            permission,
+        };
+        let mut current = AppExport {
+            app_id: 480,
+            app_name: String::new(),
+            achievements: Vec::new(),
+            stats: vec![
+                stat("NumGames", AppStatValue::Int(3), 0),
+                stat("NumWins", AppStatValue::Int(7), 0),
+                stat("FeetTraveled", AppStatValue::Float(0.0), 0),
+            ],
+        };
+        let Ok(SteamCommand::ImportApps(apps, _)) = operation.command("Spacewar", Some(&current))
+        else {
+            panic!("not an import");
+        };
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[0].app_name, "Spacewar");
+        assert!(apps[0].achievements.is_empty());
+        assert_eq!(apps[0].stats[0].value, AppStatValue::Int(10));
+        assert_eq!(apps[0].stats[1].value, AppStatValue::Int(5));
+        assert_eq!(apps[0].stats[2].value, AppStatValue::Float(1.0));
+        assert!(operation.command("Spacewar", None).is_err());
+
+        // Protected by the time it runs: the entry fails rather than skip it.
+        current.stats[2].permission = 2;
+        assert_eq!(
+            operation.command("Spacewar", Some(&current)),
+            Err("stat:FeetTraveled is protected".to_string())
+        );
+    }
+}
@@ -63,6 +63,31 @@ impl SteamLocator {
         path.exists().then_some(path)
     }
 
+    /// `config/loginusers.vdf`: the accounts signed in on this machine, readable
+    /// with Steam closed.
+    #[cfg(target_os = "linux")]
+    pub fn get_login_users_path() -> Option<PathBuf> {
+        Self::get_local_steam_install_root_folders()
+            .into_iter()
+            .map(|root| root.join("config/loginusers.vdf"))
+            .find(|p| p.exists())
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn get_login_users_path() -> Option<PathBuf> {
+        use winreg::RegKey;
+        use winreg::enums::HKEY_CURRENT_USER;
+
+        let subkey = RegKey::predef(HKEY_CURRENT_USER)
+            .open_subkey("SOFTWARE\\Valve\\Steam")
+            .ok()?;
+        let steam_path: String = subkey.get_value("SteamPath").ok()?;
+        let path = PathBuf::from(steam_path)
+            .join("config")
+            .join("loginusers.vdf");
+        path.exists().then_some(path)
+    }
+
     pub fn get_local_app_banner_file_prefix_cached(&self) -> Option<String> {
         self.local_app_banner_file_prefix
             .get_or_init(Self::get_local_app_banner_file_prefix)
@@ -51,6 +51,7 @@ use std::fs;
 use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 use std::os::raw::{c_int, c_long};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 const CLONE_NEWUSER: c_int = 0x1000_0000;
 const CLONE_NEWPID: c_int = 0x2000_0000;
@@ -66,6 +67,11 @@ unsafe extern "C" {
     fn syscall(num: c_long, ...) -> c_long;
 }
 
+/// Set when startup found Flatpak Steam running, whether or not joining its
+/// namespace worked: a new process would fare no better. Cleared once that
+/// Steam quits, as the next one gets a namespace of its own.
+static FOUND_RUNNING: AtomicBool = AtomicBool::new(false);
+
 enum NsOutcome {
     Entered,
     ParentExit(u8),
@@ -78,8 +84,7 @@ enum NsOutcome {
 /// the post-fork parent stub and the caller must exit; `None` means carry on as
 /// the orchestrator.
 pub fn enter_flatpak_steam_ns_if_needed() -> Option<u8> {
-    let lib = SteamLocator::get_steamclient_lib_path(true)?;
-    if !lib.to_string_lossy().contains("com.valvesoftware.Steam") {
+    if !flatpak_steam_selected() {
         return None;
     }
 
@@ -91,6 +96,7 @@ pub fn enter_flatpak_steam_ns_if_needed() -> Option<u8> {
         return None;
     };
 
+    FOUND_RUNNING.store(true, Ordering::Relaxed);
     dev_println!("STEAM NS", "Joining Flatpak Steam namespace via pid {pid}");
     match enter_namespace(pid) {
         NsOutcome::Entered => None,
@@ -105,6 +111,32 @@ pub fn enter_flatpak_steam_ns_if_needed() -> Option<u8> {
     }
 }
 
+/// Flatpak Steam runs now but did not when this orchestrator started, so it is
+/// outside Steam's namespace. Joining takes a process without threads; only a
+/// new orchestrator can.
+pub fn flatpak_steam_started_after_us() -> bool {
+    !FOUND_RUNNING.load(Ordering::Relaxed)
+        && flatpak_steam_selected()
+        && detect_flatpak_steam().is_some()
+}
+
+/// For when Steam looks gone: if the Flatpak Steam found at startup really
+/// quit, one started later is out of reach like any other.
+pub fn recheck_flatpak_steam() {
+    if FOUND_RUNNING.load(Ordering::Relaxed) && detect_flatpak_steam().is_none() {
+        dev_println!(
+            "STEAM NS",
+            "Flatpak Steam quit; a new one needs a new orchestrator"
+        );
+        FOUND_RUNNING.store(false, Ordering::Relaxed);
+    }
+}
+
+fn flatpak_steam_selected() -> bool {
+    SteamLocator::get_steamclient_lib_path(true)
+        .is_some_and(|lib| lib.to_string_lossy().contains("com.valvesoftware.Steam"))
+}
+
 /// Install roots a Steam client is currently running from, read from the
 /// `steamclient.so` each `steam` process has mapped (host-visible for native,
 /// Flatpak and Snap alike).
@@ -185,7 +217,7 @@ pub fn loaded_install_is_running() -> bool {
     any_steam_process_running()
 }
 
-fn any_steam_process_running() -> bool {
+pub fn any_steam_process_running() -> bool {
     let Ok(entries) = fs::read_dir("/proc") else {
         return false;
     };
@@ -210,7 +242,7 @@ fn any_steam_process_running() -> bool {
 
 /// A match is a process named `steam`, in a different PID namespace than us,
 /// whose command line points into the Flatpak Steam install.
-fn detect_flatpak_steam() -> Option<i32> {
+pub fn detect_flatpak_steam() -> Option<i32> {
     let self_pidns = fs::read_link("/proc/self/ns/pid").ok()?;
 
     for entry in fs::read_dir("/proc").ok()?.flatten() {